@@ -38,6 +38,7 @@ pub enum Error {
 }
 
 #[doc(hidden)]
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub struct Void(());
 
 impl fmt::Debug for Void {
@@ -83,6 +84,161 @@ impl StdError for Error {
     }
 }
 
+/// A detailed error from parsing a header, describing which header failed,
+/// where and why.
+///
+/// Returned by `Header::parse_header_detailed`, it converts into
+/// `Error::Header`, so code matching on `Error::Header` continues to work.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct HeaderError {
+    name: Option<String>,
+    value: Option<String>,
+    offset: Option<usize>,
+    kind: HeaderErrorKind,
+}
+
+/// The reason a header failed to parse.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HeaderErrorKind {
+    /// The header is missing.
+    Missing,
+    /// The header has more than one value (field line), but allows one.
+    MultipleValues,
+    /// The header value is empty.
+    Empty,
+    /// The header value is not valid UTF-8.
+    InvalidUtf8,
+    /// The header value has an invalid token, an unterminated quoted-string
+    /// or comment, or a character that is not allowed.
+    InvalidToken,
+    /// The header value has an invalid or out of range number.
+    InvalidNumber,
+    /// The header value has an invalid date.
+    InvalidDate,
+    /// The header value is invalid for another reason.
+    Invalid,
+
+    #[doc(hidden)]
+    __Nonexhaustive(Void)
+}
+
+impl HeaderError {
+    /// The maximum length of the value excerpt, in bytes.
+    pub const MAX_VALUE_LEN: usize = 64;
+
+    /// Create an error of the given kind.
+    pub fn new(kind: HeaderErrorKind) -> HeaderError {
+        HeaderError {
+            name: None,
+            value: None,
+            offset: None,
+            kind,
+        }
+    }
+
+    /// Set the header name.
+    pub fn with_name<S: Into<String>>(mut self, name: S) -> HeaderError {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Set the offending value, and the byte offset of the error within it
+    /// if known. A value longer than `MAX_VALUE_LEN` bytes is replaced by an
+    /// excerpt around the offset, and invalid UTF-8 is replaced.
+    pub fn with_value(mut self, value: &[u8], offset: Option<usize>) -> HeaderError {
+        self.value = Some(excerpt(value, offset.unwrap_or(0)));
+        self.offset = offset;
+        self
+    }
+
+    /// The header name, if known.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_ref().map(|name| &name[..])
+    }
+
+    /// The offending value or an excerpt of it, if any.
+    pub fn value(&self) -> Option<&str> {
+        self.value.as_ref().map(|value| &value[..])
+    }
+
+    /// The byte offset of the error within the value, if known.
+    pub fn offset(&self) -> Option<usize> {
+        self.offset
+    }
+
+    /// The reason for the error.
+    pub fn kind(&self) -> HeaderErrorKind {
+        self.kind
+    }
+}
+
+// An excerpt of at most `MAX_VALUE_LEN` bytes of the value, starting a little
+// before the offset, marking elisions with "...".
+fn excerpt(value: &[u8], offset: usize) -> String {
+    let value = String::from_utf8_lossy(value);
+    if value.len() <= HeaderError::MAX_VALUE_LEN {
+        return value.into_owned();
+    }
+    let mut start = offset.min(value.len()).saturating_sub(16);
+    while !value.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = (start + HeaderError::MAX_VALUE_LEN).min(value.len());
+    while !value.is_char_boundary(end) {
+        end -= 1;
+    }
+    let mut out = String::new();
+    if start > 0 {
+        out.push_str("...");
+    }
+    out.push_str(&value[start..end]);
+    if end < value.len() {
+        out.push_str("...");
+    }
+    out
+}
+
+impl fmt::Display for HeaderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.name {
+            Some(ref name) => write!(f, "invalid {} header: ", name)?,
+            None => f.write_str("invalid header: ")?,
+        }
+        f.write_str(self.kind.description())?;
+        if let Some(offset) = self.offset {
+            write!(f, " at byte {}", offset)?;
+        }
+        if let Some(ref value) = self.value {
+            write!(f, " in {:?}", value)?;
+        }
+        Ok(())
+    }
+}
+
+impl StdError for HeaderError {}
+
+impl HeaderErrorKind {
+    fn description(self) -> &'static str {
+        match self {
+            HeaderErrorKind::Missing => "missing",
+            HeaderErrorKind::MultipleValues => "multiple values",
+            HeaderErrorKind::Empty => "empty value",
+            HeaderErrorKind::InvalidUtf8 => "invalid UTF-8",
+            HeaderErrorKind::InvalidToken => "invalid token",
+            HeaderErrorKind::InvalidNumber => "invalid number",
+            HeaderErrorKind::InvalidDate => "invalid date",
+            HeaderErrorKind::Invalid => "invalid value",
+            HeaderErrorKind::__Nonexhaustive(..) => unreachable!(),
+        }
+    }
+}
+
+impl From<HeaderError> for Error {
+    fn from(_: HeaderError) -> Error {
+        Header
+    }
+}
+
 impl From<Utf8Error> for Error {
     fn from(err: Utf8Error) -> Error {
         Utf8(err)
@@ -99,3 +255,5 @@ impl From<FromUtf8Error> for Error {
 trait AssertSendSync: Send + Sync + 'static {}
 #[doc(hidden)]
 impl AssertSendSync for Error {}
+#[doc(hidden)]
+impl AssertSendSync for HeaderError {}
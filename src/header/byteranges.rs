@@ -0,0 +1,505 @@
+//! Writing and reading of `multipart/byteranges` bodies, as specified in
+//! [RFC 9110](https://www.rfc-editor.org/rfc/rfc9110#section-14.6), for
+//! responses with multiple ranges.
+//!
+//! Each part of the body has a `Content-Range` header field giving its
+//! range of the representation, and usually a `Content-Type` of the
+//! representation.
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate hyperx;
+//! # extern crate mime;
+//! use std::io::Cursor;
+//! use hyperx::header::{ContentRangeSpec, ContentType};
+//! use hyperx::header::byteranges::{ByteRangesReader, ByteRangesWriter};
+//!
+//! # fn main() {
+//! let representation = b"Hello, multipart world!";
+//! let writer = ByteRangesWriter::new(vec![(0, 4), (17, 21)], representation.len() as u64)
+//!     .unwrap()
+//!     .with_content_type(ContentType::plaintext());
+//!
+//! let mut body = Vec::new();
+//! writer.write_from(&mut Cursor::new(&representation[..]), &mut body).unwrap();
+//! assert_eq!(body.len() as u64, writer.content_length());
+//!
+//! let reader = ByteRangesReader::from_content_type(&writer.content_type(), &body[..]).unwrap();
+//! let parts: Vec<_> = reader.collect::<Result<_, _>>().unwrap();
+//! assert_eq!(parts[0].0, ContentRangeSpec::Bytes { range: Some((0, 4)), instance_length: Some(23) });
+//! assert_eq!(parts[0].1, b"Hello");
+//! assert_eq!(parts[1].1, b"world");
+//! # }
+//! ```
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::io::{self, BufRead, Read, Seek, SeekFrom, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use mime;
+
+use header::{ContentRangeSpec, ContentType};
+
+/// Writes a `multipart/byteranges` body for a set of ranges of a
+/// representation.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ByteRangesWriter {
+    ranges: Vec<(u64, u64)>,
+    complete_length: u64,
+    data_length: u64,
+    content_type: Option<ContentType>,
+    boundary: String,
+}
+
+impl ByteRangesWriter {
+    /// Create a writer for the end-inclusive ranges, such as those of
+    /// `Resolution::Multiple`, of a representation of the given length,
+    /// with a generated boundary.
+    ///
+    /// Fails with kind `InvalidInput` unless each range is satisfiable,
+    /// `start <= end < complete_length`, and their total length fits in a
+    /// `u64`.
+    pub fn new(ranges: Vec<(u64, u64)>, complete_length: u64) -> io::Result<ByteRangesWriter> {
+        let mut data_length = 0u64;
+        for &(start, end) in &ranges {
+            data_length = range_length(start, end)
+                .filter(|_| end < complete_length)
+                .and_then(|len| data_length.checked_add(len))
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput,
+                                              "unsatisfiable range"))?;
+        }
+        Ok(ByteRangesWriter {
+            ranges,
+            complete_length,
+            data_length,
+            content_type: None,
+            boundary: generate_boundary(),
+        })
+    }
+
+    /// Set the content type of the representation, written for each part.
+    pub fn with_content_type(mut self, content_type: ContentType) -> ByteRangesWriter {
+        self.content_type = Some(content_type);
+        self
+    }
+
+    /// Set the boundary, replacing the generated one. It must be 1 to 70
+    /// characters allowed by RFC 2046, and must not occur in the content.
+    pub fn with_boundary<S: Into<String>>(mut self, boundary: S) -> ByteRangesWriter {
+        self.boundary = boundary.into();
+        self
+    }
+
+    /// The boundary.
+    pub fn boundary(&self) -> &str {
+        &self.boundary
+    }
+
+    /// The `Content-Type` of the response: `multipart/byteranges` with the
+    /// boundary.
+    pub fn content_type(&self) -> ContentType {
+        let mime = format!("multipart/byteranges; boundary={}", self.boundary);
+        ContentType(mime.parse().expect("valid boundary"))
+    }
+
+    /// The exact `Content-Length` of the body, saturating at `u64::MAX`.
+    pub fn content_length(&self) -> u64 {
+        let heads: usize = (0..self.ranges.len()).map(|i| self.part_head(i).len()).sum();
+        self.data_length
+            .saturating_add(heads as u64)
+            .saturating_add(self.tail().len() as u64)
+    }
+
+    /// The delimiter and header fields preceding the data of the part at
+    /// the given index.
+    pub fn part_head(&self, index: usize) -> String {
+        let (start, end) = self.ranges[index];
+        let mut head = String::new();
+        if index > 0 {
+            head.push_str("\r\n");
+        }
+        head.push_str("--");
+        head.push_str(&self.boundary);
+        head.push_str("\r\n");
+        if let Some(ref content_type) = self.content_type {
+            head.push_str(&format!("Content-Type: {}\r\n", content_type));
+        }
+        let range = ContentRangeSpec::Bytes {
+            range: Some((start, end)),
+            instance_length: Some(self.complete_length),
+        };
+        head.push_str(&format!("Content-Range: {}\r\n\r\n", range));
+        head
+    }
+
+    /// The close delimiter following the data of the last part.
+    pub fn tail(&self) -> String {
+        format!("\r\n--{}--\r\n", self.boundary)
+    }
+
+    /// Write the body, reading the ranges from the representation in
+    /// `source`.
+    pub fn write_from<R, W>(&self, source: &mut R, out: &mut W) -> io::Result<()>
+    where R: Read + Seek, W: Write
+    {
+        for (i, &(start, end)) in self.ranges.iter().enumerate() {
+            out.write_all(self.part_head(i).as_bytes())?;
+            source.seek(SeekFrom::Start(start))?;
+            let len = range_length(start, end)
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "invalid range"))?;
+            if io::copy(&mut source.take(len), out)? != len {
+                return Err(io::Error::new(io::ErrorKind::UnexpectedEof,
+                                          "representation shorter than range"));
+            }
+        }
+        out.write_all(self.tail().as_bytes())
+    }
+}
+
+// The length of an end-inclusive range, or None if it is inverted or
+// overflows.
+fn range_length(start: u64, end: u64) -> Option<u64> {
+    end.checked_sub(start).and_then(|len| len.checked_add(1))
+}
+
+// A boundary of 32 hex digits, from a randomly keyed hasher.
+fn generate_boundary() -> String {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_usize(COUNTER.fetch_add(1, Ordering::Relaxed));
+    let high = hasher.finish();
+    hasher.write_u64(high);
+    format!("{:016x}{:016x}", high, hasher.finish())
+}
+
+const MAX_LINE: usize = 8 * 1024;
+const MAX_PART_HEADERS: usize = 64;
+
+/// The default maximum length of the data of a part read by
+/// `ByteRangesReader`: 16 MiB.
+pub const DEFAULT_MAX_PART_LENGTH: u64 = 16 * 1024 * 1024;
+
+/// Reads the parts of a `multipart/byteranges` body, as
+/// `(ContentRangeSpec, data)` pairs.
+///
+/// The data of each part is read using the length of its `Content-Range`,
+/// so a part without a satisfied byte range is an error, as is a range
+/// beyond its known complete length, or longer than the maximum part
+/// length. Errors have kind `InvalidData` or `UnexpectedEof`, and end the
+/// iteration.
+#[derive(Debug)]
+pub struct ByteRangesReader<R> {
+    source: R,
+    delimiter: String,
+    max_part_length: u64,
+    started: bool,
+    done: bool,
+}
+
+impl<R: BufRead> ByteRangesReader<R> {
+    /// Create a reader for a body with the given boundary.
+    pub fn new<S: Into<String>>(source: R, boundary: S) -> ByteRangesReader<R> {
+        ByteRangesReader {
+            source,
+            delimiter: format!("--{}", boundary.into()),
+            max_part_length: DEFAULT_MAX_PART_LENGTH,
+            started: false,
+            done: false,
+        }
+    }
+
+    /// Set the maximum length of the data of a part, replacing
+    /// `DEFAULT_MAX_PART_LENGTH`.
+    pub fn with_max_part_length(mut self, max: u64) -> ByteRangesReader<R> {
+        self.max_part_length = max;
+        self
+    }
+
+    /// Create a reader for a body with the given `Content-Type`, or return
+    /// `Error::Header` if it isn't `multipart/byteranges` with a boundary.
+    pub fn from_content_type(content_type: &ContentType, source: R)
+        -> ::Result<ByteRangesReader<R>>
+    {
+        let mime = &content_type.0;
+        if mime.type_() != mime::MULTIPART || mime.subtype() != "byteranges" {
+            return Err(::Error::Header);
+        }
+        match mime.get_param(mime::BOUNDARY) {
+            Some(boundary) => Ok(ByteRangesReader::new(source, boundary.as_str())),
+            None => Err(::Error::Header),
+        }
+    }
+
+    // Read a line without its line ending.
+    fn read_line(&mut self) -> io::Result<Vec<u8>> {
+        let mut line = Vec::new();
+        (&mut self.source).take(MAX_LINE as u64 + 1).read_until(b'\n', &mut line)?;
+        if line.last() != Some(&b'\n') {
+            return Err(if line.len() > MAX_LINE {
+                invalid("line too long")
+            } else {
+                io::Error::new(io::ErrorKind::UnexpectedEof, "unexpected end of body")
+            });
+        }
+        line.pop();
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+        Ok(line)
+    }
+
+    // Read a delimiter line, returning true if it is the close delimiter.
+    fn read_delimiter(&mut self, skip_other: bool) -> io::Result<bool> {
+        loop {
+            let line = self.read_line()?;
+            let line = trim_end(&line);
+            if line.starts_with(self.delimiter.as_bytes()) {
+                match &line[self.delimiter.len()..] {
+                    b"" => return Ok(false),
+                    b"--" => return Ok(true),
+                    _ => {}
+                }
+            }
+            if !skip_other {
+                return Err(invalid("expected boundary delimiter"));
+            }
+        }
+    }
+
+    fn read_part(&mut self) -> io::Result<(ContentRangeSpec, Vec<u8>)> {
+        let mut content_range = None;
+        for _ in 0..=MAX_PART_HEADERS {
+            let line = self.read_line()?;
+            if line.is_empty() {
+                let spec: ContentRangeSpec = content_range
+                    .ok_or_else(|| invalid("part without Content-Range"))?;
+                let len = match spec {
+                    ContentRangeSpec::Bytes { range: Some((start, end)), instance_length } => {
+                        if instance_length.map_or(false, |complete| end >= complete) {
+                            return Err(invalid("range beyond complete length"));
+                        }
+                        range_length(start, end).ok_or_else(|| invalid("invalid byte range"))?
+                    }
+                    _ => return Err(invalid("part without byte range")),
+                };
+                if len > self.max_part_length {
+                    return Err(invalid("part too long"));
+                }
+                let mut data = Vec::new();
+                (&mut self.source).take(len).read_to_end(&mut data)?;
+                if (data.len() as u64) < len {
+                    return Err(io::Error::new(io::ErrorKind::UnexpectedEof,
+                                              "unexpected end of part"));
+                }
+                if !self.read_line()?.is_empty() {
+                    return Err(invalid("part longer than Content-Range"));
+                }
+                self.done = self.read_delimiter(false)?;
+                return Ok((spec, data));
+            }
+            let line = std::str::from_utf8(&line).map_err(|_| invalid("invalid part header"))?;
+            let colon = line.find(':').ok_or_else(|| invalid("invalid part header"))?;
+            if line[..colon].trim().eq_ignore_ascii_case("content-range") {
+                content_range = Some(line[colon + 1..].trim().parse()
+                    .map_err(|_| invalid("invalid Content-Range"))?);
+            }
+        }
+        Err(invalid("too many part headers"))
+    }
+}
+
+impl<R: BufRead> Iterator for ByteRangesReader<R> {
+    type Item = io::Result<(ContentRangeSpec, Vec<u8>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        if !self.started {
+            self.started = true;
+            match self.read_delimiter(true) {
+                Ok(false) => {}
+                Ok(true) => {
+                    self.done = true;
+                    return None;
+                }
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+        let part = self.read_part();
+        if part.is_err() {
+            self.done = true;
+        }
+        Some(part)
+    }
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn trim_end(line: &[u8]) -> &[u8] {
+    let end = line.iter().rposition(|b| *b != b' ' && *b != b'\t').map_or(0, |i| i + 1);
+    &line[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Cursor};
+
+    use header::{ContentRangeSpec, ContentType};
+    use super::{ByteRangesReader, ByteRangesWriter};
+
+    fn spec(start: u64, end: u64, length: u64) -> ContentRangeSpec {
+        ContentRangeSpec::Bytes { range: Some((start, end)), instance_length: Some(length) }
+    }
+
+    fn read(body: &[u8]) -> Vec<io::Result<(ContentRangeSpec, Vec<u8>)>> {
+        ByteRangesReader::new(body, "THIS_STRING_SEPARATES").collect()
+    }
+
+    #[test]
+    fn test_write() {
+        let data: Vec<u8> = (0..100u8).collect();
+        let writer = ByteRangesWriter::new(vec![(0, 1), (98, 99)], 100).unwrap()
+            .with_content_type(ContentType::octet_stream())
+            .with_boundary("BOUNDARY");
+        assert_eq!(writer.content_type().to_string(), "multipart/byteranges; boundary=BOUNDARY");
+
+        let mut body = Vec::new();
+        writer.write_from(&mut Cursor::new(&data), &mut body).unwrap();
+        let mut expected = b"--BOUNDARY\r\n\
+            Content-Type: application/octet-stream\r\n\
+            Content-Range: bytes 0-1/100\r\n\r\n".to_vec();
+        expected.extend_from_slice(&[0, 1]);
+        expected.extend_from_slice(b"\r\n--BOUNDARY\r\n\
+            Content-Type: application/octet-stream\r\n\
+            Content-Range: bytes 98-99/100\r\n\r\n");
+        expected.extend_from_slice(&[98, 99]);
+        expected.extend_from_slice(b"\r\n--BOUNDARY--\r\n");
+        assert_eq!(body, expected);
+        assert_eq!(writer.content_length(), body.len() as u64);
+    }
+
+    #[test]
+    fn test_write_invalid_ranges() {
+        for ranges in vec![vec![(2, 1)], vec![(0, 12)], vec![(0, 1), (5, 3)]] {
+            let err = ByteRangesWriter::new(ranges, 12).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        }
+        let max = u64::MAX;
+        assert!(ByteRangesWriter::new(vec![(0, max - 1)], max).is_ok());
+        assert!(ByteRangesWriter::new(vec![(0, max - 1), (0, 0)], max).is_err());
+    }
+
+    #[test]
+    fn test_write_short_source() {
+        let writer = ByteRangesWriter::new(vec![(0, 1), (8, 11)], 12).unwrap();
+        let mut body = Vec::new();
+        let err = writer.write_from(&mut Cursor::new(&b"0123456789"[..]), &mut body).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn test_generated_boundary() {
+        let a = ByteRangesWriter::new(vec![(0, 0)], 1).unwrap();
+        let b = ByteRangesWriter::new(vec![(0, 0)], 1).unwrap();
+        assert_eq!(a.boundary().len(), 32);
+        assert_ne!(a.boundary(), b.boundary());
+    }
+
+    #[test]
+    fn test_read_rfc_example() {
+        // RFC 9110, section 14.6, with a preamble and transport padding
+        let body = b"preamble\r\n\
+            --THIS_STRING_SEPARATES\r\n\
+            Content-Type: application/pdf\r\n\
+            content-range: bytes 500-999/8000\r\n\
+            \r\n\
+            ...the first range...\r\n\
+            --THIS_STRING_SEPARATES \r\n\
+            Content-Type: application/pdf\r\n\
+            Content-Range: bytes 7000-7999/8000\r\n\
+            \r\n\
+            ...the second range\r\n\
+            --THIS_STRING_SEPARATES--\r\n\
+            epilogue";
+        // Shorten the ranges to the example data
+        let body = String::from_utf8_lossy(body)
+            .replace("500-999", "500-520")
+            .replace("7000-7999", "7000-7018");
+        let parts = read(body.as_bytes());
+        assert_eq!(parts.len(), 2);
+        let (ref spec0, ref data0) = *parts[0].as_ref().unwrap();
+        assert_eq!(*spec0, spec(500, 520, 8000));
+        assert_eq!(&data0[..], &b"...the first range..."[..]);
+        let (ref spec1, ref data1) = *parts[1].as_ref().unwrap();
+        assert_eq!(*spec1, spec(7000, 7018, 8000));
+        assert_eq!(&data1[..], &b"...the second range"[..]);
+    }
+
+    #[test]
+    fn test_read_invalid() {
+        let bad: &[&[u8]] = &[
+            b"",
+            b"--THIS_STRING_SEPARATES\r\n\r\nabc\r\n--THIS_STRING_SEPARATES--\r\n",
+            b"--THIS_STRING_SEPARATES\r\nContent-Range: bytes */3\r\n\r\nabc\r\n\
+              --THIS_STRING_SEPARATES--\r\n",
+            b"--THIS_STRING_SEPARATES\r\nContent-Range: bytes 0-2/3\r\n\r\nabcd\r\n\
+              --THIS_STRING_SEPARATES--\r\n",
+            b"--THIS_STRING_SEPARATES\r\nContent-Range: bytes 0-2/3\r\n\r\nab",
+            b"--THIS_STRING_SEPARATES\r\nContent-Range: bytes 0-2/3\r\n\r\nabc\r\n\
+              --OTHER--\r\n",
+            b"--THIS_STRING_SEPARATES\r\nContent-Range: bytes 0-18446744073709551615/*\r\n\r\n",
+            b"--THIS_STRING_SEPARATES\r\nContent-Range: bytes 0-3/3\r\n\r\nabcd\r\n\
+              --THIS_STRING_SEPARATES--\r\n",
+        ];
+        for body in bad {
+            let parts = read(body);
+            assert!(parts.last().map_or(false, |p| p.is_err()), "{:?}", body);
+        }
+        assert!(read(b"--THIS_STRING_SEPARATES--\r\n").is_empty());
+    }
+
+    #[test]
+    fn test_read_max_part_length() {
+        let body = b"--THIS_STRING_SEPARATES\r\nContent-Range: bytes 0-2/*\r\n\r\nabc\r\n\
+                     --THIS_STRING_SEPARATES--\r\n";
+        let reader = ByteRangesReader::new(&body[..], "THIS_STRING_SEPARATES");
+        assert_eq!(reader.map(Result::unwrap).count(), 1);
+        let mut reader = ByteRangesReader::new(&body[..], "THIS_STRING_SEPARATES")
+            .with_max_part_length(2);
+        assert_eq!(reader.next().unwrap().unwrap_err().kind(), io::ErrorKind::InvalidData);
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn test_from_content_type() {
+        assert!(ByteRangesReader::from_content_type(&ContentType::plaintext(), &b""[..]).is_err());
+        let ct = ContentType("multipart/byteranges".parse().unwrap());
+        assert!(ByteRangesReader::from_content_type(&ct, &b""[..]).is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let data: Vec<u8> = (0..=255u8).cycle().take(10_000).collect();
+        let ranges = vec![(0, 0), (100, 4099), (9000, 9999)];
+        let writer = ByteRangesWriter::new(ranges.clone(), 10_000).unwrap();
+        let mut body = Vec::new();
+        writer.write_from(&mut Cursor::new(&data), &mut body).unwrap();
+
+        let reader = ByteRangesReader::from_content_type(&writer.content_type(), &body[..])
+            .unwrap();
+        let parts: Vec<_> = reader.map(Result::unwrap).collect();
+        assert_eq!(parts.len(), 3);
+        for (&(start, end), (spec_, part)) in ranges.iter().zip(parts) {
+            assert_eq!(spec_, spec(start, end, 10_000));
+            assert_eq!(&part[..], &data[start as usize..=end as usize]);
+        }
+    }
+}
@@ -0,0 +1,707 @@
+//! Decoding and encoding of the chunked transfer coding, as specified in
+//! [RFC 9112](https://www.rfc-editor.org/rfc/rfc9112#section-7.1), with
+//! chunk extensions and a trailer section.
+//!
+//! `ChunkedDecoder` and `ChunkedEncoder` do no I/O: the decoder consumes
+//! bytes from a `BytesMut` buffer as they are received, and the encoder
+//! appends to one.
+//!
+//! Disagreement between two recipients on where a chunked body ends allows
+//! request smuggling, so the decoder is strict. A chunk size must be only
+//! hexadecimal digits, without sign, prefix or whitespace, and must not
+//! overflow. Every line must end with CRLF, and chunk data must be followed
+//! by CRLF. Obsolete line folding is rejected in trailer fields. After an
+//! error, the decoder fails all further input.
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate bytes;
+//! # extern crate http;
+//! # extern crate hyperx;
+//! use bytes::BytesMut;
+//! use hyperx::header::chunked::{ChunkedDecoder, ChunkedEncoder, Decoded};
+//!
+//! # fn main() {
+//! let mut trailers = http::HeaderMap::new();
+//! trailers.insert("grpc-status", "0".parse().unwrap());
+//!
+//! let mut buf = BytesMut::new();
+//! let mut encoder = ChunkedEncoder::new();
+//! encoder.encode_chunk(b"Hello", &mut buf);
+//! encoder.encode_last(&trailers, &mut buf);
+//! assert_eq!(&buf[..], &b"5\r\nHello\r\n0\r\ngrpc-status: 0\r\n\r\n"[..]);
+//!
+//! let mut decoder = ChunkedDecoder::new();
+//! let mut body = Vec::new();
+//! while let Some(decoded) = decoder.decode(&mut buf).unwrap() {
+//!     match decoded {
+//!         Decoded::Chunk(_) => {}
+//!         Decoded::Data(data) => body.extend_from_slice(&data),
+//!         Decoded::Trailers(t) => assert_eq!(t["grpc-status"], "0"),
+//!     }
+//! }
+//! assert!(decoder.is_done());
+//! assert_eq!(body, b"Hello");
+//! # }
+//! ```
+
+use std::error::Error as StdError;
+use std::fmt;
+use std::io;
+use std::mem;
+use std::str;
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use http::header::{HeaderMap, HeaderName, HeaderValue};
+
+use header::parsing::{is_token, parse_parameters};
+
+/// The default maximum length of a chunk size line, including extensions.
+pub const DEFAULT_MAX_LINE_LEN: usize = 4096;
+
+/// The default maximum size of the trailer section.
+pub const DEFAULT_MAX_TRAILER_SIZE: usize = 16 * 1024;
+
+/// The size and extensions of a chunk.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ChunkHead {
+    /// The size of the chunk data, zero for the last chunk.
+    pub size: u64,
+    /// The chunk extensions, as names and optional unquoted values.
+    pub extensions: Vec<(String, Option<String>)>,
+}
+
+/// An item decoded from a chunked body.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Decoded {
+    /// The start of a chunk, including the last (zero size) chunk.
+    Chunk(ChunkHead),
+    /// Chunk data, possibly only part of a chunk.
+    Data(Bytes),
+    /// The trailer fields, possibly empty, ending the body.
+    Trailers(HeaderMap),
+}
+
+/// An error decoding a chunked body.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ChunkedError {
+    /// A chunk size which is empty, not hexadecimal, or overflows.
+    InvalidChunkSize,
+    /// A chunk size larger than the configured maximum.
+    ChunkTooLarge,
+    /// A malformed chunk extension.
+    InvalidExtension,
+    /// A chunk size line longer than the configured maximum.
+    LineTooLong,
+    /// A line not ending with CRLF, or chunk data not followed by CRLF.
+    InvalidLineEnding,
+    /// A malformed trailer field line.
+    InvalidTrailer,
+    /// A trailer section larger than the configured maximum.
+    TrailersTooLarge,
+    /// The input ended before the end of the chunked body.
+    Incomplete,
+}
+
+impl fmt::Display for ChunkedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            ChunkedError::InvalidChunkSize => "invalid chunk size",
+            ChunkedError::ChunkTooLarge => "chunk too large",
+            ChunkedError::InvalidExtension => "invalid chunk extension",
+            ChunkedError::LineTooLong => "chunk size line too long",
+            ChunkedError::InvalidLineEnding => "invalid line ending in chunked body",
+            ChunkedError::InvalidTrailer => "invalid trailer field",
+            ChunkedError::TrailersTooLarge => "trailer section too large",
+            ChunkedError::Incomplete => "incomplete chunked body",
+        })
+    }
+}
+
+impl StdError for ChunkedError {}
+
+impl From<ChunkedError> for io::Error {
+    fn from(err: ChunkedError) -> io::Error {
+        let kind = match err {
+            ChunkedError::Incomplete => io::ErrorKind::UnexpectedEof,
+            _ => io::ErrorKind::InvalidData,
+        };
+        io::Error::new(kind, err)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum State {
+    Size,
+    Data(u64),
+    DataEnd,
+    Trailers,
+    Done,
+    Failed(ChunkedError),
+}
+
+/// Decodes a chunked body incrementally, from a buffer of received bytes.
+#[derive(Clone, Debug)]
+pub struct ChunkedDecoder {
+    state: State,
+    max_chunk_size: u64,
+    max_line_len: usize,
+    max_trailer_size: usize,
+    trailer_size: usize,
+    trailers: HeaderMap,
+}
+
+impl Default for ChunkedDecoder {
+    fn default() -> ChunkedDecoder {
+        ChunkedDecoder::new()
+    }
+}
+
+impl ChunkedDecoder {
+    /// Create a decoder with the default limits, and no limit on the size
+    /// of a chunk.
+    pub fn new() -> ChunkedDecoder {
+        ChunkedDecoder {
+            state: State::Size,
+            max_chunk_size: u64::MAX,
+            max_line_len: DEFAULT_MAX_LINE_LEN,
+            max_trailer_size: DEFAULT_MAX_TRAILER_SIZE,
+            trailer_size: 0,
+            trailers: HeaderMap::new(),
+        }
+    }
+
+    /// Set the maximum size of a chunk.
+    pub fn with_max_chunk_size(mut self, size: u64) -> ChunkedDecoder {
+        self.max_chunk_size = size;
+        self
+    }
+
+    /// Set the maximum length of a chunk size line, including extensions
+    /// and the CRLF.
+    pub fn with_max_line_len(mut self, len: usize) -> ChunkedDecoder {
+        self.max_line_len = len;
+        self
+    }
+
+    /// Set the maximum size of the trailer section, including the CRLF of
+    /// each line and the final empty line.
+    pub fn with_max_trailer_size(mut self, size: usize) -> ChunkedDecoder {
+        self.max_trailer_size = size;
+        self
+    }
+
+    /// Return true once the end of the body has been decoded.
+    pub fn is_done(&self) -> bool {
+        self.state == State::Done
+    }
+
+    /// Return an error if the body is not complete, when the input has
+    /// ended.
+    pub fn finish(&self) -> Result<(), ChunkedError> {
+        match self.state {
+            State::Done => Ok(()),
+            State::Failed(err) => Err(err),
+            _ => Err(ChunkedError::Incomplete),
+        }
+    }
+
+    /// Decode the next item from the start of the buffer, consuming its
+    /// bytes.
+    ///
+    /// Returns `None` when more input is needed, or once done. Bytes
+    /// following the end of the body, such as a pipelined message, are left
+    /// in the buffer.
+    pub fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Decoded>, ChunkedError> {
+        let result = self.decode_inner(src);
+        if let Err(err) = result {
+            self.state = State::Failed(err);
+        }
+        result
+    }
+
+    fn decode_inner(&mut self, src: &mut BytesMut) -> Result<Option<Decoded>, ChunkedError> {
+        loop {
+            match self.state {
+                State::Size => {
+                    let line = match take_line(src, self.max_line_len, ChunkedError::LineTooLong)? {
+                        Some(line) => line,
+                        None => return Ok(None),
+                    };
+                    let head = parse_chunk_head(&line)?;
+                    if head.size > self.max_chunk_size {
+                        return Err(ChunkedError::ChunkTooLarge);
+                    }
+                    self.state = if head.size == 0 {
+                        State::Trailers
+                    } else {
+                        State::Data(head.size)
+                    };
+                    return Ok(Some(Decoded::Chunk(head)));
+                }
+                State::Data(remaining) => {
+                    if src.is_empty() {
+                        return Ok(None);
+                    }
+                    let len = if remaining < src.len() as u64 {
+                        remaining as usize
+                    } else {
+                        src.len()
+                    };
+                    self.state = if remaining == len as u64 {
+                        State::DataEnd
+                    } else {
+                        State::Data(remaining - len as u64)
+                    };
+                    return Ok(Some(Decoded::Data(src.split_to(len).freeze())));
+                }
+                State::DataEnd => {
+                    if !b"\r\n".starts_with(&src[..src.len().min(2)]) {
+                        return Err(ChunkedError::InvalidLineEnding);
+                    }
+                    if src.len() < 2 {
+                        return Ok(None);
+                    }
+                    src.advance(2);
+                    self.state = State::Size;
+                }
+                State::Trailers => {
+                    let limit = self.max_trailer_size - self.trailer_size;
+                    let line = match take_line(src, limit, ChunkedError::TrailersTooLarge)? {
+                        Some(line) => line,
+                        None => return Ok(None),
+                    };
+                    self.trailer_size += line.len() + 2;
+                    if line.is_empty() {
+                        self.state = State::Done;
+                        let trailers = mem::replace(&mut self.trailers, HeaderMap::new());
+                        return Ok(Some(Decoded::Trailers(trailers)));
+                    }
+                    let (name, value) = parse_field(&line)?;
+                    self.trailers.append(name, value);
+                }
+                State::Done => return Ok(None),
+                State::Failed(err) => return Err(err),
+            }
+        }
+    }
+}
+
+// Take a line ending with CRLF from the buffer, without the CRLF, if the
+// buffer has one within `limit` bytes.
+fn take_line(src: &mut BytesMut, limit: usize, too_long: ChunkedError)
+    -> Result<Option<BytesMut>, ChunkedError>
+{
+    let end = match src.iter().position(|&b| b == b'\n') {
+        Some(end) => end,
+        None if src.len() >= limit => return Err(too_long),
+        None => return Ok(None),
+    };
+    if end >= limit {
+        return Err(too_long);
+    }
+    if end == 0 || src[end - 1] != b'\r' || src[..end - 1].contains(&b'\r') {
+        return Err(ChunkedError::InvalidLineEnding);
+    }
+    let mut line = src.split_to(end + 1);
+    line.truncate(end - 1);
+    Ok(Some(line))
+}
+
+fn is_ctl(b: u8) -> bool {
+    (b < 0x20 && b != b'\t') || b == 0x7f
+}
+
+fn parse_chunk_head(line: &[u8]) -> Result<ChunkHead, ChunkedError> {
+    let digits = line.iter().take_while(|b| b.is_ascii_hexdigit()).count();
+    if digits == 0 {
+        return Err(ChunkedError::InvalidChunkSize);
+    }
+    let mut size: u64 = 0;
+    for &b in &line[..digits] {
+        let digit = (b as char).to_digit(16).unwrap() as u64;
+        size = size.checked_mul(16)
+            .and_then(|s| s.checked_add(digit))
+            .ok_or(ChunkedError::InvalidChunkSize)?;
+    }
+
+    let rest = &line[digits..];
+    let ext_start = rest.iter().position(|&b| b != b' ' && b != b'\t');
+    let extensions = match ext_start {
+        None if rest.is_empty() => Vec::new(),
+        Some(i) if rest[i] == b';' => {
+            if rest.iter().any(|&b| is_ctl(b)) {
+                return Err(ChunkedError::InvalidExtension);
+            }
+            str::from_utf8(rest).ok()
+                .and_then(|s| parse_parameters(s).ok())
+                .ok_or(ChunkedError::InvalidExtension)?
+        }
+        _ => return Err(ChunkedError::InvalidChunkSize),
+    };
+    Ok(ChunkHead { size, extensions })
+}
+
+fn parse_field(line: &[u8]) -> Result<(HeaderName, HeaderValue), ChunkedError> {
+    let colon = match line.iter().position(|&b| b == b':') {
+        Some(colon) => colon,
+        None => return Err(ChunkedError::InvalidTrailer),
+    };
+    // An invalid name includes one with leading whitespace (obsolete line
+    // folding) or whitespace before the colon.
+    let name = HeaderName::from_bytes(&line[..colon])
+        .map_err(|_| ChunkedError::InvalidTrailer)?;
+    let mut value = &line[colon + 1..];
+    while let Some((&b, rest)) = value.split_first() {
+        if b != b' ' && b != b'\t' { break; }
+        value = rest;
+    }
+    while let Some((&b, rest)) = value.split_last() {
+        if b != b' ' && b != b'\t' { break; }
+        value = rest;
+    }
+    let value = HeaderValue::from_bytes(value).map_err(|_| ChunkedError::InvalidTrailer)?;
+    Ok((name, value))
+}
+
+/// Encodes a chunked body, appending to a buffer.
+#[derive(Clone, Debug, Default)]
+pub struct ChunkedEncoder {
+    finished: bool,
+}
+
+impl ChunkedEncoder {
+    /// Create an encoder.
+    pub fn new() -> ChunkedEncoder {
+        ChunkedEncoder::default()
+    }
+
+    /// Return true once the last chunk has been encoded.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Encode the data as a chunk. Empty data is skipped, since a zero
+    /// size chunk would end the body.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the last chunk has been encoded.
+    pub fn encode_chunk(&mut self, data: &[u8], dst: &mut BytesMut) {
+        self.encode_chunk_with_extensions(data, &[], dst)
+            .expect("no extensions to be invalid");
+    }
+
+    /// Encode the data as a chunk, with chunk extensions. Values which are
+    /// not tokens are quoted. Empty data is skipped.
+    ///
+    /// Returns `InvalidExtension`, without encoding, if a name is not a
+    /// token or a value has control characters.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the last chunk has been encoded.
+    pub fn encode_chunk_with_extensions(
+        &mut self,
+        data: &[u8],
+        extensions: &[(String, Option<String>)],
+        dst: &mut BytesMut)
+        -> Result<(), ChunkedError>
+    {
+        assert!(!self.finished, "chunked body already finished");
+        check_extensions(extensions)?;
+        if data.is_empty() {
+            return Ok(());
+        }
+        write_chunk_head(data.len() as u64, extensions, dst);
+        dst.reserve(data.len() + 2);
+        dst.put_slice(data);
+        dst.put_slice(b"\r\n");
+        Ok(())
+    }
+
+    /// Encode the last chunk and the trailer fields, ending the body.
+    ///
+    /// The trailer fields are not checked; see `header::trailers`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the last chunk has been encoded.
+    pub fn encode_last(&mut self, trailers: &HeaderMap, dst: &mut BytesMut) {
+        assert!(!self.finished, "chunked body already finished");
+        self.finished = true;
+        dst.put_slice(b"0\r\n");
+        for (name, value) in trailers {
+            dst.reserve(name.as_str().len() + value.len() + 4);
+            dst.put_slice(name.as_str().as_bytes());
+            dst.put_slice(b": ");
+            dst.put_slice(value.as_bytes());
+            dst.put_slice(b"\r\n");
+        }
+        dst.put_slice(b"\r\n");
+    }
+}
+
+fn check_extensions(extensions: &[(String, Option<String>)]) -> Result<(), ChunkedError> {
+    for (name, value) in extensions {
+        if !is_token(name) {
+            return Err(ChunkedError::InvalidExtension);
+        }
+        if let Some(value) = value {
+            if value.bytes().any(is_ctl) {
+                return Err(ChunkedError::InvalidExtension);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_chunk_head(size: u64, extensions: &[(String, Option<String>)], dst: &mut BytesMut) {
+    let mut head = format!("{:x}", size);
+    for (name, value) in extensions {
+        head.push(';');
+        head.push_str(name);
+        if let Some(value) = value {
+            head.push('=');
+            if is_token(value) {
+                head.push_str(value);
+            } else {
+                head.push('"');
+                for c in value.chars() {
+                    if c == '"' || c == '\\' {
+                        head.push('\\');
+                    }
+                    head.push(c);
+                }
+                head.push('"');
+            }
+        }
+    }
+    head.push_str("\r\n");
+    dst.extend_from_slice(head.as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::BytesMut;
+    use http::header::HeaderMap;
+
+    use super::*;
+
+    type DecodedAll = (Vec<u8>, Vec<ChunkHead>, Option<HeaderMap>);
+
+    // Decode all of the input, returning the data, chunk heads and trailers.
+    fn decode_all(decoder: &mut ChunkedDecoder, input: &[u8])
+        -> Result<DecodedAll, ChunkedError>
+    {
+        let mut buf = BytesMut::from(input);
+        let mut data = Vec::new();
+        let mut heads = Vec::new();
+        let mut trailers = None;
+        while let Some(decoded) = decoder.decode(&mut buf)? {
+            match decoded {
+                Decoded::Chunk(head) => heads.push(head),
+                Decoded::Data(d) => data.extend_from_slice(&d),
+                Decoded::Trailers(t) => trailers = Some(t),
+            }
+        }
+        Ok((data, heads, trailers))
+    }
+
+    fn decode_err(input: &[u8]) -> ChunkedError {
+        let mut decoder = ChunkedDecoder::new();
+        let err = decode_all(&mut decoder, input).unwrap_err();
+        assert_eq!(decoder.finish(), Err(err));
+        err
+    }
+
+    #[test]
+    fn test_decode() {
+        let mut decoder = ChunkedDecoder::new();
+        let (data, heads, trailers) = decode_all(
+            &mut decoder,
+            b"5;a=1;b=\"x y\"\r\nHello\r\n9 \t; c\r\n, world!!\r\n0\r\n\
+              Grpc-Status:  0 \r\ngrpc-message:\r\n\r\n").unwrap();
+        assert_eq!(data, b"Hello, world!!");
+        assert_eq!(heads[0].size, 5);
+        assert_eq!(heads[0].extensions, vec![
+            ("a".to_owned(), Some("1".to_owned())),
+            ("b".to_owned(), Some("x y".to_owned())),
+        ]);
+        assert_eq!(heads[1], ChunkHead { size: 9, extensions: vec![("c".to_owned(), None)] });
+        assert_eq!(heads[2].size, 0);
+        let trailers = trailers.unwrap();
+        assert_eq!(trailers["grpc-status"], "0");
+        assert_eq!(trailers["grpc-message"], "");
+        assert!(decoder.is_done());
+        assert_eq!(decoder.finish(), Ok(()));
+    }
+
+    #[test]
+    fn test_decode_incremental() {
+        let input = b"3\r\nabc\r\n10\r\n0123456789abcdef\r\n0\r\nx: y\r\n\r\n";
+        let mut decoder = ChunkedDecoder::new();
+        let mut buf = BytesMut::new();
+        let mut data = Vec::new();
+        let mut trailers = None;
+        for &b in &input[..] {
+            buf.extend_from_slice(&[b]);
+            while let Some(decoded) = decoder.decode(&mut buf).unwrap() {
+                match decoded {
+                    Decoded::Chunk(_) => {}
+                    Decoded::Data(d) => data.extend_from_slice(&d),
+                    Decoded::Trailers(t) => trailers = Some(t),
+                }
+            }
+            if decoder.is_done() {
+                break;
+            }
+            assert_eq!(decoder.finish(), Err(ChunkedError::Incomplete));
+        }
+        assert_eq!(data, b"abc0123456789abcdef");
+        assert_eq!(trailers.unwrap()["x"], "y");
+
+        // The pipelined message is left in the buffer.
+        buf.extend_from_slice(b"GET /");
+        assert_eq!(decoder.decode(&mut buf), Ok(None));
+        assert_eq!(&buf[..], b"GET /");
+    }
+
+    #[test]
+    fn test_decode_invalid_size() {
+        for input in &[
+            &b"\r\n"[..],
+            b"+5\r\nHello\r\n0\r\n\r\n",
+            b"-5\r\n",
+            b"0x5\r\nHello\r\n0\r\n\r\n",
+            b" 5\r\nHello\r\n0\r\n\r\n",
+            b"5 \r\nHello\r\n0\r\n\r\n",
+            b"5 a\r\n",
+            b"g\r\n",
+            b"10000000000000000\r\n",
+            b"5,5\r\n",
+        ] {
+            assert_eq!(decode_err(input), ChunkedError::InvalidChunkSize, "{:?}", input);
+        }
+
+        // Leading zeros are allowed.
+        let mut decoder = ChunkedDecoder::new();
+        let (data, _, _) = decode_all(&mut decoder, b"0000000000000000001\r\na\r\n0\r\n\r\n")
+            .unwrap();
+        assert_eq!(data, b"a");
+    }
+
+    #[test]
+    fn test_decode_invalid_line_ending() {
+        for input in &[
+            &b"5\nHello\r\n0\r\n\r\n"[..],
+            b"5\r\nHello\n0\r\n\r\n",
+            b"5\r\nHelloX\r\n0\r\n\r\n",
+            b"5\rX\r\nHello\r\n",
+            b"5\r\nHello\r\n0\r\n\n",
+            b"5\r\nHello\r\n0\r\nx: y\n\r\n",
+        ] {
+            assert_eq!(decode_err(input), ChunkedError::InvalidLineEnding, "{:?}", input);
+        }
+
+        // Fail early on the first byte after chunk data.
+        let mut decoder = ChunkedDecoder::new();
+        let mut buf = BytesMut::from(&b"1\r\naX"[..]);
+        decoder.decode(&mut buf).unwrap();
+        decoder.decode(&mut buf).unwrap();
+        assert_eq!(decoder.decode(&mut buf), Err(ChunkedError::InvalidLineEnding));
+        // ...and stay failed.
+        let mut buf = BytesMut::from(&b"\r\n0\r\n\r\n"[..]);
+        assert_eq!(decoder.decode(&mut buf), Err(ChunkedError::InvalidLineEnding));
+    }
+
+    #[test]
+    fn test_decode_invalid_extension() {
+        for input in &[
+            &b"5;a=\r\n"[..],
+            b"5;a=b=c\r\n",
+            b"5;a=\"b\r\n",
+            b"5;a=\x01\r\n",
+            b"5;a=\"\x00\"\r\n",
+            b"5;\xff\r\n",
+        ] {
+            assert_eq!(decode_err(input), ChunkedError::InvalidExtension, "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_decode_invalid_trailer() {
+        for input in &[
+            &b"0\r\nx: y\r\n folded\r\n\r\n"[..],
+            b"0\r\nx : y\r\n\r\n",
+            b"0\r\n: y\r\n\r\n",
+            b"0\r\nno colon\r\n\r\n",
+            b"0\r\nx: \x01\r\n\r\n",
+        ] {
+            assert_eq!(decode_err(input), ChunkedError::InvalidTrailer, "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_decode_limits() {
+        let mut decoder = ChunkedDecoder::new().with_max_chunk_size(4);
+        assert_eq!(decode_all(&mut decoder, b"4\r\nabcd\r\n0\r\n\r\n").unwrap().0, b"abcd");
+        let mut decoder = ChunkedDecoder::new().with_max_chunk_size(4);
+        assert_eq!(decode_all(&mut decoder, b"5\r\n"), Err(ChunkedError::ChunkTooLarge));
+
+        let mut decoder = ChunkedDecoder::new().with_max_line_len(8);
+        assert!(decode_all(&mut decoder, b"1;a=12\r\na\r\n").is_ok());
+        let mut decoder = ChunkedDecoder::new().with_max_line_len(8);
+        assert_eq!(decode_all(&mut decoder, b"1;a=123\r\n"), Err(ChunkedError::LineTooLong));
+        let mut decoder = ChunkedDecoder::new().with_max_line_len(8);
+        assert_eq!(decode_all(&mut decoder, b"1;a=1234"), Err(ChunkedError::LineTooLong));
+
+        let mut decoder = ChunkedDecoder::new().with_max_trailer_size(8);
+        assert!(decode_all(&mut decoder, b"0\r\na: b\r\n\r\n").unwrap().2.is_some());
+        let mut decoder = ChunkedDecoder::new().with_max_trailer_size(8);
+        assert_eq!(
+            decode_all(&mut decoder, b"0\r\na: bc\r\n\r\n"),
+            Err(ChunkedError::TrailersTooLarge));
+    }
+
+    #[test]
+    fn test_encode() {
+        let mut encoder = ChunkedEncoder::new();
+        let mut buf = BytesMut::new();
+        encoder.encode_chunk(b"", &mut buf);
+        encoder.encode_chunk(&[b'x'; 26], &mut buf);
+        encoder.encode_chunk_with_extensions(
+            b"abc",
+            &[("a".to_owned(), Some("1".to_owned())),
+              ("b".to_owned(), Some("x \"y\"".to_owned())),
+              ("c".to_owned(), None)],
+            &mut buf).unwrap();
+        assert_eq!(
+            encoder.encode_chunk_with_extensions(
+                b"abc", &[("a b".to_owned(), None)], &mut buf),
+            Err(ChunkedError::InvalidExtension));
+        assert!(!encoder.is_finished());
+        encoder.encode_last(&HeaderMap::new(), &mut buf);
+        assert!(encoder.is_finished());
+        assert_eq!(
+            &buf[..],
+            &b"1a\r\nxxxxxxxxxxxxxxxxxxxxxxxxxx\r\n\
+               3;a=1;b=\"x \\\"y\\\"\";c\r\nabc\r\n0\r\n\r\n"[..]);
+
+        let mut decoder = ChunkedDecoder::new();
+        let (data, heads, trailers) = decode_all(&mut decoder, &buf).unwrap();
+        assert_eq!(data.len(), 29);
+        assert_eq!(heads[1].extensions[1].1, Some("x \"y\"".to_owned()));
+        assert!(trailers.unwrap().is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_encode_after_last() {
+        let mut encoder = ChunkedEncoder::new();
+        let mut buf = BytesMut::new();
+        encoder.encode_last(&HeaderMap::new(), &mut buf);
+        encoder.encode_chunk(b"a", &mut buf);
+    }
+}
@@ -79,7 +79,7 @@ header! {
     ///     ])
     /// );
     /// ```
-    (Accept, "Accept") => (QualityItem<Mime>)+
+    (Accept, "Accept", ACCEPT) => (QualityItem<Mime>)+
 
     test_accept {
         // Tests from the RFC
@@ -122,6 +122,14 @@ header! {
             let header = Accept::parse_header(&raw);
             assert!(header.is_ok());
         }
+
+        #[test]
+        fn test_unterminated_quote() {
+            let raw: Raw = "text/html, foo/\"bar".into();
+            let header = Accept::parse_header(&raw);
+            assert_eq!(header.ok(), Some(Accept(vec![qitem(TEXT_HTML)])));
+            assert!(Accept::parse_header_detailed(&raw).is_err());
+        }
     }
 }
 
@@ -148,5 +156,3 @@ impl Accept {
 }
 
 bench_header!(bench, Accept, { vec![b"text/plain; q=0.5, text/html".to_vec()] });
-
-standard_header!(Accept, ACCEPT);
@@ -51,12 +51,10 @@ header! {
     ///     &AcceptCharset(vec![qitem(Charset::Ext("utf-8".to_owned()))])
     /// );
     /// ```
-    (AcceptCharset, "Accept-Charset") => (QualityItem<Charset>)+
+    (AcceptCharset, "Accept-Charset", ACCEPT_CHARSET) => (QualityItem<Charset>)+
 
     test_accept_charset {
         // Testcase from RFC
         test_header!(test1, vec![b"iso-8859-5, unicode-1-1;q=0.8"]);
     }
 }
-
-standard_header!(AcceptCharset, ACCEPT_CHARSET);
@@ -60,7 +60,7 @@ header! {
     ///     ])
     /// );
     /// ```
-    (AcceptEncoding, "Accept-Encoding") => (QualityItem<Encoding>)*
+    (AcceptEncoding, "Accept-Encoding", ACCEPT_ENCODING) => (QualityItem<Encoding>)*
 
     test_accept_encoding {
         // From the RFC
@@ -73,5 +73,3 @@ header! {
         test_header!(test5, vec![b"gzip, identity; q=0.5, *;q=0"]);
     }
 }
-
-standard_header!(AcceptEncoding, ACCEPT_ENCODING);
@@ -1,5 +1,8 @@
+use std::cmp::Reverse;
+use std::ptr;
+
 use language_tags::LanguageTag;
-use header::QualityItem;
+use header::{QualityItem, q};
 
 header! {
     /// `Accept-Language` header, defined in
@@ -52,7 +55,7 @@ header! {
     /// );
     /// # }
     /// ```
-    (AcceptLanguage, "Accept-Language") => (QualityItem<LanguageTag>)+
+    (AcceptLanguage, "Accept-Language", ACCEPT_LANGUAGE) => (QualityItem<LanguageTag>)+
 
     test_accept_language {
         // From the RFC
@@ -68,7 +71,313 @@ header! {
     }
 }
 
+impl AcceptLanguage {
+    /// Return the available tags matching any language range by basic
+    /// filtering, as defined in
+    /// [RFC4647](https://tools.ietf.org/html/rfc4647#section-3.3.1).
+    ///
+    /// A range matches a tag if it is equal to it, or to a prefix of it
+    /// followed by `-`, ignoring case: `en` matches `en` and `en-GB`.
+    ///
+    /// Matches are ranked by the quality of the range, then by the order of
+    /// ranges, then by the order of `available`. Tags for which the longest
+    /// matching range has `q=0` are excluded. Note that the `*` range is not
+    /// supported, as it isn't a valid `LanguageTag`; see
+    /// `filter_extended_ranges`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate http;
+    /// use hyperx::header::{AcceptLanguage, ContentLanguage, LanguageTag, TypedHeaders};
+    ///
+    /// let mut headers = http::HeaderMap::new();
+    /// headers.insert("accept-language", "fr-CH, fr;q=0.9, en;q=0.8, de;q=0.7".parse().unwrap());
+    /// let accept: AcceptLanguage = headers.decode().unwrap();
+    /// let available: Vec<LanguageTag> = ["de", "en-GB", "en-US", "fr"].iter()
+    ///     .map(|t| t.parse().unwrap())
+    ///     .collect();
+    /// let matches = accept.filter_basic(&available);
+    /// assert_eq!(matches, vec![&available[3], &available[1], &available[2], &available[0]]);
+    ///
+    /// let content_language: ContentLanguage = matches.into_iter().cloned().collect();
+    /// assert_eq!(content_language.to_string(), "fr, en-GB, en-US, de");
+    /// ```
+    pub fn filter_basic<'a>(&self, available: &'a [LanguageTag]) -> Vec<&'a LanguageTag> {
+        filter(&self.ranges(), available, basic_match)
+    }
+
+    /// Return the available tags matching any language range by extended
+    /// filtering, as defined in
+    /// [RFC4647](https://tools.ietf.org/html/rfc4647#section-3.3.2).
+    ///
+    /// Unlike basic filtering, subtags of the tag may be skipped, except
+    /// for singletons: `de-DE` matches `de-DE-1996` and `de-Latn-DE`, but
+    /// not `de-x-DE`. Matches are ranked and excluded as for `filter_basic`.
+    ///
+    /// Wildcard ranges such as `*`, `*-CH` and `de-*-DE` are not valid
+    /// `LanguageTag`s, so an `AcceptLanguage` never holds them: they fail
+    /// to parse and are skipped. To filter with them, parse the header into
+    /// range strings and use `filter_extended_ranges`.
+    pub fn filter_extended<'a>(&self, available: &'a [LanguageTag]) -> Vec<&'a LanguageTag> {
+        filter(&self.ranges(), available, extended_match)
+    }
+
+    /// Return the available tags matching any of the language ranges by
+    /// extended filtering, as for `filter_extended`, including the wildcard
+    /// ranges which `AcceptLanguage` can't hold.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyperx::header::{AcceptLanguage, LanguageTag, QualityItem};
+    ///
+    /// let ranges: Vec<QualityItem<String>> = "*-CH, de-*-DE;q=0.8".split(',')
+    ///     .map(|r| r.trim().parse().unwrap())
+    ///     .collect();
+    /// let available: Vec<LanguageTag> = ["de-Latn-DE", "fr-CH", "fr-FR"].iter()
+    ///     .map(|t| t.parse().unwrap())
+    ///     .collect();
+    /// let matches = AcceptLanguage::filter_extended_ranges(&ranges, &available);
+    /// assert_eq!(matches, vec![&available[1], &available[0]]);
+    /// ```
+    pub fn filter_extended_ranges<'a>(ranges: &[QualityItem<String>], available: &'a [LanguageTag])
+        -> Vec<&'a LanguageTag>
+    {
+        let ranges: Vec<QualityItem<&str>> = ranges.iter()
+            .map(|range| QualityItem::new(range.item.as_str(), range.quality))
+            .collect();
+        filter(&ranges, available, extended_match)
+    }
+
+    /// Return the single best available tag by lookup, as defined in
+    /// [RFC4647](https://tools.ietf.org/html/rfc4647#section-3.4), or `None`
+    /// if no range matches, in which case a default should be used.
+    ///
+    /// Each language range, in order of quality, is compared for equality
+    /// with the available tags, then progressively truncated: `zh-Hant-CN`
+    /// falls back to `zh-Hant`, then `zh`. A singleton subtag left at the
+    /// end is removed with the last subtag. Tags excluded as for
+    /// `filter_basic` are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate http;
+    /// use hyperx::header::{AcceptLanguage, ContentLanguage, LanguageTag, TypedHeaders};
+    ///
+    /// let mut headers = http::HeaderMap::new();
+    /// headers.insert("accept-language", "zh-Hant-CN, en;q=0.5".parse().unwrap());
+    /// let accept: AcceptLanguage = headers.decode().unwrap();
+    /// let available: Vec<LanguageTag> = ["en", "zh", "zh-Hant"].iter()
+    ///     .map(|t| t.parse().unwrap())
+    ///     .collect();
+    /// let best = accept.lookup(&available).unwrap();
+    /// assert_eq!(best.as_str(), "zh-Hant");
+    ///
+    /// let content_language = ContentLanguage::from(best.clone());
+    /// assert_eq!(content_language.to_string(), "zh-Hant");
+    /// ```
+    pub fn lookup<'a>(&self, available: &'a [LanguageTag]) -> Option<&'a LanguageTag> {
+        let ranges = self.ranges();
+        for mut range in priority_list(&ranges) {
+            loop {
+                let found = available.iter().find(|tag| {
+                    tag.as_str().eq_ignore_ascii_case(range)
+                        && !is_excluded(&ranges, tag, basic_match)
+                });
+                if found.is_some() {
+                    return found;
+                }
+                match range.rfind('-') {
+                    Some(i) => range = &range[..i],
+                    None => break,
+                }
+                if range.len() >= 2 && range.as_bytes()[range.len() - 2] == b'-' {
+                    range = &range[..range.len() - 2];
+                }
+            }
+        }
+        None
+    }
+
+    // The language ranges as strings.
+    fn ranges(&self) -> Vec<QualityItem<&str>> {
+        self.iter()
+            .map(|range| QualityItem::new(range.item.as_str(), range.quality))
+            .collect()
+    }
+}
+
+// The ranges with non-zero quality, in descending order of quality.
+fn priority_list<'r>(ranges: &[QualityItem<&'r str>]) -> Vec<&'r str> {
+    let mut ranges: Vec<&QualityItem<&str>> = ranges.iter()
+        .filter(|range| range.quality > q(0))
+        .collect();
+    ranges.sort_by_key(|range| Reverse(range.quality));
+    ranges.into_iter().map(|range| range.item).collect()
+}
+
+// Return true if the longest range matching the tag has `q=0`.
+fn is_excluded(ranges: &[QualityItem<&str>], tag: &LanguageTag, matches: fn(&str, &str) -> bool)
+    -> bool
+{
+    let mut longest: Option<&QualityItem<&str>> = None;
+    for range in ranges {
+        if matches(range.item, tag.as_str())
+            && longest.map_or(true, |l| range.item.len() > l.item.len())
+        {
+            longest = Some(range);
+        }
+    }
+    longest.map_or(false, |range| range.quality == q(0))
+}
+
+fn filter<'a>(ranges: &[QualityItem<&str>], available: &'a [LanguageTag],
+              matches: fn(&str, &str) -> bool)
+    -> Vec<&'a LanguageTag>
+{
+    let mut result: Vec<&'a LanguageTag> = Vec::new();
+    for range in priority_list(ranges) {
+        for tag in available {
+            if matches(range, tag.as_str())
+                && !result.iter().any(|t| ptr::eq(*t, tag))
+                && !is_excluded(ranges, tag, matches)
+            {
+                result.push(tag);
+            }
+        }
+    }
+    result
+}
+
+fn basic_match(range: &str, tag: &str) -> bool {
+    tag.len() >= range.len()
+        && tag[..range.len()].eq_ignore_ascii_case(range)
+        && (tag.len() == range.len() || tag.as_bytes()[range.len()] == b'-')
+}
+
+fn extended_match(range: &str, tag: &str) -> bool {
+    let mut range = range.split('-');
+    let mut tag = tag.split('-');
+    match (range.next(), tag.next()) {
+        (Some(r), Some(t)) if r == "*" || r.eq_ignore_ascii_case(t) => {}
+        _ => return false,
+    }
+    let mut t = tag.next();
+    for r in range {
+        if r == "*" {
+            continue;
+        }
+        loop {
+            match t {
+                None => return false,
+                Some(s) if s.eq_ignore_ascii_case(r) => {
+                    t = tag.next();
+                    break;
+                }
+                Some(s) if s.len() == 1 => return false,
+                Some(_) => t = tag.next(),
+            }
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use header::{Header, LanguageTag, QualityItem, Raw};
+    use super::AcceptLanguage;
+
+    fn accept(s: &str) -> AcceptLanguage {
+        let r: Raw = s.into();
+        AcceptLanguage::parse_header(&r).unwrap()
+    }
+
+    fn tags(tags: &[&str]) -> Vec<LanguageTag> {
+        tags.iter().map(|t| t.parse().unwrap()).collect()
+    }
+
+    fn strs<'a>(tags: &[&'a LanguageTag]) -> Vec<&'a str> {
+        tags.iter().map(|t| t.as_str()).collect()
+    }
+
+    #[test]
+    fn test_filter_basic() {
+        let available = tags(&["de-DE", "en", "en-GB", "en-US", "eng", "fr"]);
+        let a = accept("en-us, de;q=0.5, en;q=0.8");
+        assert_eq!(strs(&a.filter_basic(&available)), vec!["en-US", "en", "en-GB", "de-DE"]);
+
+        let a = accept("en;q=0.5, en-GB;q=0, fr");
+        assert_eq!(strs(&a.filter_basic(&available)), vec!["fr", "en", "en-US"]);
+
+        let a = accept("en;q=0, en-GB");
+        assert_eq!(strs(&a.filter_basic(&available)), vec!["en-GB"]);
+
+        assert!(accept("it").filter_basic(&available).is_empty());
+    }
+
+    #[test]
+    fn test_filter_extended() {
+        // RFC 4647, section 3.3.2; tags are normalized on parse
+        let available = tags(&[
+            "de", "de-DE", "de-de", "de-Latn-DE", "de-Latf-DE", "de-DE-x-goethe",
+            "de-Latn-DE-1996", "de-Deva-DE", "de-x-DE", "de-Deva",
+        ]);
+        let a = accept("de-DE");
+        assert_eq!(strs(&a.filter_extended(&available)), vec![
+            "de-DE", "de-DE", "de-Latn-DE", "de-Latf-DE", "de-DE-x-goethe",
+            "de-Latn-DE-1996", "de-Deva-DE",
+        ]);
+        assert_eq!(strs(&a.filter_basic(&available)), vec![
+            "de-DE", "de-DE", "de-DE-x-goethe",
+        ]);
+
+        assert!(accept("*-DE, de-*-DE").filter_extended(&available).is_empty());
+    }
+
+    #[test]
+    fn test_filter_extended_ranges() {
+        let available = tags(&["de", "de-DE", "de-Latn-DE", "de-Latn", "fr-CH", "it-CH"]);
+        let ranges = |s: &str| -> Vec<QualityItem<String>> {
+            s.split(',').map(|r| r.trim().parse().unwrap()).collect()
+        };
+        let filter = |s: &str| {
+            strs(&AcceptLanguage::filter_extended_ranges(&ranges(s), &available))
+        };
+        assert_eq!(filter("de-*-DE"), vec!["de-DE", "de-Latn-DE"]);
+        assert_eq!(filter("*-DE"), vec!["de-DE", "de-Latn-DE"]);
+        assert_eq!(filter("*-CH;q=0.5, de-DE"), vec!["de-DE", "de-Latn-DE", "fr-CH", "it-CH"]);
+        assert_eq!(filter("*, de;q=0"), vec!["fr-CH", "it-CH"]);
+        assert_eq!(filter("*, *-CH;q=0, de-Latn;q=0"), vec!["de", "de-DE"]);
+    }
+
+    #[test]
+    fn test_lookup() {
+        // RFC 4647, section 3.4
+        let a = accept("zh-Hant-CN-x-private1-private2");
+        for &(available, expected) in &[
+            (&["zh-Hant-CN-x-private1-private2", "zh"][..], Some("zh-Hant-CN-x-private1-private2")),
+            (&["zh-Hant-CN-x-private1", "zh"][..], Some("zh-Hant-CN-x-private1")),
+            (&["zh-Hant-CN", "zh"][..], Some("zh-Hant-CN")),
+            (&["zh-Hant", "zh"][..], Some("zh-Hant")),
+            (&["zh-CN", "zh"][..], Some("zh")),
+            (&["en", "zh-CN"][..], None),
+        ] {
+            let available = tags(available);
+            assert_eq!(a.lookup(&available).map(|t| t.as_str()), expected);
+        }
+
+        let available = tags(&["en", "fr", "fr-CA"]);
+        assert_eq!(accept("de, fr-CH;q=0.8, en;q=0.5").lookup(&available).map(|t| t.as_str()),
+                   Some("fr"));
+        assert_eq!(accept("fr-CA;q=0, fr-BE").lookup(&available).map(|t| t.as_str()),
+                   Some("fr"));
+        assert_eq!(accept("fr;q=0, fr-CA").lookup(&available).map(|t| t.as_str()),
+                   Some("fr-CA"));
+        assert_eq!(accept("fr;q=0, fr-BE").lookup(&available), None);
+    }
+}
+
 bench_header!(bench, AcceptLanguage,
               { vec![b"en-us;q=1.0, en;q=0.5, fr".to_vec()] });
-
-standard_header!(AcceptLanguage, ACCEPT_LANGUAGE);
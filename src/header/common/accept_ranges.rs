@@ -51,7 +51,7 @@ header! {
     ///     ])
     /// );
     /// ```
-    (AcceptRanges, "Accept-Ranges") => (RangeUnit)+
+    (AcceptRanges, "Accept-Ranges", ACCEPT_RANGES) => (RangeUnit)+
 
     test_acccept_ranges {
         test_header!(test1, vec![b"bytes"]);
@@ -105,5 +105,3 @@ impl Display for RangeUnit {
         }
     }
 }
-
-standard_header!(AcceptRanges, ACCEPT_RANGES);
@@ -49,6 +49,8 @@ impl Header for AccessControlAllowCredentials {
         NAME
     }
 
+    standard_header!(fn ACCESS_CONTROL_ALLOW_CREDENTIALS);
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<AccessControlAllowCredentials>
     where T: RawLike<'a>
     {
@@ -55,11 +55,9 @@ header! {
     /// );
     /// # }
     /// ```
-    (AccessControlAllowHeaders, "Access-Control-Allow-Headers") => (Ascii<String>)*
+    (AccessControlAllowHeaders, "Access-Control-Allow-Headers", ACCESS_CONTROL_ALLOW_HEADERS) => (Ascii<String>)*
 
     test_access_control_allow_headers {
         test_header!(test1, vec![b"accept-language, date"]);
     }
 }
-
-standard_header!(AccessControlAllowHeaders, ACCESS_CONTROL_ALLOW_HEADERS);
@@ -45,11 +45,9 @@ header! {
     ///     ])
     /// );
     /// ```
-    (AccessControlAllowMethods, "Access-Control-Allow-Methods") => (Method)*
+    (AccessControlAllowMethods, "Access-Control-Allow-Methods", ACCESS_CONTROL_ALLOW_METHODS) => (Method)*
 
     test_access_control_allow_methods {
         test_header!(test1, vec![b"PUT, DELETE, XMODIFY"]);
     }
 }
-
-standard_header!(AccessControlAllowMethods, ACCESS_CONTROL_ALLOW_METHODS);
@@ -64,6 +64,8 @@ impl Header for AccessControlAllowOrigin {
         "Access-Control-Allow-Origin"
     }
 
+    standard_header!(fn ACCESS_CONTROL_ALLOW_ORIGIN);
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<AccessControlAllowOrigin>
     where T: RawLike<'a>
     {
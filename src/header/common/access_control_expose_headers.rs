@@ -57,11 +57,9 @@ header! {
     /// );
     /// # }
     /// ```
-    (AccessControlExposeHeaders, "Access-Control-Expose-Headers") => (Ascii<String>)*
+    (AccessControlExposeHeaders, "Access-Control-Expose-Headers", ACCESS_CONTROL_EXPOSE_HEADERS) => (Ascii<String>)*
 
     test_access_control_expose_headers {
         test_header!(test1, vec![b"etag, content-length"]);
     }
 }
-
-standard_header!(AccessControlExposeHeaders, ACCESS_CONTROL_EXPOSE_HEADERS);
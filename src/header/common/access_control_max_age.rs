@@ -24,11 +24,9 @@ header! {
     /// let mut headers = http::HeaderMap::new();
     /// headers.encode(&AccessControlMaxAge(1728000u32));
     /// ```
-    (AccessControlMaxAge, "Access-Control-Max-Age") => [u32]
+    (AccessControlMaxAge, "Access-Control-Max-Age", ACCESS_CONTROL_MAX_AGE) => number [u32]
 
     test_access_control_max_age {
         test_header!(test1, vec![b"531"]);
     }
 }
-
-standard_header!(AccessControlMaxAge, ACCESS_CONTROL_MAX_AGE);
@@ -55,11 +55,9 @@ header! {
     /// );
     /// # }
     /// ```
-    (AccessControlRequestHeaders, "Access-Control-Request-Headers") => (Ascii<String>)*
+    (AccessControlRequestHeaders, "Access-Control-Request-Headers", ACCESS_CONTROL_REQUEST_HEADERS) => (Ascii<String>)*
 
     test_access_control_request_headers {
         test_header!(test1, vec![b"accept-language, date"]);
     }
 }
-
-standard_header!(AccessControlRequestHeaders, ACCESS_CONTROL_REQUEST_HEADERS);
@@ -25,11 +25,9 @@ header! {
     /// let mut headers = http::HeaderMap::new();
     /// headers.encode(&AccessControlRequestMethod(Method::Get));
     /// ```
-    (AccessControlRequestMethod, "Access-Control-Request-Method") => [Method]
+    (AccessControlRequestMethod, "Access-Control-Request-Method", ACCESS_CONTROL_REQUEST_METHOD) => [Method]
 
     test_access_control_request_method {
         test_header!(test1, vec![b"GET"]);
     }
 }
-
-standard_header!(AccessControlRequestMethod, ACCESS_CONTROL_REQUEST_METHOD);
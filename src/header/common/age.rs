@@ -0,0 +1,33 @@
+header! {
+    /// `Age` header, defined in [RFC9111](https://www.rfc-editor.org/rfc/rfc9111#section-5.1)
+    ///
+    /// The `Age` response header field conveys the sender's estimate of the
+    /// time since the response was generated or successfully validated at
+    /// the origin server, in seconds.
+    ///
+    /// # ABNF
+    ///
+    /// ```text
+    /// Age = delta-seconds
+    /// ```
+    ///
+    /// # Example values
+    ///
+    /// * `60`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate http;
+    /// use hyperx::header::{Age, TypedHeaders};
+    ///
+    /// let mut headers = http::HeaderMap::new();
+    /// headers.encode(&Age(60u32));
+    /// ```
+    (Age, "Age", AGE) => number [u32]
+
+    test_age {
+        test_header!(test1, vec![b"60"]);
+        test_header!(test2, vec![b"-1"], None);
+    }
+}
@@ -47,7 +47,7 @@ header! {
     ///     ])
     /// );
     /// ```
-    (Allow, "Allow") => (Method)*
+    (Allow, "Allow", ALLOW) => (Method)*
 
     test_allow {
         // From the RFC
@@ -79,5 +79,3 @@ header! {
 
 bench_header!(bench,
     Allow, { vec![b"OPTIONS,GET,PUT,POST,DELETE,HEAD,TRACE,CONNECT,PATCH,fOObAr".to_vec()] });
-
-standard_header!(Allow, ALLOW);
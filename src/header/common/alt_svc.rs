@@ -0,0 +1,357 @@
+use std::fmt;
+use std::str::{FromStr, from_utf8};
+use std::time::Duration;
+
+use percent_encoding::{percent_decode_str, percent_encode, AsciiSet, CONTROLS};
+
+use header::{Header, RawLike};
+use header::parsing::{fmt_quoted, is_token, split_unquoted, unquote};
+
+/// `Alt-Svc` header, defined in [RFC7838](https://tools.ietf.org/html/rfc7838#section-3)
+///
+/// The `Alt-Svc` response header field advertises alternative services:
+/// protocols and authorities through which the same resource can be
+/// accessed, such as HTTP/3 on a UDP port. The value `clear` invalidates all
+/// alternatives previously advertised by the origin.
+///
+/// # ABNF
+///
+/// ```text
+/// Alt-Svc       = clear / 1#alt-value
+/// clear         = %s"clear"; "clear", case-sensitive
+/// alt-value     = alternative *( OWS ";" OWS parameter )
+/// alternative   = protocol-id "=" alt-authority
+/// protocol-id   = token ; percent-encoded ALPN protocol name
+/// alt-authority = quoted-string ; containing [ uri-host ] ":" port
+/// parameter     = token "=" ( token / quoted-string )
+/// ```
+///
+/// # Example values
+///
+/// * `clear`
+/// * `h3=":443"; ma=86400, h2=":443"`
+/// * `h2="alt.example.com:8000"; ma=3600; persist=1`
+///
+/// # Examples
+///
+/// ```
+/// # extern crate http;
+/// use hyperx::header::{AltService, AltSvc, TypedHeaders};
+///
+/// let mut headers = http::HeaderMap::new();
+/// headers.insert("alt-svc", "h3=\":443\"; ma=3600, h2=\":443\"".parse().unwrap());
+/// let alt_svc: AltSvc = headers.decode().unwrap();
+/// match alt_svc {
+///     AltSvc::Alternatives(ref alts) => {
+///         assert_eq!(alts[0].protocol_id, "h3");
+///         assert_eq!(alts[0].port, 443);
+///         assert_eq!(alts[0].max_age, Some(3600));
+///     }
+///     AltSvc::Clear => unreachable!(),
+/// }
+///
+/// headers.encode(&AltSvc::Alternatives(vec![
+///     AltService::new("h3", 8443).with_max_age(86400),
+/// ]));
+/// assert_eq!(headers["alt-svc"], "h3=\":8443\"; ma=86400");
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub enum AltSvc {
+    /// `clear`: all alternative services of the origin are invalidated.
+    Clear,
+    /// A list of alternative services, in order of preference.
+    Alternatives(Vec<AltService>),
+}
+
+impl Header for AltSvc {
+    fn header_name() -> &'static str {
+        static NAME: &str = "Alt-Svc";
+        NAME
+    }
+
+    standard_header!(fn ALT_SVC);
+
+    fn parse_header<'a, T>(raw: &'a T) -> ::Result<AltSvc>
+    where T: RawLike<'a>
+    {
+        let mut clear = false;
+        let mut alternatives = Vec::new();
+        for line in raw.iter() {
+            let line = from_utf8(line)?.trim();
+            if line == "clear" {
+                clear = true;
+                continue;
+            }
+            for alt in split_unquoted(line, b',')? {
+                let alt = alt.trim();
+                if !alt.is_empty() {
+                    alternatives.push(alt.parse()?);
+                }
+            }
+        }
+        match (clear, alternatives.is_empty()) {
+            (true, true) => Ok(AltSvc::Clear),
+            (false, false) => Ok(AltSvc::Alternatives(alternatives)),
+            _ => Err(::Error::Header),
+        }
+    }
+
+    fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
+        f.fmt_line(self)
+    }
+}
+
+impl fmt::Display for AltSvc {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AltSvc::Clear => f.write_str("clear"),
+            AltSvc::Alternatives(ref alts) => {
+                ::header::parsing::fmt_comma_delimited(f, &alts[..])
+            }
+        }
+    }
+}
+
+/// An alternative service of the `Alt-Svc` header.
+#[derive(Clone, PartialEq, Debug)]
+pub struct AltService {
+    /// The ALPN protocol name, such as `h3` or `h2`, percent-decoded.
+    pub protocol_id: String,
+    /// The alternative host, or `None` for the same host as the origin.
+    pub host: Option<String>,
+    /// The alternative port.
+    pub port: u16,
+    /// The `ma` parameter: the number of seconds the alternative is
+    /// considered fresh. Larger values saturate at `u32::MAX`.
+    pub max_age: Option<u32>,
+    /// The `persist` parameter: `true` for `persist=1`, when the alternative
+    /// should not be cleared on network configuration changes.
+    pub persist: bool,
+    /// Any other parameters, as (name, value) pairs.
+    pub extensions: Vec<(String, String)>,
+}
+
+impl AltService {
+    /// The freshness lifetime in effect without an `ma` parameter, 24 hours.
+    pub const DEFAULT_MAX_AGE: u32 = 86_400;
+
+    /// Create an alternative service on the same host as the origin.
+    pub fn new<S: Into<String>>(protocol_id: S, port: u16) -> AltService {
+        AltService {
+            protocol_id: protocol_id.into(),
+            host: None,
+            port,
+            max_age: None,
+            persist: false,
+            extensions: Vec::new(),
+        }
+    }
+
+    /// Set the alternative host.
+    pub fn with_host<S: Into<String>>(mut self, host: S) -> AltService {
+        self.host = Some(host.into());
+        self
+    }
+
+    /// Set the `ma` parameter.
+    pub fn with_max_age(mut self, seconds: u32) -> AltService {
+        self.max_age = Some(seconds);
+        self
+    }
+
+    /// Set the `persist` parameter.
+    pub fn with_persist(mut self, persist: bool) -> AltService {
+        self.persist = persist;
+        self
+    }
+
+    /// Return the freshness lifetime, which defaults to 24 hours.
+    pub fn freshness(&self) -> Duration {
+        Duration::from_secs(u64::from(self.max_age.unwrap_or(AltService::DEFAULT_MAX_AGE)))
+    }
+}
+
+// Octets of an ALPN protocol name which aren't `tchar`, and `%`, must be
+// percent-encoded in a protocol-id.
+const PROTOCOL_ID: &AsciiSet = &CONTROLS
+    .add(b' ') .add(b'"') .add(b'%') .add(b'(') .add(b')') .add(b',')
+    .add(b'/') .add(b':') .add(b';') .add(b'<') .add(b'=') .add(b'>')
+    .add(b'?') .add(b'@') .add(b'[') .add(b'\\') .add(b']') .add(b'{')
+    .add(b'}');
+
+impl fmt::Display for AltService {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}=", percent_encode(self.protocol_id.as_bytes(), PROTOCOL_ID))?;
+        let authority = format!("{}:{}", self.host.as_ref().map_or("", |h| &h[..]), self.port);
+        fmt_quoted(f, &authority)?;
+        if let Some(ma) = self.max_age {
+            write!(f, "; ma={}", ma)?;
+        }
+        if self.persist {
+            f.write_str("; persist=1")?;
+        }
+        for (name, value) in &self.extensions {
+            write!(f, "; {}=", name)?;
+            if is_token(value) {
+                f.write_str(value)?;
+            } else {
+                fmt_quoted(f, value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for AltService {
+    type Err = ::Error;
+
+    fn from_str(s: &str) -> ::Result<AltService> {
+        let mut params = split_unquoted(s, b';')?.into_iter()
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(parse_param);
+
+        let (protocol_id, authority) = params.next().ok_or(::Error::Header)??;
+        let protocol_id = percent_decode_str(protocol_id).decode_utf8()
+            .map_err(|_| ::Error::Header)?
+            .into_owned();
+        let colon = authority.rfind(':').ok_or(::Error::Header)?;
+        let port = &authority[colon + 1..];
+        if port.is_empty() || !port.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(::Error::Header);
+        }
+        let mut alt = AltService::new(protocol_id, port.parse().map_err(|_| ::Error::Header)?);
+        if colon > 0 {
+            alt.host = Some(authority[..colon].to_owned());
+        }
+
+        for param in params {
+            let (name, value) = param?;
+            if name.eq_ignore_ascii_case("ma") {
+                if alt.max_age.is_none() {
+                    alt.max_age = Some(parse_delta_seconds(&value)?);
+                }
+            } else if name.eq_ignore_ascii_case("persist") {
+                alt.persist = value == "1";
+            } else {
+                alt.extensions.push((name.to_ascii_lowercase(), value));
+            }
+        }
+        Ok(alt)
+    }
+}
+
+fn parse_param(param: &str) -> ::Result<(&str, String)> {
+    let eq = param.find('=').ok_or(::Error::Header)?;
+    let (name, value) = (param[..eq].trim_end(), param[eq + 1..].trim_start());
+    if !is_token(name) {
+        return Err(::Error::Header);
+    }
+    if value.starts_with('"') {
+        Ok((name, unquote(value)?))
+    } else if is_token(value) {
+        Ok((name, value.to_owned()))
+    } else {
+        Err(::Error::Header)
+    }
+}
+
+fn parse_delta_seconds(s: &str) -> ::Result<u32> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(::Error::Header);
+    }
+    Ok(s.parse().unwrap_or(u32::MAX))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use header::{Header, Raw};
+    use super::{AltService, AltSvc};
+
+    fn parse(s: &str) -> ::Result<AltSvc> {
+        let r: Raw = s.into();
+        Header::parse_header(&r)
+    }
+
+    fn alternatives(s: &str) -> Vec<AltService> {
+        match parse(s).unwrap() {
+            AltSvc::Alternatives(alts) => alts,
+            AltSvc::Clear => panic!("clear"),
+        }
+    }
+
+    #[test]
+    fn test_clear() {
+        assert_eq!(parse("clear").unwrap(), AltSvc::Clear);
+        assert_eq!(AltSvc::Clear.to_string(), "clear");
+        assert!(parse("clear, h2=\":443\"").is_err());
+        let r: Raw = vec![b"clear".to_vec(), b"h2=\":443\"".to_vec()].into();
+        assert!(AltSvc::parse_header(&r).is_err());
+    }
+
+    #[test]
+    fn test_parse() {
+        let alts = alternatives(
+            "h3=\":443\"; ma=2592000; persist=1, h2=\"alt.example.com:8000\", \
+             h3-29=\"[2001:db8::1]:443\";foo=\"a b\";MA=60");
+        assert_eq!(alts.len(), 3);
+        assert_eq!(alts[0], AltService::new("h3", 443)
+                   .with_max_age(2592000)
+                   .with_persist(true));
+        assert_eq!(alts[1], AltService::new("h2", 8000).with_host("alt.example.com"));
+        assert_eq!(alts[1].freshness(), Duration::from_secs(86400));
+        assert_eq!(alts[2].host, Some("[2001:db8::1]".to_owned()));
+        assert_eq!(alts[2].max_age, Some(60));
+        assert_eq!(alts[2].extensions, vec![("foo".to_owned(), "a b".to_owned())]);
+    }
+
+    #[test]
+    fn test_percent_encoded_protocol_id() {
+        // RFC 7838, section 3
+        let alts = alternatives("w%3D%3D=\":443\"");
+        assert_eq!(alts[0].protocol_id, "w==");
+        assert_eq!(alts[0].to_string(), "w%3D%3D=\":443\"");
+        assert_eq!(AltService::new("a%b", 1).to_string(), "a%25b=\":1\"");
+    }
+
+    #[test]
+    fn test_parse_params() {
+        let alts = alternatives("h2=\":443\"; persist=0; ma=99999999999; ma=5");
+        assert!(!alts[0].persist);
+        assert_eq!(alts[0].max_age, Some(u32::MAX));
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for bad in &[
+            "",
+            "h2",
+            "h2=\"example.com\"",
+            "h2=\":\"",
+            "h2=\":99999\"",
+            "h2=\":443",
+            "h2=\":443\"; ma=-1",
+            "h2=\":443\"; ma",
+            "%FF=\":443\"",
+        ] {
+            assert!(parse(bad).is_err(), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn test_fmt() {
+        let alt_svc = AltSvc::Alternatives(vec![
+            AltService::new("h3", 443).with_max_age(3600).with_persist(true),
+            AltService::new("h2", 443).with_host("alt.example.com"),
+        ]);
+        let s = alt_svc.to_string();
+        assert_eq!(s, "h3=\":443\"; ma=3600; persist=1, h2=\"alt.example.com:443\"");
+        assert_eq!(parse(&s).unwrap(), alt_svc);
+    }
+}
+
+bench_header!(bench, AltSvc, { vec![b"h3=\":443\"; ma=86400, h2=\":443\"; ma=86400".to_vec()] });
+
+standard_header!(AltSvc, ALT_SVC);
@@ -0,0 +1,124 @@
+use std::fmt;
+use std::str::FromStr;
+
+use header::{Header, Host, RawLike};
+use header::parsing::from_one_raw_str;
+
+/// `Alt-Used` header, defined in [RFC7838](https://tools.ietf.org/html/rfc7838#section-5)
+///
+/// The `Alt-Used` request header field identifies the alternative service
+/// in use, just as the `Host` header field identifies the host and port of
+/// the origin.
+///
+/// # ABNF
+///
+/// ```text
+/// Alt-Used = uri-host [ ":" port ]
+/// ```
+///
+/// # Example values
+///
+/// * `alternate.example.net`
+/// * `alt.example.com:8443`
+///
+/// # Examples
+///
+/// ```
+/// # extern crate http;
+/// use hyperx::header::{AltUsed, TypedHeaders};
+///
+/// let mut headers = http::HeaderMap::new();
+/// headers.encode(&AltUsed::new("alt.example.com", 8443));
+/// assert_eq!(headers["alt-used"], "alt.example.com:8443");
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct AltUsed {
+    hostname: String,
+    port: Option<u16>,
+}
+
+impl AltUsed {
+    /// Create an `Alt-Used` header, providing the hostname and optional port.
+    pub fn new<H, P>(hostname: H, port: P) -> AltUsed
+    where H: Into<String>,
+          P: Into<Option<u16>>
+    {
+        AltUsed {
+            hostname: hostname.into(),
+            port: port.into(),
+        }
+    }
+
+    /// Get the hostname, such as alt.example.com.
+    pub fn hostname(&self) -> &str {
+        &self.hostname
+    }
+
+    /// Get the optional port number.
+    pub fn port(&self) -> Option<u16> {
+        self.port
+    }
+}
+
+impl Header for AltUsed {
+    fn header_name() -> &'static str {
+        static NAME: &str = "Alt-Used";
+        NAME
+    }
+
+    standard_header!(fn "alt-used");
+
+    fn parse_header<'a, T>(raw: &'a T) -> ::Result<AltUsed>
+    where T: RawLike<'a>
+    {
+        from_one_raw_str(raw)
+    }
+
+    fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
+        f.fmt_line(self)
+    }
+}
+
+impl fmt::Display for AltUsed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.port {
+            Some(port) => write!(f, "{}:{}", self.hostname, port),
+            None => f.write_str(&self.hostname),
+        }
+    }
+}
+
+impl FromStr for AltUsed {
+    type Err = ::Error;
+
+    fn from_str(s: &str) -> ::Result<AltUsed> {
+        let host: Host = s.parse()?;
+        Ok(AltUsed::new(host.hostname(), host.port()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use header::{Header, Raw};
+    use super::AltUsed;
+
+    #[test]
+    fn test_alt_used() {
+        let r: Raw = "alternate.example.net".into();
+        let alt_used: AltUsed = Header::parse_header(&r).unwrap();
+        assert_eq!(alt_used, AltUsed::new("alternate.example.net", None));
+
+        let r: Raw = "[2001:db8::1]:8443".into();
+        let alt_used: AltUsed = Header::parse_header(&r).unwrap();
+        assert_eq!(alt_used.hostname(), "[2001:db8::1]");
+        assert_eq!(alt_used.port(), Some(8443));
+        assert_eq!(alt_used.to_string(), "[2001:db8::1]:8443");
+
+        let r: Raw = "".into();
+        assert!(AltUsed::parse_header(&r).is_err());
+    }
+}
+
+bench_header!(bench, AltUsed, { vec![b"alt.example.com:8443".to_vec()] });
+
+standard_header!(AltUsed, "alt-used");
@@ -4,6 +4,7 @@ use std::str::{FromStr, from_utf8};
 use std::ops::{Deref, DerefMut};
 use base64::{encode, decode};
 use header::{Header, RawLike};
+use super::www_authenticate::{Challenge, fmt_auth_params};
 
 /// `Authorization` header, defined in [RFC7235](https://tools.ietf.org/html/rfc7235#section-4.2)
 ///
@@ -58,6 +59,9 @@ use header::{Header, RawLike};
 ///        }
 ///    )
 /// );
+///
+/// let auth: Authorization<Bearer> = headers.decode().unwrap();
+/// assert_eq!(auth.token, "QWxhZGRpbjpvcGVuIHNlc2FtZQ");
 /// ```
 #[derive(Clone, PartialEq, Debug)]
 pub struct Authorization<S: Scheme>(pub S);
@@ -82,29 +86,12 @@ impl<S: Scheme + Any> Header for Authorization<S> where <S as FromStr>::Err: 'st
         NAME
     }
 
+    standard_header!(fn AUTHORIZATION);
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<Authorization<S>>
     where T: RawLike<'a>
     {
-        if let Some(line) = raw.one() {
-            let header = from_utf8(line)?;
-            if let Some(scheme) = <S as Scheme>::scheme() {
-                if header.starts_with(scheme) && header.len() > scheme.len() + 1 {
-                    match header[scheme.len() + 1..].parse::<S>().map(Authorization) {
-                        Ok(h) => Ok(h),
-                        Err(_) => Err(::Error::Header)
-                    }
-                } else {
-                    Err(::Error::Header)
-                }
-            } else {
-                match header.parse::<S>().map(Authorization) {
-                    Ok(h) => Ok(h),
-                    Err(_) => Err(::Error::Header)
-                }
-            }
-        } else {
-            Err(::Error::Header)
-        }
+        parse_scheme(raw).map(Authorization)
     }
 
     fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
@@ -112,6 +99,31 @@ impl<S: Scheme + Any> Header for Authorization<S> where <S as FromStr>::Err: 'st
     }
 }
 
+/// Parse a single line of credentials as the `Scheme` type S.
+///
+/// If S has a scheme name, it is matched case-insensitively, as required by
+/// RFC 7235, and only the remainder following the name is passed to S.
+pub fn parse_scheme<'a, S, T>(raw: &'a T) -> ::Result<S>
+    where S: Scheme, T: RawLike<'a>
+{
+    let header = from_utf8(raw.one().ok_or(::Error::Header)?)?;
+    let rest = if let Some(scheme) = <S as Scheme>::scheme() {
+        let len = scheme.len();
+        let bytes = header.as_bytes();
+        if bytes.len() > len + 1
+            && bytes[..len].eq_ignore_ascii_case(scheme.as_bytes())
+            && bytes[len] == b' '
+        {
+            header[len + 1..].trim_start_matches(' ')
+        } else {
+            return Err(::Error::Header);
+        }
+    } else {
+        header
+    };
+    rest.parse::<S>().map_err(|_| ::Error::Header)
+}
+
 impl<S: Scheme> fmt::Display for Authorization<S> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         if let Some(scheme) = <S as Scheme>::scheme() {
@@ -227,9 +239,111 @@ impl FromStr for Bearer {
     }
 }
 
+/// Credential holder for any auth-scheme, parsed at runtime.
+///
+/// Unlike `Basic` or `Bearer`, this `Scheme` has no fixed scheme name, so an
+/// `Authorization<Credentials>` accepts credentials of any scheme. The
+/// credentials may then be inspected, or converted to a specific scheme type
+/// with `to_scheme`.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate http;
+/// use hyperx::header::{Authorization, Bearer, Credentials, TypedHeaders};
+///
+/// let mut headers = http::HeaderMap::new();
+/// headers.insert(
+///     http::header::AUTHORIZATION,
+///     "bearer fpKL54jvWmEGVoRdCNjG".parse().unwrap()
+/// );
+/// let auth: Authorization<Credentials> = headers.decode().unwrap();
+/// if auth.is_scheme("Bearer") {
+///     let bearer: Bearer = auth.to_scheme().unwrap();
+///     assert_eq!(bearer.token, "fpKL54jvWmEGVoRdCNjG");
+/// }
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct Credentials {
+    /// The auth-scheme name as received, such as `Basic` or `Negotiate`.
+    pub scheme: String,
+    /// An opaque `token68` value. Credentials with a `token68` have no
+    /// `params`: if both are set, only the `token68` is formatted.
+    pub token68: Option<String>,
+    /// The auth-params as (name, value) pairs, in received order.
+    pub params: Vec<(String, String)>,
+}
+
+impl Credentials {
+    /// Return true if the auth-scheme matches the given name,
+    /// case-insensitively.
+    pub fn is_scheme(&self, scheme: &str) -> bool {
+        self.scheme.eq_ignore_ascii_case(scheme)
+    }
+
+    /// Return the value of the first auth-param with the given name,
+    /// compared case-insensitively.
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params.iter()
+            .find(|p| p.0.eq_ignore_ascii_case(name))
+            .map(|p| &p.1[..])
+    }
+
+    /// Convert to the `Scheme` type S.
+    ///
+    /// Returns `Error::Header` if the scheme name of S does not match, or if
+    /// the credentials fail to parse as S.
+    pub fn to_scheme<S: Scheme>(&self) -> ::Result<S> {
+        match <S as Scheme>::scheme() {
+            Some(scheme) if self.is_scheme(scheme) => {
+                let rest = match self.token68 {
+                    Some(ref token68) => token68.clone(),
+                    None => AuthParams(&self.params).to_string(),
+                };
+                rest.trim_start().parse::<S>().map_err(|_| ::Error::Header)
+            }
+            Some(_) => Err(::Error::Header),
+            None => Authorization(self.clone()).to_string()
+                .parse::<S>()
+                .map_err(|_| ::Error::Header),
+        }
+    }
+}
+
+// Display adaptor for formatting only the auth-params of credentials.
+struct AuthParams<'a>(&'a [(String, String)]);
+
+impl<'a> fmt::Display for AuthParams<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_auth_params(f, self.0)
+    }
+}
+
+impl Scheme for Credentials {
+    fn scheme() -> Option<&'static str> {
+        None
+    }
+
+    fn fmt_scheme(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.scheme)?;
+        match self.token68 {
+            Some(ref token68) => write!(f, " {}", token68),
+            None => fmt_auth_params(f, &self.params),
+        }
+    }
+}
+
+impl FromStr for Credentials {
+    type Err = ::Error;
+    fn from_str(s: &str) -> ::Result<Credentials> {
+        let Challenge { scheme, token68, params } = s.parse()?;
+        Ok(Credentials { scheme, token68, params })
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{Authorization, Basic, Bearer};
+    use super::{Authorization, Basic, Bearer, Credentials};
     use super::super::super::{Header, Raw};
 
     #[cfg(feature = "headers")]
@@ -302,11 +416,75 @@ mod tests {
         let auth: Authorization<Bearer> = Header::parse_header(&r).unwrap();
         assert_eq!(auth.0.token, "fpKL54jvWmEGVoRdCNjG");
     }
+
+    #[test]
+    fn test_scheme_case_insensitive() {
+        let r: Raw = b"basic QWxhZGRpbjpvcGVuIHNlc2FtZQ==".as_ref().into();
+        let auth: Authorization<Basic> = Header::parse_header(&r).unwrap();
+        assert_eq!(auth.0.username, "Aladdin");
+
+        let r: Raw = b"BEARER fpKL54jvWmEGVoRdCNjG".as_ref().into();
+        let auth: Authorization<Bearer> = Header::parse_header(&r).unwrap();
+        assert_eq!(auth.0.token, "fpKL54jvWmEGVoRdCNjG");
+
+        let r: Raw = b"Bearerfoo".as_ref().into();
+        let auth: ::Result<Authorization<Bearer>> = Header::parse_header(&r);
+        assert!(auth.is_err());
+    }
+
+    #[test]
+    fn test_credentials_token68() {
+        let r: Raw = b"Negotiate YIIBzgYGKwYBBQUCoIIBwjCCAb6g==".as_ref().into();
+        let mut auth: Authorization<Credentials> = Header::parse_header(&r).unwrap();
+        assert_eq!(auth.0, Credentials {
+            scheme: "Negotiate".to_owned(),
+            token68: Some("YIIBzgYGKwYBBQUCoIIBwjCCAb6g==".to_owned()),
+            params: vec![],
+        });
+        assert_eq!(auth.to_string(), "Negotiate YIIBzgYGKwYBBQUCoIIBwjCCAb6g==");
+        assert!(auth.to_scheme::<Basic>().is_err());
+
+        auth.0.params.push(("realm".to_owned(), "x".to_owned()));
+        assert_eq!(auth.to_string(), "Negotiate YIIBzgYGKwYBBQUCoIIBwjCCAb6g==");
+    }
+
+    #[test]
+    fn test_credentials_params() {
+        let r: Raw = b"HMAC keyId=\"k1\", signature=\"a,b=\"".as_ref().into();
+        let auth: Authorization<Credentials> = Header::parse_header(&r).unwrap();
+        assert!(auth.is_scheme("hmac"));
+        assert_eq!(auth.param("keyid"), Some("k1"));
+        assert_eq!(auth.param("signature"), Some("a,b="));
+        assert_eq!(auth.to_string(), "HMAC keyId=k1, signature=\"a,b=\"");
+    }
+
+    #[test]
+    fn test_credentials_to_scheme() {
+        let r: Raw = b"basic QWxhZGRpbjpvcGVuIHNlc2FtZQ==".as_ref().into();
+        let auth: Authorization<Credentials> = Header::parse_header(&r).unwrap();
+        let basic: Basic = auth.to_scheme().unwrap();
+        assert_eq!(basic, Basic {
+            username: "Aladdin".to_owned(),
+            password: Some("open sesame".to_owned())
+        });
+        assert!(auth.to_scheme::<Bearer>().is_err());
+
+        let raw: String = auth.to_scheme().unwrap();
+        assert_eq!(raw, "basic QWxhZGRpbjpvcGVuIHNlc2FtZQ==");
+    }
+
+    #[test]
+    fn test_credentials_invalid() {
+        let r: Raw = b"Basic realm=\"unterminated".as_ref().into();
+        let auth: ::Result<Authorization<Credentials>> = Header::parse_header(&r);
+        assert!(auth.is_err());
+    }
 }
 
 bench_header!(raw, Authorization<String>, { vec![b"foo bar baz".to_vec()] });
 bench_header!(basic, Authorization<Basic>, { vec![b"Basic QWxhZGRpbjpuIHNlc2FtZQ==".to_vec()] });
 bench_header!(bearer, Authorization<Bearer>, { vec![b"Bearer fpKL54jvWmEGVoRdCNjG".to_vec()] });
+bench_header!(credentials, Authorization<Credentials>, { vec![b"Bearer fpKL54jvWmEGVoRdCNjG".to_vec()] });
 
 impl<S> ::header::StandardHeader for Authorization<S>
     where S: Scheme + Any
@@ -1,7 +1,9 @@
 use std::fmt;
 use std::str::FromStr;
+use {HeaderError, HeaderErrorKind};
 use header::{Header, RawLike};
-use header::parsing::{from_comma_delimited, fmt_comma_delimited};
+use header::parsing::{diagnose, from_comma_delimited, from_comma_delimited_detailed_with,
+                      fmt_comma_delimited, fmt_quoted, is_token, unquote};
 
 /// `Cache-Control` header, defined in [RFC7234](https://tools.ietf.org/html/rfc7234#section-5.2)
 ///
@@ -22,6 +24,7 @@ use header::parsing::{from_comma_delimited, fmt_comma_delimited};
 /// * `no-cache`
 /// * `private, community="UCI"`
 /// * `max-age=30`
+/// * `no-cache="Set-Cookie, X-Foo"`
 ///
 /// # Examples
 /// ```
@@ -61,6 +64,8 @@ impl Header for CacheControl {
         NAME
     }
 
+    standard_header!(fn CACHE_CONTROL);
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<CacheControl>
     where T: RawLike<'a>
     {
@@ -72,6 +77,23 @@ impl Header for CacheControl {
         }
     }
 
+    fn parse_header_detailed<'a, T>(raw: &'a T) -> Result<CacheControl, HeaderError>
+    where T: RawLike<'a>
+    {
+        // A directive fails with `Some` error for an invalid number
+        let directives = from_comma_delimited_detailed_with(raw, |element| {
+            element.parse().map_err(|e: <CacheDirective as FromStr>::Err| match e {
+                Some(_) => (HeaderErrorKind::InvalidNumber, element.find('=').map_or(0, |i| i + 1)),
+                None => (HeaderErrorKind::Invalid, 0),
+            })
+        }).map_err(|e| e.with_name(Self::header_name()))?;
+        if !directives.is_empty() {
+            Ok(CacheControl(directives))
+        } else {
+            Err(diagnose::<Self, T>(raw))
+        }
+    }
+
     fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
         f.fmt_line(self)
     }
@@ -100,6 +122,8 @@ pub enum CacheDirective {
     MaxAge(u32),
     /// "max-stale=delta"
     MaxStale(u32),
+    /// "max-stale", accepting a response of any staleness
+    MaxStaleAny,
     /// "min-fresh=delta"
     MinFresh(u32),
 
@@ -114,6 +138,20 @@ pub enum CacheDirective {
     ProxyRevalidate,
     /// "s-maxage=delta"
     SMaxAge(u32),
+    /// "no-cache=\"field-name, ...\""
+    NoCacheFields(Vec<String>),
+    /// "private=\"field-name, ...\""
+    PrivateFields(Vec<String>),
+    /// "must-understand"
+    MustUnderstand,
+    /// "immutable", from [RFC8246](https://tools.ietf.org/html/rfc8246)
+    Immutable,
+    /// "stale-while-revalidate=delta", from
+    /// [RFC5861](https://tools.ietf.org/html/rfc5861)
+    StaleWhileRevalidate(u32),
+    /// "stale-if-error=delta", from
+    /// [RFC5861](https://tools.ietf.org/html/rfc5861)
+    StaleIfError(u32),
 
     /// Extension directives. Optionally include an argument.
     Extension(String, Option<String>)
@@ -130,6 +168,7 @@ impl fmt::Display for CacheDirective {
 
             MaxAge(secs) => return write!(f, "max-age={}", secs),
             MaxStale(secs) => return write!(f, "max-stale={}", secs),
+            MaxStaleAny => "max-stale",
             MinFresh(secs) => return write!(f, "min-fresh={}", secs),
 
             MustRevalidate => "must-revalidate",
@@ -137,9 +176,21 @@ impl fmt::Display for CacheDirective {
             Private => "private",
             ProxyRevalidate => "proxy-revalidate",
             SMaxAge(secs) => return write!(f, "s-maxage={}", secs),
+            NoCacheFields(ref fields) => return fmt_fields(f, "no-cache", fields),
+            PrivateFields(ref fields) => return fmt_fields(f, "private", fields),
+            MustUnderstand => "must-understand",
+            Immutable => "immutable",
+            StaleWhileRevalidate(secs) => return write!(f, "stale-while-revalidate={}", secs),
+            StaleIfError(secs) => return write!(f, "stale-if-error={}", secs),
 
             Extension(ref name, None) => &name[..],
-            Extension(ref name, Some(ref arg)) => return write!(f, "{}={}", name, arg),
+            Extension(ref name, Some(ref arg)) if is_token(arg) => {
+                return write!(f, "{}={}", name, arg)
+            }
+            Extension(ref name, Some(ref arg)) => {
+                write!(f, "{}=", name)?;
+                return fmt_quoted(f, arg);
+            }
 
         }, f)
     }
@@ -154,19 +205,36 @@ impl FromStr for CacheDirective {
             "no-store" => Ok(NoStore),
             "no-transform" => Ok(NoTransform),
             "only-if-cached" => Ok(OnlyIfCached),
+            "max-stale" => Ok(MaxStaleAny),
             "must-revalidate" => Ok(MustRevalidate),
             "public" => Ok(Public),
             "private" => Ok(Private),
             "proxy-revalidate" => Ok(ProxyRevalidate),
+            "must-understand" => Ok(MustUnderstand),
+            "immutable" => Ok(Immutable),
             "" => Err(None),
             _ => match s.find('=') {
-                Some(idx) if idx+1 < s.len() => match (&s[..idx], (&s[idx+1..]).trim_matches('"')) {
-                    ("max-age" , secs) => secs.parse().map(MaxAge).map_err(Some),
-                    ("max-stale", secs) => secs.parse().map(MaxStale).map_err(Some),
-                    ("min-fresh", secs) => secs.parse().map(MinFresh).map_err(Some),
-                    ("s-maxage", secs) => secs.parse().map(SMaxAge).map_err(Some),
-                    (left, right) => Ok(Extension(left.to_owned(), Some(right.to_owned())))
-                },
+                Some(idx) if idx+1 < s.len() => {
+                    let value = &s[idx+1..];
+                    let value = if value.starts_with('"') {
+                        unquote(value).map_err(|_| None)?
+                    } else {
+                        value.to_owned()
+                    };
+                    match (&s[..idx], value) {
+                        ("max-age" , secs) => delta_seconds(&secs).map(MaxAge),
+                        ("max-stale", secs) => delta_seconds(&secs).map(MaxStale),
+                        ("min-fresh", secs) => delta_seconds(&secs).map(MinFresh),
+                        ("s-maxage", secs) => delta_seconds(&secs).map(SMaxAge),
+                        ("stale-while-revalidate", secs) => {
+                            delta_seconds(&secs).map(StaleWhileRevalidate)
+                        }
+                        ("stale-if-error", secs) => delta_seconds(&secs).map(StaleIfError),
+                        ("no-cache", fields) => Ok(NoCacheFields(field_names(&fields))),
+                        ("private", fields) => Ok(PrivateFields(field_names(&fields))),
+                        (left, right) => Ok(Extension(left.to_owned(), Some(right)))
+                    }
+                }
                 Some(_) => Err(None),
                 None => Ok(Extension(s.to_owned(), None))
             }
@@ -174,6 +242,37 @@ impl FromStr for CacheDirective {
     }
 }
 
+// The largest delta-seconds value, 2^31, to which greater values saturate.
+const MAX_DELTA_SECONDS: u32 = 1 << 31;
+
+// Parse delta-seconds, saturating at 2^31.
+fn delta_seconds(s: &str) -> Result<u32, Option<<u32 as FromStr>::Err>> {
+    if !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit()) {
+        Ok(s.parse::<u32>().map_or(MAX_DELTA_SECONDS, |n| n.min(MAX_DELTA_SECONDS)))
+    } else {
+        s.parse().map_err(Some)
+    }
+}
+
+fn field_names(s: &str) -> Vec<String> {
+    s.split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+fn fmt_fields(f: &mut fmt::Formatter, name: &str, fields: &[String]) -> fmt::Result {
+    write!(f, "{}=\"", name)?;
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
+        }
+        f.write_str(field)?;
+    }
+    f.write_str("\"")
+}
+
 #[cfg(test)]
 mod tests {
     use header::{Header, Raw};
@@ -211,6 +310,67 @@ mod tests {
             CacheDirective::Extension("bar".to_owned(), Some("baz".to_owned()))])))
     }
 
+    #[test]
+    fn test_parse_field_names() {
+        let r: Raw = vec![b"no-cache=\"Set-Cookie, X-Foo\", private=\"Authorization\"".to_vec()]
+            .into();
+        let cache: CacheControl = Header::parse_header(&r).unwrap();
+        assert_eq!(cache, CacheControl(vec![
+            CacheDirective::NoCacheFields(vec!["Set-Cookie".to_owned(), "X-Foo".to_owned()]),
+            CacheDirective::PrivateFields(vec!["Authorization".to_owned()])]));
+        assert_eq!(cache.to_string(), "no-cache=\"Set-Cookie, X-Foo\", private=\"Authorization\"");
+
+        let r: Raw = vec![b"no-cache=Set-Cookie".to_vec()].into();
+        let cache: CacheControl = Header::parse_header(&r).unwrap();
+        assert_eq!(cache, CacheControl(vec![
+            CacheDirective::NoCacheFields(vec!["Set-Cookie".to_owned()])]));
+    }
+
+    #[test]
+    fn test_parse_extensions() {
+        let r: Raw = vec![
+            b"immutable, must-understand, stale-while-revalidate=30, stale-if-error=\"60\""
+                .to_vec()].into();
+        let cache: CacheControl = Header::parse_header(&r).unwrap();
+        assert_eq!(cache, CacheControl(vec![
+            CacheDirective::Immutable,
+            CacheDirective::MustUnderstand,
+            CacheDirective::StaleWhileRevalidate(30),
+            CacheDirective::StaleIfError(60)]));
+        assert_eq!(cache.to_string(),
+                   "immutable, must-understand, stale-while-revalidate=30, stale-if-error=60");
+    }
+
+    #[test]
+    fn test_parse_max_stale() {
+        let r: Raw = vec![b"max-stale, max-stale=60".to_vec()].into();
+        let cache: CacheControl = Header::parse_header(&r).unwrap();
+        assert_eq!(cache, CacheControl(vec![
+            CacheDirective::MaxStaleAny,
+            CacheDirective::MaxStale(60)]));
+        assert_eq!(cache.to_string(), "max-stale, max-stale=60");
+    }
+
+    #[test]
+    fn test_delta_seconds_saturate() {
+        let r: Raw = vec![b"max-age=2147483649, s-maxage=99999999999999999999".to_vec()].into();
+        let cache: CacheControl = Header::parse_header(&r).unwrap();
+        assert_eq!(cache, CacheControl(vec![
+            CacheDirective::MaxAge(2147483648),
+            CacheDirective::SMaxAge(2147483648)]));
+        assert!("max-age=-1".parse::<CacheDirective>().is_err());
+        assert!("max-age=1a".parse::<CacheDirective>().is_err());
+    }
+
+    #[test]
+    fn test_quoted_extension() {
+        let r: Raw = vec![b"community=\"UCI, Irvine\"".to_vec()].into();
+        let cache: CacheControl = Header::parse_header(&r).unwrap();
+        assert_eq!(cache, CacheControl(vec![
+            CacheDirective::Extension("community".to_owned(), Some("UCI, Irvine".to_owned()))]));
+        assert_eq!(cache.to_string(), "community=\"UCI, Irvine\"");
+    }
+
     #[test]
     fn test_parse_bad_syntax() {
         let r: Raw = vec![b"foo=".to_vec()].into();
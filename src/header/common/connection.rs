@@ -98,7 +98,7 @@ header! {
     /// );
     /// # }
     /// ```
-    (Connection, "Connection") => (ConnectionOption)+
+    (Connection, "Connection", CONNECTION) => (ConnectionOption)+
 
     test_connection {
         test_header!(test1, vec![b"close"]);
@@ -146,5 +146,3 @@ mod tests {
             parse_option(b"upgrade".to_vec()));
     }
 }
-
-standard_header!(Connection, CONNECTION);
@@ -96,6 +96,8 @@ impl Header for ContentDisposition {
         NAME
     }
 
+    standard_header!(fn CONTENT_DISPOSITION);
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<ContentDisposition>
     where T: RawLike<'a>
     {
@@ -44,7 +44,7 @@ header! {
     ///     ])
     /// );
     /// ```
-    (ContentEncoding, "Content-Encoding") => (Encoding)+
+    (ContentEncoding, "Content-Encoding", CONTENT_ENCODING) => (Encoding)+
 
     test_content_encoding {
         // Testcase from the RFC
@@ -52,7 +52,5 @@ header! {
     }
 }
 
-standard_header!(ContentEncoding, CONTENT_ENCODING);
-
 bench_header!(single, ContentEncoding, { vec![b"gzip".to_vec()] });
 bench_header!(multiple, ContentEncoding, { vec![b"gzip, deflate".to_vec()] });
@@ -1,5 +1,7 @@
+use std::iter::FromIterator;
+
 use language_tags::LanguageTag;
-use header::QualityItem;
+use header::{QualityItem, qitem};
 
 header! {
     /// `Content-Language` header, defined in
@@ -55,7 +57,7 @@ header! {
     /// );
     /// # }
     /// ```
-    (ContentLanguage, "Content-Language") => (QualityItem<LanguageTag>)+
+    (ContentLanguage, "Content-Language", CONTENT_LANGUAGE) => (QualityItem<LanguageTag>)+
 
     test_content_language {
         test_header!(test1, vec![b"da"]);
@@ -63,4 +65,14 @@ header! {
     }
 }
 
-standard_header!(ContentLanguage, CONTENT_LANGUAGE);
+impl From<LanguageTag> for ContentLanguage {
+    fn from(tag: LanguageTag) -> ContentLanguage {
+        ContentLanguage(vec![qitem(tag)])
+    }
+}
+
+impl FromIterator<LanguageTag> for ContentLanguage {
+    fn from_iter<I: IntoIterator<Item = LanguageTag>>(tags: I) -> ContentLanguage {
+        ContentLanguage(tags.into_iter().map(qitem).collect())
+    }
+}
@@ -1,5 +1,6 @@
 use std::fmt;
 
+use {HeaderError, HeaderErrorKind};
 use header::{Header, RawLike, parsing};
 
 /// `Content-Length` header, defined in
@@ -50,6 +51,8 @@ impl Header for ContentLength {
         NAME
     }
 
+    standard_header!(fn CONTENT_LENGTH);
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<ContentLength>
     where T: RawLike<'a>
     {
@@ -70,6 +73,25 @@ impl Header for ContentLength {
             .map(ContentLength)
     }
 
+    fn parse_header_detailed<'a, T>(raw: &'a T) -> Result<ContentLength, HeaderError>
+    where T: RawLike<'a>
+    {
+        let error = |e: HeaderError| e.with_name(Self::header_name());
+        let mut value = None;
+        for line in raw.iter() {
+            let x = parsing::from_raw_number_detailed(line).map_err(error)?;
+            match value {
+                Some(prev) if prev != x => {
+                    return Err(error(HeaderError::new(HeaderErrorKind::MultipleValues)
+                        .with_value(line, None)));
+                }
+                _ => value = Some(x),
+            }
+        }
+        value.map(ContentLength)
+            .ok_or_else(|| error(HeaderError::new(HeaderErrorKind::Missing)))
+    }
+
     #[inline]
     fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
         f.danger_fmt_line_without_newline_replacer(self)
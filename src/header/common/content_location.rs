@@ -39,7 +39,7 @@ header! {
     /// headers.encode(&ContentLocation("http://www.example.org/hypertext/Overview.html".to_owned()));
     /// ```
     // TODO: use URL
-    (ContentLocation, "Content-Location") => [String]
+    (ContentLocation, "Content-Location", CONTENT_LOCATION) => [String]
 
     test_content_location {
         test_header!(partial_query, vec![b"/hypertext/Overview.html?q=tim"]);
@@ -47,5 +47,3 @@ header! {
         test_header!(absolute, vec![b"http://www.example.org/hypertext/Overview.html"]);
     }
 }
-
-standard_header!(ContentLocation, CONTENT_LOCATION);
@@ -0,0 +1,678 @@
+use std::fmt;
+use std::str::{FromStr, from_utf8};
+
+use header::{Header, RawLike};
+use header::parsing::fmt_comma_delimited;
+
+/// `Content-Security-Policy` header, defined in
+/// [CSP3](https://www.w3.org/TR/CSP3/#csp-header)
+///
+/// The `Content-Security-Policy` header field delivers one or more policies
+/// restricting the resources a document may load or execute. Each policy is
+/// enforced independently, so policies from multiple field lines, or
+/// separated by commas, are kept separate rather than merged.
+///
+/// # ABNF
+///
+/// ```text
+/// Content-Security-Policy = 1#serialized-policy
+/// serialized-policy = serialized-directive *( OWS ";" [ OWS serialized-directive ] )
+/// serialized-directive = directive-name [ required-ascii-whitespace directive-value ]
+/// ```
+///
+/// # Example values
+///
+/// * `default-src 'self'`
+/// * `script-src 'self' 'nonce-2726c7f26c' https://cdn.example.com; object-src 'none'`
+///
+/// # Examples
+///
+/// ```
+/// # extern crate http;
+/// use hyperx::header::{
+///     ContentSecurityPolicy, CspDirective, CspKeyword, CspPolicy, CspSource, TypedHeaders
+/// };
+///
+/// let mut headers = http::HeaderMap::new();
+/// headers.encode(&ContentSecurityPolicy(vec![
+///     CspPolicy::new()
+///         .with_directive(CspDirective::sources("default-src", vec![
+///             CspSource::Keyword(CspKeyword::SelfOrigin),
+///             "https://cdn.example.com".parse().unwrap(),
+///         ]))
+///         .with_directive(CspDirective::sources("object-src", vec![]))
+///         .with_directive(CspDirective::tokens("report-uri", vec!["/csp-report".to_owned()])),
+/// ]));
+/// assert_eq!(
+///     headers["content-security-policy"],
+///     "default-src 'self' https://cdn.example.com; object-src 'none'; report-uri /csp-report"
+/// );
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct ContentSecurityPolicy(pub Vec<CspPolicy>);
+
+__hyper__deref!(ContentSecurityPolicy => Vec<CspPolicy>);
+
+impl Header for ContentSecurityPolicy {
+    fn header_name() -> &'static str {
+        static NAME: &str = "Content-Security-Policy";
+        NAME
+    }
+
+    standard_header!(fn CONTENT_SECURITY_POLICY);
+
+    fn parse_header<'a, T>(raw: &'a T) -> ::Result<ContentSecurityPolicy>
+    where T: RawLike<'a>
+    {
+        parse_policies(raw).map(ContentSecurityPolicy)
+    }
+
+    fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
+        for policy in &self.0 {
+            f.fmt_line(policy)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ContentSecurityPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_comma_delimited(f, &self[..])
+    }
+}
+
+/// `Content-Security-Policy-Report-Only` header, defined in
+/// [CSP3](https://www.w3.org/TR/CSP3/#cspro-header)
+///
+/// The `Content-Security-Policy-Report-Only` header field delivers policies
+/// which are monitored, with violations reported, but not enforced. It has
+/// the same syntax as `Content-Security-Policy`.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate http;
+/// use hyperx::header::{ContentSecurityPolicyReportOnly, TypedHeaders};
+///
+/// let mut headers = http::HeaderMap::new();
+/// headers.insert(
+///     "content-security-policy-report-only",
+///     "script-src 'self'; report-to csp-endpoint".parse().unwrap()
+/// );
+/// let csp: ContentSecurityPolicyReportOnly = headers.decode().unwrap();
+/// assert_eq!(csp[0].directive("report-to").unwrap().to_string(), "report-to csp-endpoint");
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct ContentSecurityPolicyReportOnly(pub Vec<CspPolicy>);
+
+__hyper__deref!(ContentSecurityPolicyReportOnly => Vec<CspPolicy>);
+
+impl Header for ContentSecurityPolicyReportOnly {
+    fn header_name() -> &'static str {
+        static NAME: &str = "Content-Security-Policy-Report-Only";
+        NAME
+    }
+
+    standard_header!(fn CONTENT_SECURITY_POLICY_REPORT_ONLY);
+
+    fn parse_header<'a, T>(raw: &'a T) -> ::Result<ContentSecurityPolicyReportOnly>
+    where T: RawLike<'a>
+    {
+        parse_policies(raw).map(ContentSecurityPolicyReportOnly)
+    }
+
+    fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
+        for policy in &self.0 {
+            f.fmt_line(policy)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ContentSecurityPolicyReportOnly {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_comma_delimited(f, &self[..])
+    }
+}
+
+/// A single Content Security Policy: an ordered list of directives.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct CspPolicy(pub Vec<CspDirective>);
+
+__hyper__deref!(CspPolicy => Vec<CspDirective>);
+
+impl CspPolicy {
+    /// Create an empty policy.
+    pub fn new() -> CspPolicy {
+        CspPolicy(Vec::new())
+    }
+
+    /// Return this policy with the directive appended.
+    pub fn with_directive(mut self, directive: CspDirective) -> CspPolicy {
+        self.0.push(directive);
+        self
+    }
+
+    /// Return the directive with the given name, compared
+    /// case-insensitively.
+    pub fn directive(&self, name: &str) -> Option<&CspDirective> {
+        self.0.iter().find(|d| d.name.eq_ignore_ascii_case(name))
+    }
+}
+
+impl fmt::Display for CspPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, directive) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str("; ")?;
+            }
+            fmt::Display::fmt(directive, f)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for CspPolicy {
+    type Err = ::Error;
+
+    /// Parse a single serialized policy. Later duplicates of a directive
+    /// are ignored, as are invalid source expressions in a source list.
+    fn from_str(s: &str) -> ::Result<CspPolicy> {
+        if s.contains(',') {
+            return Err(::Error::Header);
+        }
+        let mut policy = CspPolicy::new();
+        for token in s.split(';') {
+            let token = token.trim_matches(is_ascii_whitespace);
+            if token.is_empty() {
+                continue;
+            }
+            let mut parts = token.split(is_ascii_whitespace).filter(|p| !p.is_empty());
+            let name = parts.next().unwrap().to_ascii_lowercase();
+            if !name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-') {
+                return Err(::Error::Header);
+            }
+            if policy.directive(&name).is_some() {
+                continue;
+            }
+            let value = if is_source_list_directive(&name) {
+                CspDirectiveValue::Sources(
+                    parts.filter(|p| !p.eq_ignore_ascii_case("'none'"))
+                        .filter_map(|p| p.parse().ok())
+                        .collect())
+            } else {
+                CspDirectiveValue::Tokens(parts.map(|p| p.to_owned()).collect())
+            };
+            policy.0.push(CspDirective { name, value });
+        }
+        Ok(policy)
+    }
+}
+
+/// A directive of a Content Security Policy.
+#[derive(Clone, PartialEq, Debug)]
+pub struct CspDirective {
+    /// The directive name, such as `script-src`, in lowercase.
+    pub name: String,
+    /// The directive value.
+    pub value: CspDirectiveValue,
+}
+
+impl CspDirective {
+    /// Create a directive with a source list value.
+    pub fn sources<N: Into<String>>(name: N, sources: Vec<CspSource>) -> CspDirective {
+        CspDirective {
+            name: name.into(),
+            value: CspDirectiveValue::Sources(sources),
+        }
+    }
+
+    /// Create a directive with a value of other tokens.
+    pub fn tokens<N: Into<String>>(name: N, tokens: Vec<String>) -> CspDirective {
+        CspDirective {
+            name: name.into(),
+            value: CspDirectiveValue::Tokens(tokens),
+        }
+    }
+
+    /// Return the source list, if this directive has one.
+    pub fn source_list(&self) -> Option<&[CspSource]> {
+        match self.value {
+            CspDirectiveValue::Sources(ref sources) => Some(sources),
+            CspDirectiveValue::Tokens(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for CspDirective {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.name)?;
+        match self.value {
+            CspDirectiveValue::Sources(ref sources) if sources.is_empty() => {
+                f.write_str(" 'none'")
+            }
+            CspDirectiveValue::Sources(ref sources) => {
+                for source in sources {
+                    write!(f, " {}", source)?;
+                }
+                Ok(())
+            }
+            CspDirectiveValue::Tokens(ref tokens) => {
+                for token in tokens {
+                    write!(f, " {}", token)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// The value of a Content Security Policy directive.
+#[derive(Clone, PartialEq, Debug)]
+pub enum CspDirectiveValue {
+    /// A source list, as used by fetch directives such as `default-src`,
+    /// and by `base-uri`, `form-action` and `frame-ancestors`. An empty list
+    /// is formatted as `'none'`.
+    Sources(Vec<CspSource>),
+    /// The space separated tokens of any other directive, such as
+    /// `sandbox` or `report-uri`.
+    Tokens(Vec<String>),
+}
+
+/// A source expression of a Content Security Policy source list.
+#[derive(Clone, PartialEq, Debug)]
+pub enum CspSource {
+    /// A keyword, such as `'self'`.
+    Keyword(CspKeyword),
+    /// A nonce, as `'nonce-<base64-value>'`.
+    Nonce(String),
+    /// A hash, as `'<algorithm>-<base64-value>'`.
+    Hash(CspHashAlgorithm, String),
+    /// A scheme, such as `https:`, stored without the colon.
+    Scheme(String),
+    /// A host, with optional scheme, port and path.
+    Host(CspHostSource),
+}
+
+impl fmt::Display for CspSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CspSource::Keyword(ref keyword) => fmt::Display::fmt(keyword, f),
+            CspSource::Nonce(ref nonce) => write!(f, "'nonce-{}'", nonce),
+            CspSource::Hash(ref algorithm, ref hash) => write!(f, "'{}-{}'", algorithm, hash),
+            CspSource::Scheme(ref scheme) => write!(f, "{}:", scheme),
+            CspSource::Host(ref host) => fmt::Display::fmt(host, f),
+        }
+    }
+}
+
+impl FromStr for CspSource {
+    type Err = ::Error;
+
+    fn from_str(s: &str) -> ::Result<CspSource> {
+        if s.len() > 2 && s.starts_with('\'') && s.ends_with('\'') {
+            let inner = &s[1..s.len() - 1];
+            if let Some(dash) = inner.find('-') {
+                let (prefix, value) = (&inner[..dash], &inner[dash + 1..]);
+                if is_base64_value(value) {
+                    if prefix.eq_ignore_ascii_case("nonce") {
+                        return Ok(CspSource::Nonce(value.to_owned()));
+                    }
+                    if let Ok(algorithm) = prefix.parse() {
+                        return Ok(CspSource::Hash(algorithm, value.to_owned()));
+                    }
+                }
+            }
+            return inner.parse().map(CspSource::Keyword);
+        }
+        if s.ends_with(':') && is_scheme(&s[..s.len() - 1]) {
+            return Ok(CspSource::Scheme(s[..s.len() - 1].to_owned()));
+        }
+        s.parse().map(CspSource::Host)
+    }
+}
+
+/// A keyword source expression, formatted with single quotes.
+#[derive(Clone, PartialEq, Debug)]
+pub enum CspKeyword {
+    /// `'self'`
+    SelfOrigin,
+    /// `'unsafe-inline'`
+    UnsafeInline,
+    /// `'unsafe-eval'`
+    UnsafeEval,
+    /// `'strict-dynamic'`
+    StrictDynamic,
+    /// `'unsafe-hashes'`
+    UnsafeHashes,
+    /// `'report-sample'`
+    ReportSample,
+    /// `'unsafe-allow-redirects'`
+    UnsafeAllowRedirects,
+    /// `'wasm-unsafe-eval'`
+    WasmUnsafeEval,
+    /// Some other keyword, without quotes
+    Ext(String),
+}
+
+impl fmt::Display for CspKeyword {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "'{}'", match *self {
+            CspKeyword::SelfOrigin => "self",
+            CspKeyword::UnsafeInline => "unsafe-inline",
+            CspKeyword::UnsafeEval => "unsafe-eval",
+            CspKeyword::StrictDynamic => "strict-dynamic",
+            CspKeyword::UnsafeHashes => "unsafe-hashes",
+            CspKeyword::ReportSample => "report-sample",
+            CspKeyword::UnsafeAllowRedirects => "unsafe-allow-redirects",
+            CspKeyword::WasmUnsafeEval => "wasm-unsafe-eval",
+            CspKeyword::Ext(ref s) => s,
+        })
+    }
+}
+
+impl FromStr for CspKeyword {
+    type Err = ::Error;
+
+    /// Parse a keyword, without the surrounding quotes.
+    fn from_str(s: &str) -> ::Result<CspKeyword> {
+        Ok(match &s.to_ascii_lowercase()[..] {
+            "self" => CspKeyword::SelfOrigin,
+            "unsafe-inline" => CspKeyword::UnsafeInline,
+            "unsafe-eval" => CspKeyword::UnsafeEval,
+            "strict-dynamic" => CspKeyword::StrictDynamic,
+            "unsafe-hashes" => CspKeyword::UnsafeHashes,
+            "report-sample" => CspKeyword::ReportSample,
+            "unsafe-allow-redirects" => CspKeyword::UnsafeAllowRedirects,
+            "wasm-unsafe-eval" => CspKeyword::WasmUnsafeEval,
+            _ if !s.is_empty() && !s.contains('\'') => CspKeyword::Ext(s.to_owned()),
+            _ => return Err(::Error::Header),
+        })
+    }
+}
+
+/// The hash algorithm of a hash source expression.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CspHashAlgorithm {
+    /// `sha256`
+    Sha256,
+    /// `sha384`
+    Sha384,
+    /// `sha512`
+    Sha512,
+}
+
+impl fmt::Display for CspHashAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            CspHashAlgorithm::Sha256 => "sha256",
+            CspHashAlgorithm::Sha384 => "sha384",
+            CspHashAlgorithm::Sha512 => "sha512",
+        })
+    }
+}
+
+impl FromStr for CspHashAlgorithm {
+    type Err = ::Error;
+
+    fn from_str(s: &str) -> ::Result<CspHashAlgorithm> {
+        match &s.to_ascii_lowercase()[..] {
+            "sha256" => Ok(CspHashAlgorithm::Sha256),
+            "sha384" => Ok(CspHashAlgorithm::Sha384),
+            "sha512" => Ok(CspHashAlgorithm::Sha512),
+            _ => Err(::Error::Header),
+        }
+    }
+}
+
+/// A host source expression, such as `https://*.example.com:443/path`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct CspHostSource {
+    /// The scheme, without `://`.
+    pub scheme: Option<String>,
+    /// The host, which may be `*` or start with a `*.` wildcard.
+    pub host: String,
+    /// The port, as digits or `*`.
+    pub port: Option<String>,
+    /// The path, starting with `/`.
+    pub path: Option<String>,
+}
+
+impl CspHostSource {
+    /// Create a host source expression without scheme, port or path.
+    pub fn new<H: Into<String>>(host: H) -> CspHostSource {
+        CspHostSource {
+            scheme: None,
+            host: host.into(),
+            port: None,
+            path: None,
+        }
+    }
+}
+
+impl fmt::Display for CspHostSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(ref scheme) = self.scheme {
+            write!(f, "{}://", scheme)?;
+        }
+        f.write_str(&self.host)?;
+        if let Some(ref port) = self.port {
+            write!(f, ":{}", port)?;
+        }
+        if let Some(ref path) = self.path {
+            f.write_str(path)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for CspHostSource {
+    type Err = ::Error;
+
+    fn from_str(s: &str) -> ::Result<CspHostSource> {
+        let (scheme, rest) = match s.find("://") {
+            Some(i) if is_scheme(&s[..i]) => (Some(s[..i].to_owned()), &s[i + 3..]),
+            Some(_) => return Err(::Error::Header),
+            None => (None, s),
+        };
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], Some(rest[i..].to_owned())),
+            None => (rest, None),
+        };
+        let (host, port) = match authority.find(':') {
+            Some(i) => {
+                let port = &authority[i + 1..];
+                if port != "*" && (port.is_empty() || !port.bytes().all(|b| b.is_ascii_digit())) {
+                    return Err(::Error::Header);
+                }
+                (&authority[..i], Some(port.to_owned()))
+            }
+            None => (authority, None),
+        };
+        if !is_host(host) {
+            return Err(::Error::Header);
+        }
+        if let Some(ref path) = path {
+            if path.bytes().any(|b| b == b';' || b == b',' || b == b'?' || b == b'#') {
+                return Err(::Error::Header);
+            }
+        }
+        Ok(CspHostSource {
+            scheme,
+            host: host.to_owned(),
+            port,
+            path,
+        })
+    }
+}
+
+fn parse_policies<'a, T>(raw: &'a T) -> ::Result<Vec<CspPolicy>>
+    where T: RawLike<'a>
+{
+    let mut policies = Vec::new();
+    for line in raw.iter() {
+        for serialized in from_utf8(line)?.split(',') {
+            let policy: CspPolicy = serialized.parse()?;
+            if !policy.is_empty() {
+                policies.push(policy);
+            }
+        }
+    }
+    if policies.is_empty() {
+        Err(::Error::Header)
+    } else {
+        Ok(policies)
+    }
+}
+
+fn is_source_list_directive(name: &str) -> bool {
+    matches!(name,
+        "default-src" | "child-src" | "connect-src" | "font-src" | "frame-src" |
+        "img-src" | "manifest-src" | "media-src" | "object-src" | "prefetch-src" |
+        "script-src" | "script-src-elem" | "script-src-attr" |
+        "style-src" | "style-src-elem" | "style-src-attr" | "worker-src" |
+        "base-uri" | "form-action" | "frame-ancestors" | "navigate-to")
+}
+
+fn is_ascii_whitespace(c: char) -> bool {
+    matches!(c, '\t' | '\n' | '\x0C' | '\r' | ' ')
+}
+
+fn is_scheme(s: &str) -> bool {
+    let mut bytes = s.bytes();
+    bytes.next().map_or(false, |b| b.is_ascii_alphabetic()) &&
+        bytes.all(|b| b.is_ascii_alphanumeric() || b == b'+' || b == b'-' || b == b'.')
+}
+
+fn is_host(s: &str) -> bool {
+    if s == "*" {
+        return true;
+    }
+    let s = s.strip_prefix("*.").unwrap_or(s);
+    let s = s.strip_suffix('.').unwrap_or(s);
+    !s.is_empty() && s.split('.').all(|label| {
+        !label.is_empty() && label.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-')
+    })
+}
+
+fn is_base64_value(s: &str) -> bool {
+    let value = s.trim_end_matches('=');
+    s.len() - value.len() <= 2 && !value.is_empty() &&
+        value.bytes().all(|b| b.is_ascii_alphanumeric() || b"+/-_".contains(&b))
+}
+
+#[cfg(test)]
+mod tests {
+    use header::{Header, Raw};
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let r: Raw = "default-src 'self'; img-src *; \
+                      script-src 'SELF' 'nonce-2726c7f26c' \
+                      'sha256-2NkEG2Q0fmZoEyKB+3nKLR+s9oaKPj9m/YMh1P3PV/E=' \
+                      https: *.cdn.example.com https://example.com:8080/js/; \
+                      sandbox allow-scripts allow-forms; upgrade-insecure-requests".into();
+        let csp: ContentSecurityPolicy = Header::parse_header(&r).unwrap();
+        assert_eq!(csp.len(), 1);
+        let policy = &csp[0];
+        assert_eq!(policy.len(), 5);
+        assert_eq!(policy.directive("default-src").unwrap().source_list(),
+                   Some(&[CspSource::Keyword(CspKeyword::SelfOrigin)][..]));
+        assert_eq!(policy.directive("img-src").unwrap().source_list(),
+                   Some(&[CspSource::Host(CspHostSource::new("*"))][..]));
+        assert_eq!(policy.directive("script-src").unwrap().source_list().unwrap(), &[
+            CspSource::Keyword(CspKeyword::SelfOrigin),
+            CspSource::Nonce("2726c7f26c".to_owned()),
+            CspSource::Hash(CspHashAlgorithm::Sha256,
+                            "2NkEG2Q0fmZoEyKB+3nKLR+s9oaKPj9m/YMh1P3PV/E=".to_owned()),
+            CspSource::Scheme("https".to_owned()),
+            CspSource::Host(CspHostSource::new("*.cdn.example.com")),
+            CspSource::Host(CspHostSource {
+                scheme: Some("https".to_owned()),
+                host: "example.com".to_owned(),
+                port: Some("8080".to_owned()),
+                path: Some("/js/".to_owned()),
+            }),
+        ][..]);
+        assert_eq!(policy.directive("SANDBOX").unwrap().value, CspDirectiveValue::Tokens(vec![
+            "allow-scripts".to_owned(), "allow-forms".to_owned()
+        ]));
+        assert_eq!(policy.directive("upgrade-insecure-requests").unwrap().value,
+                   CspDirectiveValue::Tokens(vec![]));
+    }
+
+    #[test]
+    fn test_multiple_policies() {
+        let r: Raw = vec![
+            b"default-src 'self', script-src 'none'".to_vec(),
+            b"  ; ; ".to_vec(),
+            b"img-src https:".to_vec(),
+        ].into();
+        let csp: ContentSecurityPolicy = Header::parse_header(&r).unwrap();
+        assert_eq!(csp.len(), 3);
+        assert_eq!(csp[1].directive("script-src").unwrap().source_list(), Some(&[][..]));
+        assert_eq!(
+            csp.to_string(),
+            "default-src 'self', script-src 'none', img-src https:");
+    }
+
+    #[test]
+    fn test_duplicate_directive_ignored() {
+        let policy: CspPolicy = "script-src a.com; Script-Src b.com".parse().unwrap();
+        assert_eq!(policy.len(), 1);
+        assert_eq!(policy.to_string(), "script-src a.com");
+    }
+
+    #[test]
+    fn test_invalid_sources_ignored() {
+        let policy: CspPolicy = "script-src 'self' 'none' https://a.com?q \
+                                 'sha1-abc' http://:80 'foo'".parse().unwrap();
+        assert_eq!(policy.to_string(), "script-src 'self' 'sha1-abc' 'foo'");
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for bad in &["", " ; ", "script_src 'self'"] {
+            let r: Raw = (*bad).into();
+            let csp: ::Result<ContentSecurityPolicy> = Header::parse_header(&r);
+            assert!(csp.is_err(), "{:?}", bad);
+        }
+        assert!("a, b".parse::<CspPolicy>().is_err());
+    }
+
+    #[test]
+    fn test_report_only() {
+        let r: Raw = "default-src https:; report-uri /csp /csp2".into();
+        let csp: ContentSecurityPolicyReportOnly = Header::parse_header(&r).unwrap();
+        assert_eq!(csp[0].directive("report-uri").unwrap().value, CspDirectiveValue::Tokens(vec![
+            "/csp".to_owned(), "/csp2".to_owned()
+        ]));
+        assert_eq!(csp.to_string(), "default-src https:; report-uri /csp /csp2");
+    }
+
+    #[cfg(feature = "headers")]
+    #[test]
+    fn test_fmt_lines() {
+        use header::Headers;
+        let mut headers = Headers::new();
+        headers.set(ContentSecurityPolicy(vec![
+            CspPolicy::new().with_directive(CspDirective::sources("default-src", vec![
+                CspSource::Keyword(CspKeyword::SelfOrigin),
+            ])),
+            CspPolicy::new().with_directive(CspDirective::sources("frame-ancestors", vec![])),
+        ]));
+        assert_eq!(
+            headers.to_string(),
+            "Content-Security-Policy: default-src 'self'\r\n\
+             Content-Security-Policy: frame-ancestors 'none'\r\n");
+    }
+}
+
+bench_header!(bench, ContentSecurityPolicy, {
+    vec![b"default-src 'self'; script-src 'self' 'nonce-2726c7f26c' https://cdn.example.com; \
+           object-src 'none'".to_vec()]
+});
+
+standard_header!(ContentSecurityPolicy, CONTENT_SECURITY_POLICY);
+standard_header!(ContentSecurityPolicyReportOnly, CONTENT_SECURITY_POLICY_REPORT_ONLY);
@@ -51,7 +51,7 @@ header! {
     ///     &ContentType(mime::TEXT_HTML)
     /// );
     /// ```
-    (ContentType, "Content-Type") => danger [Mime]
+    (ContentType, "Content-Type", CONTENT_TYPE) => danger [Mime]
 
     test_content_type {
         test_header!(
@@ -127,5 +127,3 @@ impl ContentType {
 impl Eq for ContentType {}
 
 bench_header!(bench, ContentType, { vec![b"application/json".to_vec()] });
-
-standard_header!(ContentType, CONTENT_TYPE);
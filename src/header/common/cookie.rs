@@ -120,6 +120,8 @@ impl Header for Cookie {
         NAME
     }
 
+    standard_header!(fn COOKIE);
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<Cookie>
     where T: RawLike<'a>
     {
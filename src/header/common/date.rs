@@ -26,7 +26,7 @@ header! {
     /// let mut headers = http::HeaderMap::new();
     /// headers.encode(&Date(SystemTime::now().into()));
     /// ```
-    (Date, "Date") => [HttpDate]
+    (Date, "Date", DATE) => date [HttpDate]
 
     test_date {
         test_header!(test1, vec![b"Tue, 15 Nov 1994 08:12:31 GMT"]);
@@ -36,5 +36,3 @@ header! {
 bench_header!(imf_fixdate, Date, { vec![b"Mon, 07 Nov 1994 08:48:37 GMT".to_vec()] });
 bench_header!(rfc_850, Date, { vec![b"Sunday, 06-Nov-94 08:49:37 GMT".to_vec()] });
 bench_header!(asctime, Date, { vec![b"Sun Nov  6 08:49:37 1994".to_vec()] });
-
-standard_header!(Date, DATE);
@@ -0,0 +1,758 @@
+use std::fmt;
+use std::str::FromStr;
+
+use header::Charset;
+use header::parsing::{fmt_quoted, http_percent_encode, parse_extended_value};
+#[cfg(feature = "digest")]
+use method::Method;
+use super::authorization::Scheme;
+use super::www_authenticate::{Challenge, parse_auth_params};
+
+/// Credential holder for Digest Authentication, defined in
+/// [RFC7616](https://tools.ietf.org/html/rfc7616#section-3.4)
+///
+/// Parameter values are stored unquoted. The `response` is not computed
+/// when formatting; with the `digest` feature enabled, use
+/// `compute_response` to obtain it, and `verify_response` to check it on
+/// the server side.
+///
+/// # ABNF
+///
+/// ```text
+/// credentials      = "Digest" digest-response
+/// digest-response  = 1#( username / realm / nonce / digest-uri
+///                    / response / [ algorithm ] / [ cnonce ]
+///                    / [ opaque ] / [ message-qop ]
+///                    / [ nonce-count ] / [ userhash ] / [ auth-param ] )
+/// ```
+///
+/// # Examples
+///
+/// ```
+/// # extern crate http;
+/// use hyperx::header::{Authorization, Digest, DigestAlgorithm, DigestQop, TypedHeaders};
+///
+/// let mut headers = http::HeaderMap::new();
+/// headers.encode(&Authorization(Digest {
+///     username: "Mufasa".to_owned(),
+///     realm: "http-auth@example.org".to_owned(),
+///     nonce: "7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v".to_owned(),
+///     uri: "/dir/index.html".to_owned(),
+///     response: "8ca523f5e9506fed4657c9700eebdbec".to_owned(),
+///     algorithm: Some(DigestAlgorithm::Md5),
+///     qop: Some(DigestQop::Auth),
+///     nc: Some(1),
+///     cnonce: Some("f2/wE4q74E6zIJEtWaHKaf5wv/H5QzzpXusqGemxURZJ".to_owned()),
+///     ..Default::default()
+/// }));
+///
+/// let auth: Authorization<Digest> = headers.decode().unwrap();
+/// assert_eq!(auth.username, "Mufasa");
+/// assert_eq!(auth.nc, Some(1));
+/// ```
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Digest {
+    /// The user's name, or its hash if `userhash` is set.
+    pub username: String,
+    /// The protection space, as given in the challenge.
+    pub realm: String,
+    /// The server nonce, as given in the challenge.
+    pub nonce: String,
+    /// The effective request URI.
+    pub uri: String,
+    /// The hex encoded digest proving the user knows the password.
+    pub response: String,
+    /// The algorithm used to compute the digest, MD5 if absent.
+    pub algorithm: Option<DigestAlgorithm>,
+    /// The quality of protection applied.
+    pub qop: Option<DigestQop>,
+    /// The nonce count, sent as 8 hex digits. Required with a `qop`.
+    pub nc: Option<u32>,
+    /// The client nonce. Required with a `qop` or a `-sess` algorithm.
+    pub cnonce: Option<String>,
+    /// The opaque value, as given in the challenge.
+    pub opaque: Option<String>,
+    /// Whether `username` is hashed.
+    pub userhash: bool,
+}
+
+impl Scheme for Digest {
+    fn scheme() -> Option<&'static str> {
+        Some("Digest")
+    }
+
+    fn fmt_scheme(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.userhash || self.username.is_ascii() {
+            f.write_str("username=")?;
+            fmt_quoted(f, &self.username)?;
+        } else {
+            f.write_str("username*=UTF-8''")?;
+            http_percent_encode(f, self.username.as_bytes())?;
+        }
+        f.write_str(", realm=")?;
+        fmt_quoted(f, &self.realm)?;
+        f.write_str(", uri=")?;
+        fmt_quoted(f, &self.uri)?;
+        if let Some(ref algorithm) = self.algorithm {
+            write!(f, ", algorithm={}", algorithm)?;
+        }
+        f.write_str(", nonce=")?;
+        fmt_quoted(f, &self.nonce)?;
+        if let Some(nc) = self.nc {
+            write!(f, ", nc={:08x}", nc)?;
+        }
+        if let Some(ref cnonce) = self.cnonce {
+            f.write_str(", cnonce=")?;
+            fmt_quoted(f, cnonce)?;
+        }
+        if let Some(ref qop) = self.qop {
+            write!(f, ", qop={}", qop)?;
+        }
+        f.write_str(", response=")?;
+        fmt_quoted(f, &self.response)?;
+        if let Some(ref opaque) = self.opaque {
+            f.write_str(", opaque=")?;
+            fmt_quoted(f, opaque)?;
+        }
+        if self.userhash {
+            f.write_str(", userhash=true")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Digest {
+    type Err = ::Error;
+
+    fn from_str(s: &str) -> ::Result<Digest> {
+        let mut digest = Digest::default();
+        let (mut username, mut realm, mut nonce, mut uri, mut response) =
+            (None, None, None, None, None);
+        for (name, value) in parse_auth_params(s)? {
+            match &name.to_ascii_lowercase()[..] {
+                "username" => username = Some(value),
+                "username*" => username = Some(parse_ext_username(&value)?),
+                "realm" => realm = Some(value),
+                "nonce" => nonce = Some(value),
+                "uri" => uri = Some(value),
+                "response" => response = Some(value),
+                "algorithm" => digest.algorithm = Some(value.parse()?),
+                "qop" => digest.qop = Some(value.parse()?),
+                "nc" => digest.nc = Some(parse_nc(&value)?),
+                "cnonce" => digest.cnonce = Some(value),
+                "opaque" => digest.opaque = Some(value),
+                "userhash" => digest.userhash = parse_bool(&value)?,
+                _ => (),
+            }
+        }
+        digest.username = username.ok_or(::Error::Header)?;
+        digest.realm = realm.ok_or(::Error::Header)?;
+        digest.nonce = nonce.ok_or(::Error::Header)?;
+        digest.uri = uri.ok_or(::Error::Header)?;
+        digest.response = response.ok_or(::Error::Header)?;
+        Ok(digest)
+    }
+}
+
+#[cfg(feature = "digest")]
+impl Digest {
+    /// Compute the `response` value for these credentials, given the
+    /// user's plain (unhashed) name and password, the request method, and
+    /// the request body.
+    ///
+    /// The body is only used with `qop=auth-int`.
+    ///
+    /// Fails if the algorithm or qop is unknown, or if the `nc` or `cnonce`
+    /// required by the qop or algorithm is missing.
+    pub fn compute_response(&self, username: &str, password: &str,
+                            method: &Method, body: &[u8]) -> ::Result<String> {
+        let ha1 = self.algorithm().hash(
+            format!("{}:{}:{}", username, self.realm, password).as_bytes())?;
+        self.compute_response_from_ha1(&ha1, method, body)
+    }
+
+    /// Compute the `response` value as `compute_response` does, but from
+    /// a precomputed `H(username:realm:password)`, as servers often store.
+    pub fn compute_response_from_ha1(&self, ha1: &str, method: &Method, body: &[u8])
+        -> ::Result<String>
+    {
+        let algorithm = self.algorithm();
+        let ha1 = if algorithm.is_sess() {
+            let cnonce = self.cnonce.as_ref().ok_or(::Error::Header)?;
+            algorithm.hash(format!("{}:{}:{}", ha1, self.nonce, cnonce).as_bytes())?
+        } else {
+            ha1.to_owned()
+        };
+        let a2 = match self.qop {
+            None | Some(DigestQop::Auth) => format!("{}:{}", method, self.uri),
+            Some(DigestQop::AuthInt) => {
+                format!("{}:{}:{}", method, self.uri, algorithm.hash(body)?)
+            }
+            Some(DigestQop::Ext(_)) => return Err(::Error::Header),
+        };
+        let ha2 = algorithm.hash(a2.as_bytes())?;
+        let kd = match self.qop {
+            Some(ref qop) => {
+                let nc = self.nc.ok_or(::Error::Header)?;
+                let cnonce = self.cnonce.as_ref().ok_or(::Error::Header)?;
+                format!("{}:{}:{:08x}:{}:{}:{}", ha1, self.nonce, nc, cnonce, qop, ha2)
+            }
+            None => format!("{}:{}:{}", ha1, self.nonce, ha2),
+        };
+        algorithm.hash(kd.as_bytes())
+    }
+
+    /// Check the `response` value against the expected one for the given
+    /// user's plain name and password, in constant time.
+    pub fn verify_response(&self, username: &str, password: &str,
+                           method: &Method, body: &[u8]) -> bool {
+        match self.compute_response(username, password, method, body) {
+            Ok(expected) => constant_time_eq(&expected, &self.response),
+            Err(_) => false,
+        }
+    }
+
+    /// Check the `response` value as `verify_response` does, but from a
+    /// precomputed `H(username:realm:password)`.
+    pub fn verify_response_from_ha1(&self, ha1: &str, method: &Method, body: &[u8])
+        -> bool
+    {
+        match self.compute_response_from_ha1(ha1, method, body) {
+            Ok(expected) => constant_time_eq(&expected, &self.response),
+            Err(_) => false,
+        }
+    }
+
+    fn algorithm(&self) -> &DigestAlgorithm {
+        self.algorithm.as_ref().unwrap_or(&DigestAlgorithm::Md5)
+    }
+}
+
+/// A Digest challenge, as sent in `WWW-Authenticate` and
+/// `Proxy-Authenticate`, defined in
+/// [RFC7616](https://tools.ietf.org/html/rfc7616#section-3.3)
+///
+/// Formats as a complete challenge, including the `Digest` auth-scheme.
+///
+/// # Examples
+///
+/// ```
+/// use hyperx::header::{DigestAlgorithm, DigestChallenge, DigestQop};
+///
+/// let challenge: DigestChallenge = "Digest realm=\"http-auth@example.org\", \
+///     qop=\"auth, auth-int\", algorithm=SHA-256, nonce=\"7ypf/xlj9XXw\"".parse().unwrap();
+/// assert_eq!(challenge.algorithm, Some(DigestAlgorithm::Sha256));
+/// assert_eq!(challenge.qop, vec![DigestQop::Auth, DigestQop::AuthInt]);
+/// ```
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct DigestChallenge {
+    /// The protection space.
+    pub realm: String,
+    /// The URIs defining the protection space, if given.
+    pub domain: Vec<String>,
+    /// The server nonce.
+    pub nonce: String,
+    /// An opaque value the client must return unchanged.
+    pub opaque: Option<String>,
+    /// Whether the request was rejected only because the nonce was stale.
+    pub stale: bool,
+    /// The algorithm to use, MD5 if absent.
+    pub algorithm: Option<DigestAlgorithm>,
+    /// The supported qualities of protection.
+    pub qop: Vec<DigestQop>,
+    /// The expected character set of the username and password, only
+    /// `UTF-8` is allowed.
+    pub charset: Option<String>,
+    /// Whether the server supports username hashing.
+    pub userhash: bool,
+}
+
+impl DigestChallenge {
+    /// Interpret a generic `Challenge` with the `Digest` auth-scheme.
+    pub fn from_challenge(challenge: &Challenge) -> ::Result<DigestChallenge> {
+        if !challenge.is_scheme("Digest") || challenge.token68.is_some() {
+            return Err(::Error::Header);
+        }
+        let mut digest = DigestChallenge::default();
+        let (mut realm, mut nonce) = (None, None);
+        for (name, value) in &challenge.params {
+            match &name.to_ascii_lowercase()[..] {
+                "realm" => realm = Some(value.clone()),
+                "domain" => {
+                    digest.domain = value.split_whitespace().map(|s| s.to_owned()).collect();
+                }
+                "nonce" => nonce = Some(value.clone()),
+                "opaque" => digest.opaque = Some(value.clone()),
+                "stale" => digest.stale = parse_bool(value)?,
+                "algorithm" => digest.algorithm = Some(value.parse()?),
+                "qop" => {
+                    digest.qop = value.split(',')
+                        .map(|s| s.trim())
+                        .filter(|s| !s.is_empty())
+                        .map(|s| s.parse())
+                        .collect::<::Result<_>>()?;
+                }
+                "charset" => digest.charset = Some(value.clone()),
+                "userhash" => digest.userhash = parse_bool(value)?,
+                _ => (),
+            }
+        }
+        digest.realm = realm.ok_or(::Error::Header)?;
+        digest.nonce = nonce.ok_or(::Error::Header)?;
+        Ok(digest)
+    }
+}
+
+impl From<DigestChallenge> for Challenge {
+    fn from(digest: DigestChallenge) -> Challenge {
+        let mut challenge = Challenge::new("Digest").push_param("realm", digest.realm);
+        if !digest.domain.is_empty() {
+            challenge = challenge.push_param("domain", digest.domain.join(" "));
+        }
+        if !digest.qop.is_empty() {
+            let qop: Vec<String> = digest.qop.iter().map(|q| q.to_string()).collect();
+            challenge = challenge.push_param("qop", qop.join(", "));
+        }
+        if let Some(algorithm) = digest.algorithm {
+            challenge = challenge.push_param("algorithm", algorithm.to_string());
+        }
+        challenge = challenge.push_param("nonce", digest.nonce);
+        if let Some(opaque) = digest.opaque {
+            challenge = challenge.push_param("opaque", opaque);
+        }
+        if digest.stale {
+            challenge = challenge.push_param("stale", "true");
+        }
+        if let Some(charset) = digest.charset {
+            challenge = challenge.push_param("charset", charset);
+        }
+        if digest.userhash {
+            challenge = challenge.push_param("userhash", "true");
+        }
+        challenge
+    }
+}
+
+impl fmt::Display for DigestChallenge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Digest realm=")?;
+        fmt_quoted(f, &self.realm)?;
+        if !self.domain.is_empty() {
+            f.write_str(", domain=")?;
+            fmt_quoted(f, &self.domain.join(" "))?;
+        }
+        if !self.qop.is_empty() {
+            f.write_str(", qop=\"")?;
+            for (i, qop) in self.qop.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(", ")?;
+                }
+                fmt::Display::fmt(qop, f)?;
+            }
+            f.write_str("\"")?;
+        }
+        if let Some(ref algorithm) = self.algorithm {
+            write!(f, ", algorithm={}", algorithm)?;
+        }
+        f.write_str(", nonce=")?;
+        fmt_quoted(f, &self.nonce)?;
+        if let Some(ref opaque) = self.opaque {
+            f.write_str(", opaque=")?;
+            fmt_quoted(f, opaque)?;
+        }
+        if self.stale {
+            f.write_str(", stale=true")?;
+        }
+        if let Some(ref charset) = self.charset {
+            write!(f, ", charset={}", charset)?;
+        }
+        if self.userhash {
+            f.write_str(", userhash=true")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for DigestChallenge {
+    type Err = ::Error;
+
+    fn from_str(s: &str) -> ::Result<DigestChallenge> {
+        DigestChallenge::from_challenge(&s.parse()?)
+    }
+}
+
+/// A Digest hash algorithm, defined in
+/// [RFC7616](https://tools.ietf.org/html/rfc7616#section-6.1)
+#[derive(Clone, PartialEq, Debug)]
+pub enum DigestAlgorithm {
+    /// `MD5`
+    Md5,
+    /// `MD5-sess`
+    Md5Sess,
+    /// `SHA-256`
+    Sha256,
+    /// `SHA-256-sess`
+    Sha256Sess,
+    /// `SHA-512-256`
+    Sha512_256,
+    /// `SHA-512-256-sess`
+    Sha512_256Sess,
+    /// Some other algorithm
+    Ext(String),
+}
+
+impl DigestAlgorithm {
+    /// Return true for the `-sess` variants, where the hashed user
+    /// credentials also cover the server and client nonces.
+    pub fn is_sess(&self) -> bool {
+        matches!(*self,
+                 DigestAlgorithm::Md5Sess |
+                 DigestAlgorithm::Sha256Sess |
+                 DigestAlgorithm::Sha512_256Sess)
+    }
+}
+
+#[cfg(feature = "digest")]
+impl DigestAlgorithm {
+    /// Hash the data with this algorithm, returning lowercase hex.
+    ///
+    /// Fails for an unknown (`Ext`) algorithm.
+    pub fn hash(&self, data: &[u8]) -> ::Result<String> {
+        use md5::Md5;
+        use sha2::{Digest as _, Sha256, Sha512_256};
+
+        let hash = match *self {
+            DigestAlgorithm::Md5 | DigestAlgorithm::Md5Sess => Md5::digest(data).to_vec(),
+            DigestAlgorithm::Sha256 | DigestAlgorithm::Sha256Sess => {
+                Sha256::digest(data).to_vec()
+            }
+            DigestAlgorithm::Sha512_256 | DigestAlgorithm::Sha512_256Sess => {
+                Sha512_256::digest(data).to_vec()
+            }
+            DigestAlgorithm::Ext(_) => return Err(::Error::Header),
+        };
+        let mut hex = String::with_capacity(hash.len() * 2);
+        for b in hash {
+            hex.push_str(&format!("{:02x}", b));
+        }
+        Ok(hex)
+    }
+
+    /// Compute the hashed `username` for use with `userhash=true`.
+    pub fn user_hash(&self, username: &str, realm: &str) -> ::Result<String> {
+        self.hash(format!("{}:{}", username, realm).as_bytes())
+    }
+}
+
+impl fmt::Display for DigestAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            DigestAlgorithm::Md5 => "MD5",
+            DigestAlgorithm::Md5Sess => "MD5-sess",
+            DigestAlgorithm::Sha256 => "SHA-256",
+            DigestAlgorithm::Sha256Sess => "SHA-256-sess",
+            DigestAlgorithm::Sha512_256 => "SHA-512-256",
+            DigestAlgorithm::Sha512_256Sess => "SHA-512-256-sess",
+            DigestAlgorithm::Ext(ref s) => s,
+        })
+    }
+}
+
+impl FromStr for DigestAlgorithm {
+    type Err = ::Error;
+
+    fn from_str(s: &str) -> ::Result<DigestAlgorithm> {
+        Ok(match &s.to_ascii_uppercase()[..] {
+            "MD5" => DigestAlgorithm::Md5,
+            "MD5-SESS" => DigestAlgorithm::Md5Sess,
+            "SHA-256" => DigestAlgorithm::Sha256,
+            "SHA-256-SESS" => DigestAlgorithm::Sha256Sess,
+            "SHA-512-256" => DigestAlgorithm::Sha512_256,
+            "SHA-512-256-SESS" => DigestAlgorithm::Sha512_256Sess,
+            "" => return Err(::Error::Header),
+            _ => DigestAlgorithm::Ext(s.to_owned()),
+        })
+    }
+}
+
+/// A Digest quality of protection, defined in
+/// [RFC7616](https://tools.ietf.org/html/rfc7616#section-3.3)
+#[derive(Clone, PartialEq, Debug)]
+pub enum DigestQop {
+    /// `auth`, authentication only
+    Auth,
+    /// `auth-int`, authentication with integrity protection of the body
+    AuthInt,
+    /// Some other quality of protection
+    Ext(String),
+}
+
+impl fmt::Display for DigestQop {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            DigestQop::Auth => "auth",
+            DigestQop::AuthInt => "auth-int",
+            DigestQop::Ext(ref s) => s,
+        })
+    }
+}
+
+impl FromStr for DigestQop {
+    type Err = ::Error;
+
+    fn from_str(s: &str) -> ::Result<DigestQop> {
+        Ok(match &s.to_ascii_lowercase()[..] {
+            "auth" => DigestQop::Auth,
+            "auth-int" => DigestQop::AuthInt,
+            "" => return Err(::Error::Header),
+            _ => DigestQop::Ext(s.to_owned()),
+        })
+    }
+}
+
+fn parse_ext_username(s: &str) -> ::Result<String> {
+    let ext = parse_extended_value(s)?;
+    match ext.charset {
+        Charset::Ext(ref cs) if cs.eq_ignore_ascii_case("UTF-8") => {
+            String::from_utf8(ext.value).map_err(|_| ::Error::Header)
+        }
+        _ => Err(::Error::Header),
+    }
+}
+
+fn parse_nc(s: &str) -> ::Result<u32> {
+    if s.len() != 8 || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(::Error::Header);
+    }
+    u32::from_str_radix(s, 16).map_err(|_| ::Error::Header)
+}
+
+fn parse_bool(s: &str) -> ::Result<bool> {
+    if s.eq_ignore_ascii_case("true") {
+        Ok(true)
+    } else if s.eq_ignore_ascii_case("false") {
+        Ok(false)
+    } else {
+        Err(::Error::Header)
+    }
+}
+
+#[cfg(feature = "digest")]
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.bytes().zip(b.bytes())
+        .fold(0, |acc, (x, y)| acc | (x.to_ascii_lowercase() ^ y.to_ascii_lowercase())) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use header::{Authorization, Challenge, Header, Raw, WwwAuthenticate};
+    use super::*;
+
+    // From RFC 7616, section 3.9.1
+    const RFC_MD5: &str = "Digest username=\"Mufasa\", \
+        realm=\"http-auth@example.org\", uri=\"/dir/index.html\", \
+        algorithm=MD5, nonce=\"7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v\", \
+        nc=00000001, cnonce=\"f2/wE4q74E6zIJEtWaHKaf5wv/H5QzzpXusqGemxURZJ\", \
+        qop=auth, response=\"8ca523f5e9506fed4657c9700eebdbec\", \
+        opaque=\"FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS\"";
+
+    fn rfc_digest(algorithm: DigestAlgorithm) -> Digest {
+        Digest {
+            username: "Mufasa".to_owned(),
+            realm: "http-auth@example.org".to_owned(),
+            nonce: "7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v".to_owned(),
+            uri: "/dir/index.html".to_owned(),
+            response: String::new(),
+            algorithm: Some(algorithm),
+            qop: Some(DigestQop::Auth),
+            nc: Some(1),
+            cnonce: Some("f2/wE4q74E6zIJEtWaHKaf5wv/H5QzzpXusqGemxURZJ".to_owned()),
+            opaque: Some("FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS".to_owned()),
+            userhash: false,
+        }
+    }
+
+    #[test]
+    fn test_credentials_round_trip() {
+        let r: Raw = RFC_MD5.into();
+        let auth: Authorization<Digest> = Header::parse_header(&r).unwrap();
+        let mut expected = rfc_digest(DigestAlgorithm::Md5);
+        expected.response = "8ca523f5e9506fed4657c9700eebdbec".to_owned();
+        assert_eq!(auth.0, expected);
+        assert_eq!(auth.to_string(), RFC_MD5);
+    }
+
+    #[test]
+    fn test_credentials_minimal() {
+        let r: Raw = "digest USERNAME=\"Mufasa\", realm=\"testrealm@host.com\", \
+                      nonce=\"dcd98b7102dd2f0e8b11d0f600bfb0c093\", uri=\"/\", \
+                      response=\"e966c932a9242554e42c8ee200cec7f6\"".into();
+        let auth: Authorization<Digest> = Header::parse_header(&r).unwrap();
+        assert_eq!(auth.username, "Mufasa");
+        assert_eq!(auth.algorithm, None);
+        assert_eq!(auth.qop, None);
+        assert_eq!(auth.nc, None);
+    }
+
+    #[test]
+    fn test_credentials_ext_username() {
+        let r: Raw = "Digest username*=UTF-8''J%C3%A4s%C3%B8n%20Doe, \
+                      realm=\"api@example.org\", nonce=\"n\", uri=\"/doe.json\", \
+                      response=\"r\"".into();
+        let auth: Authorization<Digest> = Header::parse_header(&r).unwrap();
+        assert_eq!(auth.username, "J\u{e4}s\u{f8}n Doe");
+        assert!(auth.to_string().starts_with(
+            "Digest username*=UTF-8''J%C3%A4s%C3%B8n%20Doe, "));
+    }
+
+    #[test]
+    fn test_credentials_invalid() {
+        for bad in &[
+            "Digest realm=\"r\", nonce=\"n\", uri=\"/\", response=\"x\"",
+            "Digest username=\"u\", realm=\"r\", nonce=\"n\", uri=\"/\"",
+            "Digest username=\"u\", realm=\"r\", nonce=\"n\", uri=\"/\", \
+             response=\"x\", nc=1",
+            "Digest username=\"u\", realm=\"r\", nonce=\"n\", uri=\"/\", \
+             response=\"x\", userhash=yes",
+            "Basic username=\"u\", realm=\"r\", nonce=\"n\", uri=\"/\", response=\"x\"",
+        ] {
+            let r: Raw = (*bad).into();
+            let auth: ::Result<Authorization<Digest>> = Header::parse_header(&r);
+            assert!(auth.is_err(), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn test_challenge() {
+        // From RFC 7616, section 3.9.1
+        let s = "Digest realm=\"http-auth@example.org\", qop=\"auth, auth-int\", \
+                 algorithm=SHA-256, nonce=\"7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v\", \
+                 opaque=\"FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS\"";
+        let challenge: DigestChallenge = s.parse().unwrap();
+        assert_eq!(challenge, DigestChallenge {
+            realm: "http-auth@example.org".to_owned(),
+            nonce: "7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v".to_owned(),
+            opaque: Some("FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS".to_owned()),
+            algorithm: Some(DigestAlgorithm::Sha256),
+            qop: vec![DigestQop::Auth, DigestQop::AuthInt],
+            ..Default::default()
+        });
+        assert_eq!(challenge.to_string(), s);
+    }
+
+    #[test]
+    fn test_challenge_from_www_authenticate() {
+        let r: Raw = "Basic realm=\"x\", Digest realm=\"x\", nonce=\"abc\", \
+                      domain=\"/a /b\", stale=TRUE, userhash=true, charset=UTF-8".into();
+        let auth: WwwAuthenticate = Header::parse_header(&r).unwrap();
+        assert!(DigestChallenge::from_challenge(&auth[0]).is_err());
+        let digest = DigestChallenge::from_challenge(&auth[1]).unwrap();
+        assert_eq!(digest.domain, vec!["/a".to_owned(), "/b".to_owned()]);
+        assert!(digest.stale);
+        assert!(digest.userhash);
+        assert_eq!(digest.charset, Some("UTF-8".to_owned()));
+
+        let challenge: Challenge = digest.clone().into();
+        assert_eq!(DigestChallenge::from_challenge(&challenge).unwrap(), digest);
+    }
+
+    #[test]
+    fn test_algorithm_qop() {
+        assert_eq!("sha-512-256-SESS".parse::<DigestAlgorithm>().unwrap(),
+                   DigestAlgorithm::Sha512_256Sess);
+        assert_eq!("Other".parse::<DigestAlgorithm>().unwrap(),
+                   DigestAlgorithm::Ext("Other".to_owned()));
+        assert!(DigestAlgorithm::Md5Sess.is_sess());
+        assert!(!DigestAlgorithm::Sha256.is_sess());
+        assert_eq!("AUTH-INT".parse::<DigestQop>().unwrap(), DigestQop::AuthInt);
+        assert_eq!(DigestQop::AuthInt.to_string(), "auth-int");
+    }
+
+    #[cfg(feature = "digest")]
+    #[test]
+    fn test_compute_response_rfc7616() {
+        let digest = rfc_digest(DigestAlgorithm::Md5);
+        assert_eq!(
+            digest.compute_response("Mufasa", "Circle of Life", &Method::Get, b"").unwrap(),
+            "8ca523f5e9506fed4657c9700eebdbec");
+
+        let digest = rfc_digest(DigestAlgorithm::Sha256);
+        assert_eq!(
+            digest.compute_response("Mufasa", "Circle of Life", &Method::Get, b"").unwrap(),
+            "753927fa0e85d155564e2e272a28d1802ca10daf4496794697cf8db5856cb6c1");
+    }
+
+    #[cfg(feature = "digest")]
+    #[test]
+    fn test_compute_response_rfc2617() {
+        let digest = Digest {
+            username: "Mufasa".to_owned(),
+            realm: "testrealm@host.com".to_owned(),
+            nonce: "dcd98b7102dd2f0e8b11d0f600bfb0c093".to_owned(),
+            uri: "/dir/index.html".to_owned(),
+            response: "6629FAE49393A05397450978507C4EF1".to_owned(),
+            qop: Some(DigestQop::Auth),
+            nc: Some(1),
+            cnonce: Some("0a4f113b".to_owned()),
+            ..Default::default()
+        };
+        assert!(digest.verify_response("Mufasa", "Circle Of Life", &Method::Get, b""));
+        assert!(!digest.verify_response("Mufasa", "Circle of Life", &Method::Get, b""));
+        assert!(!digest.verify_response("Mufasa", "Circle Of Life", &Method::Post, b""));
+
+        let ha1 = DigestAlgorithm::Md5
+            .hash(b"Mufasa:testrealm@host.com:Circle Of Life").unwrap();
+        assert!(digest.verify_response_from_ha1(&ha1, &Method::Get, b""));
+    }
+
+    #[cfg(feature = "digest")]
+    #[test]
+    fn test_compute_response_variants() {
+        for algorithm in vec![
+            DigestAlgorithm::Md5Sess,
+            DigestAlgorithm::Sha256Sess,
+            DigestAlgorithm::Sha512_256,
+            DigestAlgorithm::Sha512_256Sess,
+        ] {
+            for qop in &[None, Some(DigestQop::Auth), Some(DigestQop::AuthInt)] {
+                let mut digest = rfc_digest(algorithm.clone());
+                digest.qop = qop.clone();
+                digest.response = digest
+                    .compute_response("Mufasa", "Circle of Life", &Method::Post, b"body")
+                    .unwrap();
+                assert!(digest.verify_response(
+                    "Mufasa", "Circle of Life", &Method::Post, b"body"));
+                assert_eq!(
+                    digest.verify_response("Mufasa", "Circle of Life", &Method::Post, b"other"),
+                    *qop != Some(DigestQop::AuthInt));
+            }
+        }
+
+        let mut digest = rfc_digest(DigestAlgorithm::Md5Sess);
+        digest.cnonce = None;
+        assert!(digest.compute_response("Mufasa", "", &Method::Get, b"").is_err());
+        let digest = rfc_digest(DigestAlgorithm::Ext("SHA-1".to_owned()));
+        assert!(digest.compute_response("Mufasa", "", &Method::Get, b"").is_err());
+    }
+
+    #[cfg(feature = "digest")]
+    #[test]
+    fn test_hash() {
+        assert_eq!(
+            DigestAlgorithm::Sha512_256.hash(b"abc").unwrap(),
+            "53048e2681941ef99b2e29b76b4c7dabe4c2d0c634fc6d46e0e2f13107e7af23");
+        assert_eq!(
+            DigestAlgorithm::Sha256.user_hash("Mufasa", "http-auth@example.org").unwrap(),
+            DigestAlgorithm::Sha256.hash(b"Mufasa:http-auth@example.org").unwrap());
+        assert!(DigestAlgorithm::Ext("SHA-1".to_owned()).hash(b"abc").is_err());
+    }
+}
+
+bench_header!(bench, ::header::Authorization<Digest>, {
+    vec![b"Digest username=\"Mufasa\", realm=\"http-auth@example.org\", \
+           uri=\"/dir/index.html\", nonce=\"7ypf/xlj9XXwfDPEoM4URrv\", nc=00000001, \
+           cnonce=\"f2/wE4q74E6z\", qop=auth, response=\"8ca523f5e9506fed4657c9700eebdbec\"".to_vec()]
+});
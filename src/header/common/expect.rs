@@ -33,6 +33,8 @@ impl Header for Expect {
         NAME
     }
 
+    standard_header!(fn EXPECT);
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<Expect>
     where T: RawLike<'a>
     {
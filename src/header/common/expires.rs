@@ -30,7 +30,7 @@ header! {
     /// let expiration = SystemTime::now() + Duration::from_secs(60 * 60 * 24);
     /// headers.encode(&Expires(expiration.into()));
     /// ```
-    (Expires, "Expires") => [HttpDate]
+    (Expires, "Expires", EXPIRES) => date [HttpDate]
 
     test_expires {
         // Testcase from RFC
@@ -41,5 +41,3 @@ header! {
 bench_header!(imf_fixdate, Expires, { vec![b"Mon, 07 Nov 1994 08:48:37 GMT".to_vec()] });
 bench_header!(rfc_850, Expires, { vec![b"Sunday, 06-Nov-94 08:49:37 GMT".to_vec()] });
 bench_header!(asctime, Expires, { vec![b"Sun Nov  6 08:49:37 1994".to_vec()] });
-
-standard_header!(Expires, EXPIRES);
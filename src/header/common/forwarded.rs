@@ -0,0 +1,541 @@
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::str::{FromStr, from_utf8};
+
+use header::{Header, RawLike, XForwardedFor, XForwardedHost, XForwardedProto};
+use header::parsing::{fmt_comma_delimited, fmt_quoted, is_token, split_unquoted, unquote};
+
+/// `Forwarded` header, defined in [RFC7239](https://tools.ietf.org/html/rfc7239#section-4)
+///
+/// The `Forwarded` header field discloses information from the client-facing
+/// side of proxy servers that is altered or lost when a proxy is involved in
+/// the path of the request. Each proxy appends an element to the list.
+///
+/// # ABNF
+///
+/// ```text
+/// Forwarded         = 1#forwarded-element
+/// forwarded-element = [ forwarded-pair ] *( ";" [ forwarded-pair ] )
+/// forwarded-pair    = token "=" value
+/// value             = token / quoted-string
+/// ```
+///
+/// # Example values
+///
+/// * `for="_gazonk"`
+/// * `For="[2001:db8:cafe::17]:4711"`
+/// * `for=192.0.2.60;proto=http;by=203.0.113.43`
+/// * `for=192.0.2.43, for=198.51.100.17`
+///
+/// # Examples
+///
+/// ```
+/// # extern crate http;
+/// use hyperx::header::{Forwarded, ForwardedElement, TypedHeaders};
+///
+/// let mut headers = http::HeaderMap::new();
+/// headers.insert("forwarded", "for=192.0.2.43".parse().unwrap());
+///
+/// let mut forwarded: Forwarded = headers.decode().unwrap();
+/// let peer: std::net::SocketAddr = "[2001:db8:cafe::17]:4711".parse().unwrap();
+/// forwarded.push(ForwardedElement::new().set_for(peer).set_proto("https"));
+/// headers.encode(&forwarded);
+/// assert_eq!(
+///     headers["forwarded"],
+///     "for=192.0.2.43, for=\"[2001:db8:cafe::17]:4711\";proto=https"
+/// );
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct Forwarded(pub Vec<ForwardedElement>);
+
+__hyper__deref!(Forwarded => Vec<ForwardedElement>);
+
+impl Forwarded {
+    /// Combine the legacy `X-Forwarded-For`, `X-Forwarded-Host` and
+    /// `X-Forwarded-Proto` headers into `Forwarded` elements.
+    ///
+    /// The n-th values of each header are combined in the n-th element, so
+    /// a single host or proto (as is usual) describes the first element,
+    /// which is the original client request.
+    pub fn from_legacy(
+        xff: Option<&XForwardedFor>,
+        host: Option<&XForwardedHost>,
+        proto: Option<&XForwardedProto>,
+    ) -> Forwarded {
+        let fors: &[ForwardedNode] = xff.map(|h| &h[..]).unwrap_or(&[]);
+        let hosts: &[String] = host.map(|h| &h[..]).unwrap_or(&[]);
+        let protos: &[String] = proto.map(|h| &h[..]).unwrap_or(&[]);
+        let len = fors.len().max(hosts.len()).max(protos.len());
+        Forwarded((0..len).map(|i| ForwardedElement {
+            by: None,
+            for_: fors.get(i).cloned(),
+            host: hosts.get(i).cloned(),
+            proto: protos.get(i).cloned(),
+            extensions: Vec::new(),
+        }).collect())
+    }
+}
+
+impl Header for Forwarded {
+    fn header_name() -> &'static str {
+        static NAME: &str = "Forwarded";
+        NAME
+    }
+
+    standard_header!(fn FORWARDED);
+
+    fn parse_header<'a, T>(raw: &'a T) -> ::Result<Forwarded>
+    where T: RawLike<'a>
+    {
+        let mut elements = Vec::new();
+        for line in raw.iter() {
+            for element in split_unquoted(from_utf8(line)?, b',')? {
+                let element = element.trim();
+                if !element.is_empty() {
+                    elements.push(element.parse()?);
+                }
+            }
+        }
+        if elements.is_empty() {
+            Err(::Error::Header)
+        } else {
+            Ok(Forwarded(elements))
+        }
+    }
+
+    fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
+        f.fmt_line(self)
+    }
+}
+
+impl fmt::Display for Forwarded {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_comma_delimited(f, &self[..])
+    }
+}
+
+impl<'a> From<&'a Forwarded> for XForwardedFor {
+    /// Collect the `for` nodes of all elements which have one.
+    fn from(forwarded: &'a Forwarded) -> XForwardedFor {
+        XForwardedFor(forwarded.iter().filter_map(|e| e.for_.clone()).collect())
+    }
+}
+
+impl<'a> From<&'a Forwarded> for XForwardedHost {
+    /// Collect the `host` values of all elements which have one.
+    fn from(forwarded: &'a Forwarded) -> XForwardedHost {
+        XForwardedHost(forwarded.iter().filter_map(|e| e.host.clone()).collect())
+    }
+}
+
+impl<'a> From<&'a Forwarded> for XForwardedProto {
+    /// Collect the `proto` values of all elements which have one.
+    fn from(forwarded: &'a Forwarded) -> XForwardedProto {
+        XForwardedProto(forwarded.iter().filter_map(|e| e.proto.clone()).collect())
+    }
+}
+
+/// A single element of the `Forwarded` header, as appended by one proxy.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct ForwardedElement {
+    /// The `by` parameter: the interface where the request came in to the
+    /// proxy.
+    pub by: Option<ForwardedNode>,
+    /// The `for` parameter: the client that made the request to the proxy.
+    pub for_: Option<ForwardedNode>,
+    /// The `host` parameter: the `Host` request header as received by the
+    /// proxy.
+    pub host: Option<String>,
+    /// The `proto` parameter: the protocol used to make the request, such as
+    /// `https`.
+    pub proto: Option<String>,
+    /// Any other parameters, as (name, value) pairs.
+    pub extensions: Vec<(String, String)>,
+}
+
+impl ForwardedElement {
+    /// Create an empty element.
+    pub fn new() -> ForwardedElement {
+        ForwardedElement::default()
+    }
+
+    /// Set the `by` parameter.
+    pub fn set_by<N: Into<ForwardedNode>>(mut self, by: N) -> ForwardedElement {
+        self.by = Some(by.into());
+        self
+    }
+
+    /// Set the `for` parameter.
+    pub fn set_for<N: Into<ForwardedNode>>(mut self, for_: N) -> ForwardedElement {
+        self.for_ = Some(for_.into());
+        self
+    }
+
+    /// Set the `host` parameter.
+    pub fn set_host<S: Into<String>>(mut self, host: S) -> ForwardedElement {
+        self.host = Some(host.into());
+        self
+    }
+
+    /// Set the `proto` parameter.
+    pub fn set_proto<S: Into<String>>(mut self, proto: S) -> ForwardedElement {
+        self.proto = Some(proto.into());
+        self
+    }
+}
+
+impl fmt::Display for ForwardedElement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut first = true;
+        let mut pair = |f: &mut fmt::Formatter, name: &str, value: &str| {
+            if !first {
+                f.write_str(";")?;
+            }
+            first = false;
+            write!(f, "{}=", name)?;
+            if is_token(value) {
+                f.write_str(value)
+            } else {
+                fmt_quoted(f, value)
+            }
+        };
+        if let Some(ref by) = self.by {
+            pair(f, "by", &by.to_string())?;
+        }
+        if let Some(ref for_) = self.for_ {
+            pair(f, "for", &for_.to_string())?;
+        }
+        if let Some(ref host) = self.host {
+            pair(f, "host", host)?;
+        }
+        if let Some(ref proto) = self.proto {
+            pair(f, "proto", proto)?;
+        }
+        for (name, value) in &self.extensions {
+            pair(f, name, value)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for ForwardedElement {
+    type Err = ::Error;
+
+    fn from_str(s: &str) -> ::Result<ForwardedElement> {
+        let mut element = ForwardedElement::new();
+        for pair in split_unquoted(s, b';')? {
+            let pair = pair.trim();
+            if pair.is_empty() {
+                continue;
+            }
+            let eq = pair.find('=').ok_or(::Error::Header)?;
+            let (name, value) = (&pair[..eq], &pair[eq + 1..]);
+            if !is_token(name) {
+                return Err(::Error::Header);
+            }
+            let value = if value.starts_with('"') {
+                unquote(value)?
+            } else if is_token(value) {
+                value.to_owned()
+            } else {
+                return Err(::Error::Header);
+            };
+            let name = name.to_ascii_lowercase();
+            let duplicate = match &name[..] {
+                "by" => element.by.replace(value.parse()?).is_some(),
+                "for" => element.for_.replace(value.parse()?).is_some(),
+                "host" => element.host.replace(value).is_some(),
+                "proto" => element.proto.replace(value).is_some(),
+                _ => {
+                    let duplicate = element.extensions.iter().any(|e| e.0 == name);
+                    element.extensions.push((name, value));
+                    duplicate
+                }
+            };
+            if duplicate {
+                return Err(::Error::Header);
+            }
+        }
+        Ok(element)
+    }
+}
+
+/// A node identifier, as used in the `for` and `by` parameters of
+/// `Forwarded`, and in `X-Forwarded-For`.
+///
+/// Formats in the `Forwarded` syntax, with IPv6 addresses in brackets.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ForwardedNode {
+    /// The node name.
+    pub name: NodeName,
+    /// The optional port.
+    pub port: Option<NodePort>,
+}
+
+impl ForwardedNode {
+    /// Create a node identifier without port.
+    pub fn new(name: NodeName) -> ForwardedNode {
+        ForwardedNode {
+            name,
+            port: None,
+        }
+    }
+
+    /// Return the IP address, if the node name is one.
+    pub fn ip(&self) -> Option<IpAddr> {
+        match self.name {
+            NodeName::Ipv4(ip) => Some(IpAddr::V4(ip)),
+            NodeName::Ipv6(ip) => Some(IpAddr::V6(ip)),
+            _ => None,
+        }
+    }
+}
+
+impl From<IpAddr> for ForwardedNode {
+    fn from(ip: IpAddr) -> ForwardedNode {
+        ForwardedNode::new(match ip {
+            IpAddr::V4(ip) => NodeName::Ipv4(ip),
+            IpAddr::V6(ip) => NodeName::Ipv6(ip),
+        })
+    }
+}
+
+impl From<SocketAddr> for ForwardedNode {
+    fn from(addr: SocketAddr) -> ForwardedNode {
+        ForwardedNode {
+            port: Some(NodePort::Port(addr.port())),
+            ..addr.ip().into()
+        }
+    }
+}
+
+impl fmt::Display for ForwardedNode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.name, f)?;
+        if let Some(ref port) = self.port {
+            write!(f, ":{}", port)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for ForwardedNode {
+    type Err = ::Error;
+
+    fn from_str(s: &str) -> ::Result<ForwardedNode> {
+        let (name, port) = if s.starts_with('[') {
+            let end = s.find(']').ok_or(::Error::Header)?;
+            match &s[end + 1..] {
+                "" => (&s[..=end], None),
+                rest if rest.starts_with(':') => (&s[..=end], Some(&rest[1..])),
+                _ => return Err(::Error::Header),
+            }
+        } else {
+            match s.find(':') {
+                Some(i) => (&s[..i], Some(&s[i + 1..])),
+                None => (s, None),
+            }
+        };
+        Ok(ForwardedNode {
+            name: name.parse()?,
+            port: match port {
+                Some(port) => Some(port.parse()?),
+                None => None,
+            },
+        })
+    }
+}
+
+/// The name of a `ForwardedNode`.
+#[derive(Clone, PartialEq, Debug)]
+pub enum NodeName {
+    /// An IPv4 address
+    Ipv4(Ipv4Addr),
+    /// An IPv6 address, in brackets when formatted
+    Ipv6(Ipv6Addr),
+    /// An obfuscated identifier, starting with `_`
+    Obfuscated(String),
+    /// `unknown`: the node is not known or not disclosed
+    Unknown,
+}
+
+impl fmt::Display for NodeName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            NodeName::Ipv4(ref ip) => fmt::Display::fmt(ip, f),
+            NodeName::Ipv6(ref ip) => write!(f, "[{}]", ip),
+            NodeName::Obfuscated(ref s) => f.write_str(s),
+            NodeName::Unknown => f.write_str("unknown"),
+        }
+    }
+}
+
+impl FromStr for NodeName {
+    type Err = ::Error;
+
+    fn from_str(s: &str) -> ::Result<NodeName> {
+        if s.starts_with('[') && s.ends_with(']') {
+            s[1..s.len() - 1].parse().map(NodeName::Ipv6).map_err(|_| ::Error::Header)
+        } else if s.eq_ignore_ascii_case("unknown") {
+            Ok(NodeName::Unknown)
+        } else if is_obfuscated(s) {
+            Ok(NodeName::Obfuscated(s.to_owned()))
+        } else {
+            s.parse().map(NodeName::Ipv4).map_err(|_| ::Error::Header)
+        }
+    }
+}
+
+/// The port of a `ForwardedNode`.
+#[derive(Clone, PartialEq, Debug)]
+pub enum NodePort {
+    /// A port number
+    Port(u16),
+    /// An obfuscated port, starting with `_`
+    Obfuscated(String),
+}
+
+impl fmt::Display for NodePort {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            NodePort::Port(port) => fmt::Display::fmt(&port, f),
+            NodePort::Obfuscated(ref s) => f.write_str(s),
+        }
+    }
+}
+
+impl FromStr for NodePort {
+    type Err = ::Error;
+
+    fn from_str(s: &str) -> ::Result<NodePort> {
+        if is_obfuscated(s) {
+            Ok(NodePort::Obfuscated(s.to_owned()))
+        } else if !s.is_empty() && s.len() <= 5 && s.bytes().all(|b| b.is_ascii_digit()) {
+            s.parse().map(NodePort::Port).map_err(|_| ::Error::Header)
+        } else {
+            Err(::Error::Header)
+        }
+    }
+}
+
+// obfnode = "_" 1*( ALPHA / DIGIT / "." / "_" / "-")
+fn is_obfuscated(s: &str) -> bool {
+    s.len() > 1 && s.starts_with('_') && s.bytes().all(|b| {
+        b.is_ascii_alphanumeric() || b == b'.' || b == b'_' || b == b'-'
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    use header::{Header, Raw, XForwardedFor, XForwardedHost, XForwardedProto};
+    use super::*;
+
+    #[test]
+    fn test_parse_rfc_examples() {
+        // From RFC 7239, section 4
+        let r: Raw = vec![
+            b"for=\"_gazonk\"".to_vec(),
+            b"For=\"[2001:db8:cafe::17]:4711\"".to_vec(),
+            b"for=192.0.2.60;proto=http;by=203.0.113.43".to_vec(),
+            b"for=192.0.2.43, for=198.51.100.17".to_vec(),
+        ].into();
+        let fwd: Forwarded = Header::parse_header(&r).unwrap();
+        assert_eq!(fwd.len(), 5);
+        assert_eq!(fwd[0].for_, Some(ForwardedNode::new(
+            NodeName::Obfuscated("_gazonk".to_owned()))));
+        assert_eq!(fwd[1].for_, Some(ForwardedNode {
+            name: NodeName::Ipv6("2001:db8:cafe::17".parse().unwrap()),
+            port: Some(NodePort::Port(4711)),
+        }));
+        assert_eq!(fwd[2], ForwardedElement::new()
+            .set_for(IpAddr::V4(Ipv4Addr::new(192, 0, 2, 60)))
+            .set_proto("http")
+            .set_by(IpAddr::V4(Ipv4Addr::new(203, 0, 113, 43))));
+        assert_eq!(fwd[4].for_.as_ref().and_then(|n| n.ip()),
+                   Some(IpAddr::V4(Ipv4Addr::new(198, 51, 100, 17))));
+        assert_eq!(
+            fwd.to_string(),
+            "for=_gazonk, for=\"[2001:db8:cafe::17]:4711\", \
+             by=203.0.113.43;for=192.0.2.60;proto=http, \
+             for=192.0.2.43, for=198.51.100.17");
+    }
+
+    #[test]
+    fn test_parse_nodes() {
+        for &(s, ref expected) in &[
+            ("unknown", ForwardedNode::new(NodeName::Unknown)),
+            ("UNKNOWN:_p1", ForwardedNode {
+                name: NodeName::Unknown,
+                port: Some(NodePort::Obfuscated("_p1".to_owned())),
+            }),
+            ("192.0.2.1:8080", ForwardedNode {
+                name: NodeName::Ipv4(Ipv4Addr::new(192, 0, 2, 1)),
+                port: Some(NodePort::Port(8080)),
+            }),
+            ("[::1]", ForwardedNode::new(NodeName::Ipv6(Ipv6Addr::LOCALHOST))),
+        ] {
+            assert_eq!(&s.parse::<ForwardedNode>().unwrap(), expected);
+        }
+        for bad in &["", "::1", "[::1", "[::1]80", "host.example", "1.2.3.4:99999",
+                     "1.2.3.4:", "_", "_a b"] {
+            assert!(bad.parse::<ForwardedNode>().is_err(), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn test_parse_extensions() {
+        let r: Raw = "for=unknown;host=\"example.com:8080\";secret=\"a b\"".into();
+        let fwd: Forwarded = Header::parse_header(&r).unwrap();
+        assert_eq!(fwd[0].host, Some("example.com:8080".to_owned()));
+        assert_eq!(fwd[0].extensions, vec![("secret".to_owned(), "a b".to_owned())]);
+        assert_eq!(fwd.to_string(),
+                   "for=unknown;host=\"example.com:8080\";secret=\"a b\"");
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for bad in &[
+            "",
+            "for=[::1]",
+            "for=192.0.2.1;for=192.0.2.2",
+            "for=\"unterminated",
+            "for",
+            "for=host.example",
+            "a=1;a=2",
+        ] {
+            let r: Raw = (*bad).into();
+            let fwd: ::Result<Forwarded> = Header::parse_header(&r);
+            assert!(fwd.is_err(), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn test_legacy_conversions() {
+        let xff: XForwardedFor = XForwardedFor(vec![
+            "192.0.2.43".parse().unwrap(),
+            "[2001:db8::1]:443".parse().unwrap(),
+        ]);
+        let host = XForwardedHost(vec!["example.com".to_owned()]);
+        let proto = XForwardedProto(vec!["https".to_owned()]);
+        let fwd = Forwarded::from_legacy(Some(&xff), Some(&host), Some(&proto));
+        assert_eq!(
+            fwd.to_string(),
+            "for=192.0.2.43;host=example.com;proto=https, for=\"[2001:db8::1]:443\"");
+
+        assert_eq!(XForwardedFor::from(&fwd), xff);
+        assert_eq!(XForwardedHost::from(&fwd), host);
+        assert_eq!(XForwardedProto::from(&fwd), proto);
+
+        let fwd = Forwarded::from_legacy(None, None, Some(&proto));
+        assert_eq!(fwd.to_string(), "proto=https");
+        assert!(Forwarded::from_legacy(None, None, None).is_empty());
+    }
+}
+
+bench_header!(bench, Forwarded, {
+    vec![b"for=192.0.2.60;proto=http;by=203.0.113.43, for=\"[2001:db8:cafe::17]:4711\"".to_vec()]
+});
+
+standard_header!(Forwarded, FORWARDED);
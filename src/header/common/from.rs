@@ -22,11 +22,9 @@ header! {
     /// headers.encode(&From("webmaster@example.org".to_owned()));
     /// ```
     // FIXME: Maybe use mailbox?
-    (From, "From") => [String]
+    (From, "From", FROM) => [String]
 
     test_from {
         test_header!(test1, vec![b"webmaster@example.org"]);
     }
 }
-
-standard_header!(From, FROM);
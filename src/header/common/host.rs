@@ -64,6 +64,8 @@ impl Header for Host {
         NAME
     }
 
+    standard_header!(fn HOST);
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<Host>
     where T: RawLike<'a>
     {
@@ -50,7 +50,7 @@ header! {
     ///     ])
     /// );
     /// ```
-    (IfMatch, "If-Match") => {Any / (EntityTag)+}
+    (IfMatch, "If-Match", IF_MATCH) => {Any / (EntityTag)+}
 
     test_if_match {
         test_header!(
@@ -73,5 +73,3 @@ bench_header!(star, IfMatch, { vec![b"*".to_vec()] });
 bench_header!(single , IfMatch, { vec![b"\"xyzzy\"".to_vec()] });
 bench_header!(multi, IfMatch,
               { vec![b"\"xyzzy\", \"r2d2xxxx\", \"c3piozzzz\"".to_vec()] });
-
-standard_header!(IfMatch, IF_MATCH);
@@ -30,7 +30,7 @@ header! {
     /// let modified = SystemTime::now() - Duration::from_secs(60 * 60 * 24);
     /// headers.encode(&IfModifiedSince(modified.into()));
     /// ```
-    (IfModifiedSince, "If-Modified-Since") => [HttpDate]
+    (IfModifiedSince, "If-Modified-Since", IF_MODIFIED_SINCE) => date [HttpDate]
 
     test_if_modified_since {
         // Testcase from RFC
@@ -41,5 +41,3 @@ header! {
 bench_header!(imf_fixdate, IfModifiedSince, { vec![b"Mon, 07 Nov 1994 08:48:37 GMT".to_vec()] });
 bench_header!(rfc_850, IfModifiedSince, { vec![b"Sunday, 06-Nov-94 08:49:37 GMT".to_vec()] });
 bench_header!(asctime, IfModifiedSince, { vec![b"Sun Nov  6 08:49:37 1994".to_vec()] });
-
-standard_header!(IfModifiedSince, IF_MODIFIED_SINCE);
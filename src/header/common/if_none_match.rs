@@ -52,7 +52,7 @@ header! {
     ///     ])
     /// );
     /// ```
-    (IfNoneMatch, "If-None-Match") => {Any / (EntityTag)+}
+    (IfNoneMatch, "If-None-Match", IF_NONE_MATCH) => {Any / (EntityTag)+}
 
     test_if_none_match {
         test_header!(test1, vec![b"\"xyzzy\""]);
@@ -86,8 +86,15 @@ mod tests {
         entities.push(weak_etag);
         assert_eq!(if_none_match.ok(), Some(IfNoneMatch::Items(entities)));
     }
+
+    #[test]
+    fn test_unterminated_quote() {
+        let r: Raw = b"\"a\", \"b".as_ref().into();
+        let if_none_match: ::Result<IfNoneMatch> = Header::parse_header(&r);
+        let a = EntityTag::new(false, "a".to_owned());
+        assert_eq!(if_none_match.ok(), Some(IfNoneMatch::Items(vec![a])));
+        assert!(IfNoneMatch::parse_header_detailed(&r).is_err());
+    }
 }
 
 bench_header!(bench, IfNoneMatch, { vec![b"W/\"nonemptytag\"".to_vec()] });
-
-standard_header!(IfNoneMatch, IF_NONE_MATCH);
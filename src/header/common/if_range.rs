@@ -59,6 +59,7 @@ impl Header for IfRange {
         static NAME: &'static str = "If-Range";
         NAME
     }
+    standard_header!(fn IF_RANGE);
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<IfRange>
     where T: RawLike<'a>
     {
@@ -31,7 +31,7 @@ header! {
     /// let modified = SystemTime::now() - Duration::from_secs(60 * 60 * 24);
     /// headers.encode(&IfUnmodifiedSince(modified.into()));
     /// ```
-    (IfUnmodifiedSince, "If-Unmodified-Since") => [HttpDate]
+    (IfUnmodifiedSince, "If-Unmodified-Since", IF_UNMODIFIED_SINCE) => date [HttpDate]
 
     test_if_unmodified_since {
         // Testcase from RFC
@@ -42,5 +42,3 @@ header! {
 bench_header!(imf_fixdate, IfUnmodifiedSince, { vec![b"Mon, 07 Nov 1994 08:48:37 GMT".to_vec()] });
 bench_header!(rfc_850, IfUnmodifiedSince, { vec![b"Sunday, 06-Nov-94 08:49:37 GMT".to_vec()] });
 bench_header!(asctime, IfUnmodifiedSince, { vec![b"Sun Nov  6 08:49:37 1994".to_vec()] });
-
-standard_header!(IfUnmodifiedSince, IF_UNMODIFIED_SINCE);
@@ -30,7 +30,7 @@ header! {
     /// let modified = SystemTime::now() - Duration::from_secs(60 * 60 * 24);
     /// headers.encode(&LastModified(modified.into()));
     /// ```
-    (LastModified, "Last-Modified") => [HttpDate]
+    (LastModified, "Last-Modified", LAST_MODIFIED) => date [HttpDate]
 
     test_last_modified {
         // Testcase from RFC
@@ -40,5 +40,3 @@ header! {
 bench_header!(imf_fixdate, LastModified, { vec![b"Mon, 07 Nov 1994 08:48:37 GMT".to_vec()] });
 bench_header!(rfc_850, LastModified, { vec![b"Sunday, 06-Nov-94 08:49:37 GMT".to_vec()] });
 bench_header!(asctime, LastModified, { vec![b"Sun Nov  6 08:49:37 1994".to_vec()] });
-
-standard_header!(LastModified, LAST_MODIFIED);
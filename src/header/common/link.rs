@@ -390,6 +390,8 @@ impl Header for Link {
         NAME
     }
 
+    standard_header!(fn LINK);
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<Link>
     where T: RawLike<'a>
     {
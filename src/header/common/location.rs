@@ -35,7 +35,7 @@ header! {
     /// headers.encode(&Location::new("http://www.example.com/index.html"));
     /// ```
     // TODO: Use URL
-    (Location, "Location") => Cow[str]
+    (Location, "Location", LOCATION) => Cow[str]
 
     test_location {
         // Testcase from RFC
@@ -46,5 +46,3 @@ header! {
 }
 
 bench_header!(bench, Location, { vec![b"http://foo.com/hello:3000".to_vec()] });
-
-standard_header!(Location, LOCATION);
@@ -19,8 +19,11 @@ pub use self::access_control_expose_headers::AccessControlExposeHeaders;
 pub use self::access_control_max_age::AccessControlMaxAge;
 pub use self::access_control_request_headers::AccessControlRequestHeaders;
 pub use self::access_control_request_method::AccessControlRequestMethod;
+pub use self::age::Age;
 pub use self::allow::Allow;
-pub use self::authorization::{Authorization, Scheme, Basic, Bearer};
+pub use self::alt_svc::{AltService, AltSvc};
+pub use self::alt_used::AltUsed;
+pub use self::authorization::{Authorization, Scheme, Basic, Bearer, Credentials};
 pub use self::cache_control::{CacheControl, CacheDirective};
 pub use self::connection::{Connection, ConnectionOption};
 pub use self::content_disposition::{ContentDisposition, DispositionType, DispositionParam};
@@ -29,12 +32,18 @@ pub use self::content_language::ContentLanguage;
 pub use self::content_length::ContentLength;
 pub use self::content_location::ContentLocation;
 pub use self::content_range::{ContentRange, ContentRangeSpec};
+pub use self::content_security_policy::{
+    ContentSecurityPolicy, ContentSecurityPolicyReportOnly, CspDirective,
+    CspDirectiveValue, CspHashAlgorithm, CspHostSource, CspKeyword, CspPolicy,
+    CspSource};
 pub use self::content_type::ContentType;
 pub use self::cookie::{Cookie, CookieIter};
 pub use self::date::Date;
+pub use self::digest_auth::{Digest, DigestAlgorithm, DigestChallenge, DigestQop};
 pub use self::etag::ETag;
 pub use self::expect::Expect;
 pub use self::expires::Expires;
+pub use self::forwarded::{Forwarded, ForwardedElement, ForwardedNode, NodeName, NodePort};
 pub use self::from::From;
 pub use self::host::Host;
 pub use self::if_match::IfMatch;
@@ -50,6 +59,8 @@ pub use self::origin::Origin;
 pub use self::pragma::Pragma;
 pub use self::prefer::{Prefer, Preference};
 pub use self::preference_applied::PreferenceApplied;
+pub use self::priority::Priority;
+pub use self::proxy_authenticate::ProxyAuthenticate;
 pub use self::proxy_authorization::ProxyAuthorization;
 pub use self::range::{Range, ByteRangeSpec};
 pub use self::referer::Referer;
@@ -59,11 +70,16 @@ pub use self::server::Server;
 pub use self::set_cookie::SetCookie;
 pub use self::strict_transport_security::StrictTransportSecurity;
 pub use self::te::Te;
+pub use self::trailer::Trailer;
 pub use self::transfer_encoding::TransferEncoding;
 pub use self::upgrade::{Upgrade, Protocol, ProtocolName};
 pub use self::user_agent::UserAgent;
 pub use self::vary::Vary;
 pub use self::warning::Warning;
+pub use self::www_authenticate::{WwwAuthenticate, Challenge};
+pub use self::x_forwarded_for::XForwardedFor;
+pub use self::x_forwarded_host::XForwardedHost;
+pub use self::x_forwarded_proto::XForwardedProto;
 
 #[doc(hidden)]
 #[macro_export]
@@ -194,10 +210,12 @@ macro_rules! header {
     // $a:meta: Attributes associated with the header item (usually docs)
     // $id:ident: Identifier of the header
     // $n:expr: Lowercase name of the header
+    // $hname:tt: Optional `http::header` constant or lowercase literal name,
+    //            for a standard header
     // $nn:expr: Nice name of the header
 
     // List header, zero or more items
-    ($(#[$a:meta])*($id:ident, $n:expr) => ($item:ty)*) => {
+    ($(#[$a:meta])*($id:ident, $n:expr $(, $hname:tt)?) => ($item:ty)*) => {
         $(#[$a])*
         #[derive(Clone, Debug, PartialEq)]
         pub struct $id(pub Vec<$item>);
@@ -207,17 +225,27 @@ macro_rules! header {
                 static NAME: &'static str = $n;
                 NAME
             }
+            $(standard_header!(fn $hname);)?
             #[inline]
             fn parse_header<'a, T>(raw: &'a T) -> $crate::Result<Self>
             where T: $crate::header::RawLike<'a>
             {
                 $crate::header::parsing::from_comma_delimited(raw).map($id)
             }
+            fn parse_header_detailed<'a, T>(raw: &'a T)
+                -> ::std::result::Result<Self, $crate::HeaderError>
+            where T: $crate::header::RawLike<'a>
+            {
+                $crate::header::parsing::from_comma_delimited_detailed(raw)
+                    .map($id)
+                    .map_err(|e| e.with_name($n))
+            }
             #[inline]
             fn fmt_header(&self, f: &mut $crate::header::Formatter) -> ::std::fmt::Result {
                 f.fmt_line(self)
             }
         }
+        $(standard_header!($id, $hname);)?
         impl ::std::fmt::Display for $id {
             #[inline]
             fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
@@ -226,7 +254,7 @@ macro_rules! header {
         }
     };
     // List header, one or more items
-    ($(#[$a:meta])*($id:ident, $n:expr) => ($item:ty)+) => {
+    ($(#[$a:meta])*($id:ident, $n:expr $(, $hname:tt)?) => ($item:ty)+) => {
         $(#[$a])*
         #[derive(Clone, Debug, PartialEq)]
         pub struct $id(pub Vec<$item>);
@@ -237,17 +265,27 @@ macro_rules! header {
                 static NAME: &'static str = $n;
                 NAME
             }
+            $(standard_header!(fn $hname);)?
             #[inline]
             fn parse_header<'a, T>(raw: &'a T) -> $crate::Result<Self>
             where T: $crate::header::RawLike<'a>
             {
                 $crate::header::parsing::from_comma_delimited(raw).map($id)
             }
+            fn parse_header_detailed<'a, T>(raw: &'a T)
+                -> ::std::result::Result<Self, $crate::HeaderError>
+            where T: $crate::header::RawLike<'a>
+            {
+                $crate::header::parsing::from_comma_delimited_detailed(raw)
+                    .map($id)
+                    .map_err(|e| e.with_name($n))
+            }
             #[inline]
             fn fmt_header(&self, f: &mut $crate::header::Formatter) -> ::std::fmt::Result {
                 f.fmt_line(self)
             }
         }
+        $(standard_header!($id, $hname);)?
         impl ::std::fmt::Display for $id {
             #[inline]
             fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
@@ -256,7 +294,32 @@ macro_rules! header {
         }
     };
     // Single value header
-    ($(#[$a:meta])*($id:ident, $n:expr) => [$value:ty]) => {
+    ($(#[$a:meta])*($id:ident, $n:expr $(, $hname:tt)?) => [$value:ty]) => {
+        header! {
+            @single
+            $(#[$a])*
+            ($id, $n $(, $hname)?) => [$value] from_one_raw_str_detailed
+        }
+    };
+    // Single value header, a number of decimal digits
+    ($(#[$a:meta])*($id:ident, $n:expr $(, $hname:tt)?) => number [$value:ty]) => {
+        header! {
+            @single
+            $(#[$a])*
+            ($id, $n $(, $hname)?) => [$value] from_one_raw_number_detailed
+        }
+    };
+    // Single value header, a date
+    ($(#[$a:meta])*($id:ident, $n:expr $(, $hname:tt)?) => date [$value:ty]) => {
+        header! {
+            @single
+            $(#[$a])*
+            ($id, $n $(, $hname)?) => [$value] from_one_raw_date_detailed
+        }
+    };
+    // Single value header (internal), with the `parsing` function for
+    // `parse_header_detailed`
+    (@single $(#[$a:meta])*($id:ident, $n:expr $(, $hname:tt)?) => [$value:ty] $detailed:ident) => {
         $(#[$a])*
         #[derive(Clone, Debug, PartialEq)]
         pub struct $id(pub $value);
@@ -267,17 +330,27 @@ macro_rules! header {
                 static NAME: &'static str = $n;
                 NAME
             }
+            $(standard_header!(fn $hname);)?
             #[inline]
             fn parse_header<'a, T>(raw: &'a T) -> $crate::Result<Self>
             where T: $crate::header::RawLike<'a>
             {
                 $crate::header::parsing::from_one_raw_str(raw).map($id)
             }
+            fn parse_header_detailed<'a, T>(raw: &'a T)
+                -> ::std::result::Result<Self, $crate::HeaderError>
+            where T: $crate::header::RawLike<'a>
+            {
+                $crate::header::parsing::$detailed(raw)
+                    .map($id)
+                    .map_err(|e| e.with_name($n))
+            }
             #[inline]
             fn fmt_header(&self, f: &mut $crate::header::Formatter) -> ::std::fmt::Result {
                 f.fmt_line(self)
             }
         }
+        $(standard_header!($id, $hname);)?
         impl ::std::fmt::Display for $id {
             #[inline]
             fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
@@ -286,7 +359,7 @@ macro_rules! header {
         }
     };
     // Single value header (internal)
-    ($(#[$a:meta])*($id:ident, $n:expr) => danger [$value:ty]) => {
+    ($(#[$a:meta])*($id:ident, $n:expr $(, $hname:tt)?) => danger [$value:ty]) => {
         $(#[$a])*
         #[derive(Clone, Debug, PartialEq)]
         pub struct $id(pub $value);
@@ -297,17 +370,27 @@ macro_rules! header {
                 static NAME: &'static str = $n;
                 NAME
             }
+            $(standard_header!(fn $hname);)?
             #[inline]
             fn parse_header<'a, T>(raw: &'a T) -> $crate::Result<Self>
             where T: $crate::header::RawLike<'a>
             {
                 $crate::header::parsing::from_one_raw_str(raw).map($id)
             }
+            fn parse_header_detailed<'a, T>(raw: &'a T)
+                -> ::std::result::Result<Self, $crate::HeaderError>
+            where T: $crate::header::RawLike<'a>
+            {
+                $crate::header::parsing::from_one_raw_str_detailed(raw)
+                    .map($id)
+                    .map_err(|e| e.with_name($n))
+            }
             #[inline]
             fn fmt_header(&self, f: &mut $crate::header::Formatter) -> ::std::fmt::Result {
                 f.danger_fmt_line_without_newline_replacer(self)
             }
         }
+        $(standard_header!($id, $hname);)?
         impl ::std::fmt::Display for $id {
             #[inline]
             fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
@@ -316,7 +399,7 @@ macro_rules! header {
         }
     };
     // Single value cow header
-    ($(#[$a:meta])*($id:ident, $n:expr) => Cow[$value:ty]) => {
+    ($(#[$a:meta])*($id:ident, $n:expr $(, $hname:tt)?) => Cow[$value:ty]) => {
         $(#[$a])*
         #[derive(Clone, Debug, PartialEq)]
         pub struct $id(::std::borrow::Cow<'static,$value>);
@@ -339,17 +422,27 @@ macro_rules! header {
                 static NAME: &'static str = $n;
                 NAME
             }
+            $(standard_header!(fn $hname);)?
             #[inline]
             fn parse_header<'a, T>(raw: &'a T) -> $crate::Result<Self>
             where T: $crate::header::RawLike<'a>
             {
                 $crate::header::parsing::from_one_raw_str::<_, <$value as ::std::borrow::ToOwned>::Owned>(raw).map($id::new)
             }
+            fn parse_header_detailed<'a, T>(raw: &'a T)
+                -> ::std::result::Result<Self, $crate::HeaderError>
+            where T: $crate::header::RawLike<'a>
+            {
+                $crate::header::parsing::from_one_raw_str_detailed::<_, <$value as ::std::borrow::ToOwned>::Owned>(raw)
+                    .map($id::new)
+                    .map_err(|e| e.with_name($n))
+            }
             #[inline]
             fn fmt_header(&self, f: &mut $crate::header::Formatter) -> ::std::fmt::Result {
                 f.fmt_line(self)
             }
         }
+        $(standard_header!($id, $hname);)?
         impl ::std::fmt::Display for $id {
             #[inline]
             fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
@@ -358,7 +451,7 @@ macro_rules! header {
         }
     };
     // List header, one or more items with "*" option
-    ($(#[$a:meta])*($id:ident, $n:expr) => {Any / ($item:ty)+}) => {
+    ($(#[$a:meta])*($id:ident, $n:expr $(, $hname:tt)?) => {Any / ($item:ty)+}) => {
         $(#[$a])*
         #[derive(Clone, Debug, PartialEq)]
         pub enum $id {
@@ -373,6 +466,7 @@ macro_rules! header {
                 static NAME: &'static str = $n;
                 NAME
             }
+            $(standard_header!(fn $hname);)?
             #[inline]
             fn parse_header<'a, T>(raw: &'a T) -> $crate::Result<Self>
             where T: $crate::header::RawLike<'a>
@@ -385,11 +479,25 @@ macro_rules! header {
                 }
                 $crate::header::parsing::from_comma_delimited(raw).map($id::Items)
             }
+            fn parse_header_detailed<'a, T>(raw: &'a T)
+                -> ::std::result::Result<Self, $crate::HeaderError>
+            where T: $crate::header::RawLike<'a>
+            {
+                if let Some(l) = raw.one() {
+                    if l == b"*" {
+                        return Ok($id::Any)
+                    }
+                }
+                $crate::header::parsing::from_comma_delimited_detailed(raw)
+                    .map($id::Items)
+                    .map_err(|e| e.with_name($n))
+            }
             #[inline]
             fn fmt_header(&self, f: &mut $crate::header::Formatter) -> ::std::fmt::Result {
                 f.fmt_line(self)
             }
         }
+        $(standard_header!($id, $hname);)?
         impl ::std::fmt::Display for $id {
             #[inline]
             fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
@@ -403,50 +511,66 @@ macro_rules! header {
     };
 
     // optional test module
-    ($(#[$a:meta])*($id:ident, $n:expr) => ($item:ty)* $tm:ident{$($tf:item)*}) => {
+    ($(#[$a:meta])*($id:ident, $n:expr $(, $hname:tt)?) => ($item:ty)* $tm:ident{$($tf:item)*}) => {
         header! {
             $(#[$a])*
-            ($id, $n) => ($item)*
+            ($id, $n $(, $hname)?) => ($item)*
         }
 
         __hyper__tm! { $id, $tm { $($tf)* }}
     };
-    ($(#[$a:meta])*($id:ident, $n:expr) => ($item:ty)+ $tm:ident{$($tf:item)*}) => {
+    ($(#[$a:meta])*($id:ident, $n:expr $(, $hname:tt)?) => ($item:ty)+ $tm:ident{$($tf:item)*}) => {
         header! {
             $(#[$a])*
-            ($id, $n) => ($item)+
+            ($id, $n $(, $hname)?) => ($item)+
         }
 
         __hyper__tm! { $id, $tm { $($tf)* }}
     };
-    ($(#[$a:meta])*($id:ident, $n:expr) => [$item:ty] $tm:ident{$($tf:item)*}) => {
+    ($(#[$a:meta])*($id:ident, $n:expr $(, $hname:tt)?) => [$item:ty] $tm:ident{$($tf:item)*}) => {
         header! {
             $(#[$a])*
-            ($id, $n) => [$item]
+            ($id, $n $(, $hname)?) => [$item]
         }
 
         __hyper__tm! { $id, $tm { $($tf)* }}
     };
-    ($(#[$a:meta])*($id:ident, $n:expr) => danger [$item:ty] $tm:ident{$($tf:item)*}) => {
+    ($(#[$a:meta])*($id:ident, $n:expr $(, $hname:tt)?) => number [$item:ty] $tm:ident{$($tf:item)*}) => {
         header! {
             $(#[$a])*
-            ($id, $n) => danger [$item]
+            ($id, $n $(, $hname)?) => number [$item]
         }
 
         __hyper__tm! { $id, $tm { $($tf)* }}
     };
-    ($(#[$a:meta])*($id:ident, $n:expr) => Cow[$item:ty] $tm:ident{$($tf:item)*}) => {
+    ($(#[$a:meta])*($id:ident, $n:expr $(, $hname:tt)?) => date [$item:ty] $tm:ident{$($tf:item)*}) => {
         header! {
             $(#[$a])*
-            ($id, $n) => Cow[$item]
+            ($id, $n $(, $hname)?) => date [$item]
         }
 
         __hyper__tm! { $id, $tm { $($tf)* }}
     };
-    ($(#[$a:meta])*($id:ident, $n:expr) => {Any / ($item:ty)+} $tm:ident{$($tf:item)*}) => {
+    ($(#[$a:meta])*($id:ident, $n:expr $(, $hname:tt)?) => danger [$item:ty] $tm:ident{$($tf:item)*}) => {
         header! {
             $(#[$a])*
-            ($id, $n) => {Any / ($item)+}
+            ($id, $n $(, $hname)?) => danger [$item]
+        }
+
+        __hyper__tm! { $id, $tm { $($tf)* }}
+    };
+    ($(#[$a:meta])*($id:ident, $n:expr $(, $hname:tt)?) => Cow[$item:ty] $tm:ident{$($tf:item)*}) => {
+        header! {
+            $(#[$a])*
+            ($id, $n $(, $hname)?) => Cow[$item]
+        }
+
+        __hyper__tm! { $id, $tm { $($tf)* }}
+    };
+    ($(#[$a:meta])*($id:ident, $n:expr $(, $hname:tt)?) => {Any / ($item:ty)+} $tm:ident{$($tf:item)*}) => {
+        header! {
+            $(#[$a])*
+            ($id, $n $(, $hname)?) => {Any / ($item)+}
         }
 
         __hyper__tm! { $id, $tm { $($tf)* }}
@@ -456,6 +580,19 @@ macro_rules! header {
 #[doc(hidden)]
 #[macro_export]
 macro_rules! standard_header {
+    // Within `impl Header`, override `Header::http_header_name`
+    (fn $hname:ident) => {
+        #[inline]
+        fn http_header_name() -> ::http::header::HeaderName {
+            ::http::header::$hname
+        }
+    };
+    (fn $hname:literal) => {
+        #[inline]
+        fn http_header_name() -> ::http::header::HeaderName {
+            ::http::header::HeaderName::from_static($hname)
+        }
+    };
     ($local:ident, $hname:ident) => {
         impl $crate::header::StandardHeader for $local {
             #[inline]
@@ -463,7 +600,15 @@ macro_rules! standard_header {
                 ::http::header::$hname
             }
         }
-    }
+    };
+    ($local:ident, $hname:literal) => {
+        impl $crate::header::StandardHeader for $local {
+            #[inline]
+            fn http_header_name() -> ::http::header::HeaderName {
+                ::http::header::HeaderName::from_static($hname)
+            }
+        }
+    };
 }
 
 mod accept_charset;
@@ -479,7 +624,10 @@ mod access_control_expose_headers;
 mod access_control_max_age;
 mod access_control_request_headers;
 mod access_control_request_method;
+mod age;
 mod allow;
+mod alt_svc;
+mod alt_used;
 mod authorization;
 mod cache_control;
 mod connection;
@@ -489,12 +637,15 @@ mod content_language;
 mod content_length;
 mod content_location;
 mod content_range;
+mod content_security_policy;
 mod content_type;
 mod cookie;
 mod date;
+mod digest_auth;
 mod etag;
 mod expect;
 mod expires;
+mod forwarded;
 mod from;
 mod host;
 mod if_match;
@@ -510,6 +661,8 @@ mod origin;
 mod pragma;
 mod prefer;
 mod preference_applied;
+mod priority;
+mod proxy_authenticate;
 mod proxy_authorization;
 mod range;
 mod referer;
@@ -519,8 +672,13 @@ mod server;
 mod set_cookie;
 mod strict_transport_security;
 mod te;
+mod trailer;
 mod transfer_encoding;
 mod upgrade;
 mod user_agent;
 mod vary;
 mod warning;
+mod www_authenticate;
+mod x_forwarded_for;
+mod x_forwarded_host;
+mod x_forwarded_proto;
@@ -106,6 +106,8 @@ impl Header for Origin {
         NAME
     }
 
+    standard_header!(fn ORIGIN);
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<Origin>
     where T: RawLike<'a>
     {
@@ -48,6 +48,8 @@ impl Header for Pragma {
         NAME
     }
 
+    standard_header!(fn PRAGMA);
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<Pragma>
     where T: RawLike<'a>
     {
@@ -1,7 +1,9 @@
 use std::fmt;
 use std::str::FromStr;
+use HeaderError;
 use header::{Header, RawLike};
-use header::parsing::{from_comma_delimited, fmt_comma_delimited};
+use header::parsing::{diagnose, from_comma_delimited, from_comma_delimited_detailed,
+                      fmt_comma_delimited, fmt_token_or_quoted, split_unquoted, unquote};
 
 /// `Prefer` header, defined in [RFC7240](http://tools.ietf.org/html/rfc7240)
 ///
@@ -74,6 +76,18 @@ impl Header for Prefer {
         }
     }
 
+    fn parse_header_detailed<'a, T>(raw: &'a T) -> Result<Prefer, HeaderError>
+    where T: RawLike<'a>
+    {
+        let preferences = from_comma_delimited_detailed(raw)
+            .map_err(|e| e.with_name(Self::header_name()))?;
+        if !preferences.is_empty() {
+            Ok(Prefer(preferences))
+        } else {
+            Err(diagnose::<Self, T>(raw))
+        }
+    }
+
     fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
         f.fmt_line(self)
     }
@@ -120,11 +134,17 @@ impl fmt::Display for Preference {
 
             Extension(ref name, ref value, ref params) => {
                 write!(f, "{}", name)?;
-                if value != "" { write!(f, "={}", value)?; }
+                if value != "" {
+                    f.write_str("=")?;
+                    fmt_token_or_quoted(f, value)?;
+                }
                 if !params.is_empty() {
                     for &(ref name, ref value) in params {
                         write!(f, "; {}", name)?;
-                        if value != "" { write!(f, "={}", value)?; }
+                        if value != "" {
+                            f.write_str("=")?;
+                            fmt_token_or_quoted(f, value)?;
+                        }
                     }
                 }
                 return Ok(());
@@ -137,22 +157,22 @@ impl FromStr for Preference {
     type Err = Option<<u32 as FromStr>::Err>;
     fn from_str(s: &str) -> Result<Preference, Option<<u32 as FromStr>::Err>> {
         use self::Preference::*;
-        let mut params = s.split(';').map(|p| {
+        let mut params = Vec::new();
+        for p in split_unquoted(s, b';').map_err(|_| None)? {
             let mut param = p.splitn(2, '=');
-            match (param.next(), param.next()) {
-                (Some(name), Some(value)) => (name.trim(), value.trim().trim_matches('"')),
-                (Some(name), None) => (name.trim(), ""),
-                // This can safely be unreachable because the [`splitn`][1]
-                // function (used above) will always have at least one value.
-                //
-                // [1]: http://doc.rust-lang.org/std/primitive.str.html#method.splitn
-                _ => { unreachable!(); }
-            }
-        });
+            let name = param.next().unwrap_or("").trim().to_owned();
+            let value = match param.next().map(str::trim) {
+                Some(value) if value.starts_with('"') => unquote(value).map_err(|_| None)?,
+                Some(value) => value.to_owned(),
+                None => String::new(),
+            };
+            params.push((name, value));
+        }
+        let mut params = params.into_iter();
         match params.nth(0) {
             Some(param) => {
-                let rest: Vec<(String, String)> = params.map(|(l, r)| (l.to_owned(), r.to_owned())).collect();
-                match param {
+                let rest: Vec<(String, String)> = params.collect();
+                match (&param.0[..], &param.1[..]) {
                     ("respond-async", "") => if rest.is_empty() { Ok(RespondAsync) } else { Err(None) },
                     ("return", "representation") => if rest.is_empty() { Ok(ReturnRepresentation) } else { Err(None) },
                     ("return", "minimal") => if rest.is_empty() { Ok(ReturnMinimal) } else { Err(None) },
@@ -210,6 +230,17 @@ mod tests {
             Preference::Extension("buz".to_owned(), "some parameter".to_owned(), vec![])])))
     }
 
+    #[test]
+    fn test_parse_quoted_delimiters() {
+        let r: Raw = "foo=\"a, b; c\"; bar=\"\\\"d\\\"\", baz".into();
+        let prefer: Prefer = Header::parse_header(&r).unwrap();
+        assert_eq!(prefer, Prefer(vec![
+            Preference::Extension("foo".to_owned(), "a, b; c".to_owned(),
+                                  vec![("bar".to_owned(), "\"d\"".to_owned())]),
+            Preference::Extension("baz".to_owned(), "".to_owned(), vec![])]));
+        assert_eq!(prefer.to_string(), "foo=\"a, b; c\"; bar=\"\\\"d\\\"\", baz");
+    }
+
     #[test]
     fn test_fail_with_args() {
         let r: Raw = "respond-async; foo=bar".into();
@@ -1,6 +1,8 @@
 use std::fmt;
+use HeaderError;
 use header::{Header, RawLike, Preference};
-use header::parsing::{from_comma_delimited, fmt_comma_delimited};
+use header::parsing::{diagnose, from_comma_delimited, from_comma_delimited_detailed,
+                      fmt_comma_delimited};
 
 /// `Preference-Applied` header, defined in [RFC7240](http://tools.ietf.org/html/rfc7240)
 ///
@@ -75,6 +77,18 @@ impl Header for PreferenceApplied {
         }
     }
 
+    fn parse_header_detailed<'a, T>(raw: &'a T) -> Result<PreferenceApplied, HeaderError>
+    where T: RawLike<'a>
+    {
+        let preferences = from_comma_delimited_detailed(raw)
+            .map_err(|e| e.with_name(Self::header_name()))?;
+        if !preferences.is_empty() {
+            Ok(PreferenceApplied(preferences))
+        } else {
+            Err(diagnose::<Self, T>(raw))
+        }
+    }
+
     fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
         f.fmt_line(self)
     }
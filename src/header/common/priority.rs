@@ -0,0 +1,161 @@
+use std::fmt;
+
+use header::{Header, RawLike};
+use header::structured::{BareItem, Dictionary, Item, Member, parse_dictionary};
+
+/// `Priority` header, defined in [RFC9218](https://www.rfc-editor.org/rfc/rfc9218#section-5)
+///
+/// The `Priority` header field carries priority parameters for a request, as
+/// a Structured Field Dictionary. A client uses it to signal the urgency of
+/// a response and whether it can be processed incrementally, and a server
+/// may send it to indicate a different priority.
+///
+/// Unknown parameters, and known parameters with an invalid type or value,
+/// are ignored, leaving the default.
+///
+/// # ABNF
+///
+/// ```text
+/// Priority = sf-dictionary
+/// ```
+///
+/// # Example values
+///
+/// * `u=5`
+/// * `u=0, i`
+/// * `i=?0, u=7, x=unknown`
+///
+/// # Examples
+///
+/// ```
+/// # extern crate http;
+/// use hyperx::header::{Priority, TypedHeaders};
+///
+/// let mut headers = http::HeaderMap::new();
+/// headers.insert("priority", "u=1, i, x=42".parse().unwrap());
+/// let priority: Priority = headers.decode().unwrap();
+/// assert_eq!(priority, Priority { urgency: 1, incremental: true });
+///
+/// headers.encode(&Priority { urgency: 5, ..Default::default() });
+/// assert_eq!(headers["priority"], "u=5");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Priority {
+    /// The urgency, from 0 (highest) to 7 (lowest). Values above 7 are
+    /// formatted as 7.
+    pub urgency: u8,
+    /// Whether the response can be processed incrementally.
+    pub incremental: bool,
+}
+
+impl Priority {
+    /// The default urgency, 3.
+    pub const DEFAULT_URGENCY: u8 = 3;
+}
+
+impl Default for Priority {
+    fn default() -> Priority {
+        Priority {
+            urgency: Priority::DEFAULT_URGENCY,
+            incremental: false,
+        }
+    }
+}
+
+impl Header for Priority {
+    fn header_name() -> &'static str {
+        static NAME: &str = "Priority";
+        NAME
+    }
+
+    standard_header!(fn "priority");
+
+    fn parse_header<'a, T>(raw: &'a T) -> ::Result<Priority>
+    where T: RawLike<'a>
+    {
+        let dict = parse_dictionary(raw)?;
+        let mut priority = Priority::default();
+        if let Some(urgency) = bare_item(&dict, "u").and_then(BareItem::as_integer) {
+            if (0..=7).contains(&urgency) {
+                priority.urgency = urgency as u8;
+            }
+        }
+        if let Some(incremental) = bare_item(&dict, "i").and_then(BareItem::as_bool) {
+            priority.incremental = incremental;
+        }
+        Ok(priority)
+    }
+
+    fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
+        f.fmt_line(self)
+    }
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut dict = Dictionary::new();
+        dict.insert("u", Item::new(i64::from(self.urgency.min(7))));
+        if self.incremental {
+            dict.insert("i", Item::new(true));
+        }
+        fmt::Display::fmt(&dict, f)
+    }
+}
+
+fn bare_item<'a>(dict: &'a Dictionary, key: &str) -> Option<&'a BareItem> {
+    dict.get(key).and_then(Member::as_item).map(|item| &item.bare_item)
+}
+
+#[cfg(test)]
+mod tests {
+    use header::{Header, Raw};
+    use super::Priority;
+
+    fn parse(s: &str) -> ::Result<Priority> {
+        let r: Raw = s.into();
+        Header::parse_header(&r)
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse("u=5").unwrap(), Priority { urgency: 5, incremental: false });
+        assert_eq!(parse("u=0, i").unwrap(), Priority { urgency: 0, incremental: true });
+        assert_eq!(parse("i=?0, u=7").unwrap(), Priority { urgency: 7, incremental: false });
+        assert_eq!(parse("").unwrap(), Priority::default());
+    }
+
+    #[test]
+    fn test_parse_ignored() {
+        // Unknown parameters, and known ones with invalid values
+        assert_eq!(parse("x=1, y=(a b);c").unwrap(), Priority::default());
+        assert_eq!(parse("u=8, i=1").unwrap(), Priority::default());
+        assert_eq!(parse("u=-1, i=\"yes\"").unwrap(), Priority::default());
+        assert_eq!(parse("u=1.0").unwrap(), Priority::default());
+        assert_eq!(parse("u=(1), i;foo").unwrap(), Priority { urgency: 3, incremental: true });
+    }
+
+    #[test]
+    fn test_parse_multiple_lines() {
+        let r: Raw = vec![b"u=6".to_vec(), b"i, u=2".to_vec()].into();
+        let priority: Priority = Header::parse_header(&r).unwrap();
+        assert_eq!(priority, Priority { urgency: 2, incremental: true });
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(parse("u=").is_err());
+        assert!(parse("U=1").is_err());
+        assert!(parse("u=1,").is_err());
+    }
+
+    #[test]
+    fn test_fmt() {
+        assert_eq!(Priority::default().to_string(), "u=3");
+        assert_eq!(Priority { urgency: 0, incremental: true }.to_string(), "u=0, i");
+        assert_eq!(Priority { urgency: 9, incremental: false }.to_string(), "u=7");
+    }
+}
+
+bench_header!(bench, Priority, { vec![b"u=1, i".to_vec()] });
+
+standard_header!(Priority, "priority");
@@ -0,0 +1,103 @@
+use std::fmt;
+
+use header::{Challenge, Header, RawLike};
+use header::parsing::fmt_comma_delimited;
+use super::www_authenticate::parse_challenges;
+
+/// `Proxy-Authenticate` header, defined in [RFC7235](https://tools.ietf.org/html/rfc7235#section-4.3)
+///
+/// The `Proxy-Authenticate` header field consists of at least one challenge
+/// that indicates the authentication scheme(s) and parameters applicable to
+/// the proxy for this effective request URI. A proxy MUST send at least one
+/// `Proxy-Authenticate` header field in each 407 (Proxy Authentication
+/// Required) response that it generates.
+///
+/// Unlike `WWW-Authenticate`, this header field applies only to the next
+/// outbound client on the response chain.
+///
+/// # ABNF
+///
+/// ```text
+/// Proxy-Authenticate = 1#challenge
+/// ```
+///
+/// # Example values
+///
+/// * `Basic realm="proxy"`
+/// * `Digest realm="proxy", nonce="dcd98b7102dd2f0e8b11d0f600bfb0c093"`
+///
+/// # Examples
+///
+/// ```
+/// # extern crate http;
+/// use hyperx::header::{Challenge, ProxyAuthenticate, TypedHeaders};
+///
+/// let mut headers = http::HeaderMap::new();
+/// headers.encode(&ProxyAuthenticate(vec![
+///     Challenge::new("Basic").push_param("realm", "proxy"),
+/// ]));
+/// let auth: ProxyAuthenticate = headers.decode().unwrap();
+/// assert_eq!(auth[0].realm(), Some("proxy"));
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct ProxyAuthenticate(pub Vec<Challenge>);
+
+__hyper__deref!(ProxyAuthenticate => Vec<Challenge>);
+
+impl Header for ProxyAuthenticate {
+    fn header_name() -> &'static str {
+        static NAME: &str = "Proxy-Authenticate";
+        NAME
+    }
+
+    standard_header!(fn PROXY_AUTHENTICATE);
+
+    fn parse_header<'a, T>(raw: &'a T) -> ::Result<ProxyAuthenticate>
+    where T: RawLike<'a>
+    {
+        parse_challenges(raw).map(ProxyAuthenticate)
+    }
+
+    fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
+        f.fmt_line(self)
+    }
+}
+
+impl fmt::Display for ProxyAuthenticate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_comma_delimited(f, &self[..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use header::{Challenge, Header, Raw};
+    use super::ProxyAuthenticate;
+
+    #[test]
+    fn test_parse() {
+        let r: Raw = vec![
+            b"Basic realm=\"proxy\"".to_vec(),
+            b"Bearer realm=\"proxy\", error=\"invalid_token\"".to_vec(),
+        ].into();
+        let auth: ProxyAuthenticate = Header::parse_header(&r).unwrap();
+        assert_eq!(auth, ProxyAuthenticate(vec![
+            Challenge::new("Basic").push_param("realm", "proxy"),
+            Challenge::new("Bearer")
+                .push_param("realm", "proxy")
+                .push_param("error", "invalid_token"),
+        ]));
+        assert_eq!(
+            auth.to_string(),
+            "Basic realm=\"proxy\", Bearer realm=\"proxy\", error=invalid_token");
+    }
+
+    #[test]
+    fn test_parse_empty() {
+        let r: Raw = "".into();
+        let auth: ::Result<ProxyAuthenticate> = Header::parse_header(&r);
+        assert!(auth.is_err());
+    }
+}
+
+standard_header!(ProxyAuthenticate, PROXY_AUTHENTICATE);
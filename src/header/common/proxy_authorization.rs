@@ -1,8 +1,9 @@
 use std::any::Any;
 use std::fmt;
-use std::str::{FromStr, from_utf8};
+use std::str::FromStr;
 use std::ops::{Deref, DerefMut};
 use header::{Header, RawLike, Scheme};
+use super::authorization::parse_scheme;
 
 /// `Proxy-Authorization` header, defined in [RFC7235](https://tools.ietf.org/html/rfc7235#section-4.4)
 ///
@@ -59,6 +60,9 @@ use header::{Header, RawLike, Scheme};
 ///        }
 ///    )
 /// );
+///
+/// let auth: ProxyAuthorization<Bearer> = headers.decode().unwrap();
+/// assert_eq!(auth.token, "QWxhZGRpbjpvcGVuIHNlc2FtZQ");
 /// ```
 #[derive(Clone, PartialEq, Debug)]
 pub struct ProxyAuthorization<S: Scheme>(pub S);
@@ -83,29 +87,12 @@ impl<S: Scheme + Any> Header for ProxyAuthorization<S> where <S as FromStr>::Err
         NAME
     }
 
+    standard_header!(fn PROXY_AUTHORIZATION);
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<ProxyAuthorization<S>>
     where T: RawLike<'a>
     {
-        if let Some(line) = raw.one() {
-            let header = from_utf8(line)?;
-            if let Some(scheme) = <S as Scheme>::scheme() {
-                if header.starts_with(scheme) && header.len() > scheme.len() + 1 {
-                    match header[scheme.len() + 1..].parse::<S>().map(ProxyAuthorization) {
-                        Ok(h) => Ok(h),
-                        Err(_) => Err(::Error::Header)
-                    }
-                } else {
-                    Err(::Error::Header)
-                }
-            } else {
-                match header.parse::<S>().map(ProxyAuthorization) {
-                    Ok(h) => Ok(h),
-                    Err(_) => Err(::Error::Header)
-                }
-            }
-        } else {
-            Err(::Error::Header)
-        }
+        parse_scheme(raw).map(ProxyAuthorization)
     }
 
     fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
@@ -197,6 +184,13 @@ mod tests {
         let auth: ProxyAuthorization<Bearer> = Header::parse_header(&r).unwrap();
         assert_eq!(auth.0.token, "fpKL54jvWmEGVoRdCNjG");
     }
+
+    #[test]
+    fn test_scheme_case_insensitive() {
+        let r: Raw = b"bASIC QWxhZGRpbjpvcGVuIHNlc2FtZQ==".as_ref().into();
+        let auth: ProxyAuthorization<Basic> = Header::parse_header(&r).unwrap();
+        assert_eq!(auth.0.username, "Aladdin");
+    }
 }
 
 #[cfg(test)]
@@ -261,6 +261,8 @@ impl Header for Range {
         NAME
     }
 
+    standard_header!(fn RANGE);
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<Range>
     where T: RawLike<'a>
     {
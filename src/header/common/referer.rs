@@ -36,7 +36,7 @@ header! {
     /// headers.encode(&Referer::new("http://www.example.com/index.html"));
     /// ```
     // TODO Use URL
-    (Referer, "Referer") => Cow[str]
+    (Referer, "Referer", REFERER) => Cow[str]
 
     test_referer {
         // Testcase from the RFC
@@ -45,5 +45,3 @@ header! {
 }
 
 bench_header!(bench, Referer, { vec![b"http://foo.com/hello:3000".to_vec()] });
-
-standard_header!(Referer, REFERER);
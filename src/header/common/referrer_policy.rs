@@ -59,6 +59,8 @@ impl Header for ReferrerPolicy {
         NAME
     }
 
+    standard_header!(fn REFERRER_POLICY);
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<ReferrerPolicy>
     where T: RawLike<'a>
     {
@@ -92,6 +92,8 @@ impl Header for RetryAfter {
         NAME
     }
 
+    standard_header!(fn RETRY_AFTER);
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<RetryAfter>
     where T: RawLike<'a>
     {
@@ -28,7 +28,7 @@ header! {
     /// headers.encode(&Server::new("hyper/0.5.2"));
     /// ```
     // TODO: Maybe parse as defined in the spec?
-    (Server, "Server") => Cow[str]
+    (Server, "Server", SERVER) => Cow[str]
 
     test_server {
         // Testcase from RFC
@@ -37,5 +37,3 @@ header! {
 }
 
 bench_header!(bench, Server, { vec![b"Some String".to_vec()] });
-
-standard_header!(Server, SERVER);
@@ -87,6 +87,8 @@ impl Header for SetCookie {
         NAME
     }
 
+    standard_header!(fn SET_COOKIE);
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<SetCookie>
     where T: RawLike<'a>
     {
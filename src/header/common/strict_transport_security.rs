@@ -128,6 +128,8 @@ impl Header for StrictTransportSecurity {
         NAME
     }
 
+    standard_header!(fn STRICT_TRANSPORT_SECURITY);
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<StrictTransportSecurity>
     where T: RawLike<'a>
     {
@@ -63,7 +63,7 @@ header! {
     ///     ])
     /// );
     /// ```
-    (Te, "TE") => (QualityItem<Encoding>)*
+    (Te, "TE", TE) => (QualityItem<Encoding>)*
 
     test_te {
         // From the RFC
@@ -72,5 +72,3 @@ header! {
         test_header!(test3, vec![b""]);
     }
 }
-
-standard_header!(Te, TE);
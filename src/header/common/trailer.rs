@@ -0,0 +1,59 @@
+use unicase::Ascii;
+
+header! {
+    /// `Trailer` header, defined in
+    /// [RFC7230](http://tools.ietf.org/html/rfc7230#section-4.4)
+    ///
+    /// When a message includes a message body encoded with the chunked
+    /// transfer coding and the sender desires to send metadata in the form
+    /// of trailer fields at the end of the message, the sender SHOULD
+    /// generate a "Trailer" header field before the message body to indicate
+    /// which fields will be present in the trailers. See `header::trailers`
+    /// for checking the trailer fields actually sent.
+    ///
+    /// # ABNF
+    ///
+    /// ```text
+    /// Trailer = 1#field-name
+    /// ```
+    ///
+    /// # Example values
+    ///
+    /// * `grpc-status, grpc-message`
+    /// * `Server-Timing`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate http;
+    /// # extern crate hyperx;
+    /// # extern crate unicase;
+    /// # fn main() {
+    /// // extern crate unicase;
+    ///
+    /// use hyperx::header::{Trailer, TypedHeaders};
+    /// use unicase::Ascii;
+    ///
+    /// let mut headers = http::HeaderMap::new();
+    /// headers.encode(
+    ///     &Trailer(vec![
+    ///         Ascii::new("grpc-status".to_owned()),
+    ///         Ascii::new("grpc-message".to_owned()),
+    ///     ])
+    /// );
+    /// # }
+    /// ```
+    (Trailer, "Trailer", TRAILER) => (Ascii<String>)+
+
+    test_trailer {
+        test_header!(test1, vec![b"grpc-status, grpc-message"]);
+        test_header!(test2, vec![b"Server-Timing"]);
+
+        #[test]
+        fn test_case_insensitive() {
+            let r: Raw = "Grpc-Status".into();
+            let trailer: Trailer = Header::parse_header(&r).unwrap();
+            assert_eq!(trailer, Trailer(vec!["grpc-status".parse().unwrap()]));
+        }
+    }
+}
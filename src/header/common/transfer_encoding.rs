@@ -40,7 +40,7 @@ header! {
     ///     ])
     /// );
     /// ```
-    (TransferEncoding, "Transfer-Encoding") => (Encoding)+
+    (TransferEncoding, "Transfer-Encoding", TRANSFER_ENCODING) => (Encoding)+
 
     transfer_encoding {
         test_header!(
@@ -69,5 +69,3 @@ impl TransferEncoding {
 
 bench_header!(normal, TransferEncoding, { vec![b"chunked, gzip".to_vec()] });
 bench_header!(ext, TransferEncoding, { vec![b"ext".to_vec()] });
-
-standard_header!(TransferEncoding, TRANSFER_ENCODING);
@@ -54,7 +54,7 @@ header! {
     ///     ])
     /// );
     /// ```
-    (Upgrade, "Upgrade") => (Protocol)+
+    (Upgrade, "Upgrade", UPGRADE) => (Protocol)+
 
     test_upgrade {
         // Testcase from the RFC
@@ -166,5 +166,3 @@ impl Display for Protocol {
 }
 
 bench_header!(bench, Upgrade, { vec![b"HTTP/2.0, RTA/x11, websocket".to_vec()] });
-
-standard_header!(Upgrade, UPGRADE);
@@ -36,7 +36,7 @@ header! {
     /// let mut headers = http::HeaderMap::new();
     /// headers.encode(&UserAgent::new("hyper/0.5.2"));
     /// ```
-    (UserAgent, "User-Agent") => Cow[str]
+    (UserAgent, "User-Agent", USER_AGENT) => Cow[str]
 
     test_user_agent {
         // Testcase from RFC
@@ -45,5 +45,3 @@ header! {
         test_header!(test2, vec![b"Bunnies"], Some(UserAgent::new("Bunnies")));
     }
 }
-
-standard_header!(UserAgent, USER_AGENT);
@@ -51,7 +51,7 @@ header! {
     /// );
     /// # }
     /// ```
-    (Vary, "Vary") => {Any / (Ascii<String>)+}
+    (Vary, "Vary", VARY) => {Any / (Ascii<String>)+}
 
     test_vary {
         test_header!(test1, vec![b"accept-encoding, accept-language"]);
@@ -75,5 +75,3 @@ header! {
         }
     }
 }
-
-standard_header!(Vary, VARY);
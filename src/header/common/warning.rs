@@ -97,6 +97,8 @@ impl Header for Warning {
         NAME
     }
 
+    standard_header!(fn WARNING);
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<Warning>
     where T: RawLike<'a>
     {
@@ -0,0 +1,390 @@
+use std::fmt;
+use std::str::{FromStr, from_utf8};
+
+use header::{Header, RawLike};
+use header::parsing::{fmt_comma_delimited, fmt_quoted, is_token, split_unquoted, unquote};
+
+/// `WWW-Authenticate` header, defined in [RFC7235](https://tools.ietf.org/html/rfc7235#section-4.1)
+///
+/// The `WWW-Authenticate` header field indicates the authentication
+/// scheme(s) and parameters applicable to the target resource. It is sent
+/// with 401 (Unauthorized) responses, and may contain more than one
+/// challenge, possibly spread over more than one field line.
+///
+/// # ABNF
+///
+/// ```text
+/// WWW-Authenticate = 1#challenge
+///
+/// challenge   = auth-scheme [ 1*SP ( token68 / #auth-param ) ]
+/// auth-scheme = token
+/// auth-param  = token BWS "=" BWS ( token / quoted-string )
+/// token68     = 1*( ALPHA / DIGIT /
+///                   "-" / "." / "_" / "~" / "+" / "/" ) *"="
+/// ```
+///
+/// # Example values
+///
+/// * `Basic realm="simple"`
+/// * `Newauth realm="apps", type=1, title="Login to \"apps\"", Basic realm="simple"`
+///
+/// # Examples
+///
+/// ```
+/// # extern crate http;
+/// use hyperx::header::{Challenge, TypedHeaders, WwwAuthenticate};
+///
+/// let mut headers = http::HeaderMap::new();
+/// headers.encode(&WwwAuthenticate(vec![
+///     Challenge::new("Bearer")
+///         .push_param("realm", "example")
+///         .push_param("error", "invalid_token"),
+///     Challenge::new("Basic")
+///         .push_param("realm", "example"),
+/// ]));
+/// assert_eq!(
+///     headers["www-authenticate"],
+///     "Bearer realm=\"example\", error=invalid_token, Basic realm=\"example\""
+/// );
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct WwwAuthenticate(pub Vec<Challenge>);
+
+__hyper__deref!(WwwAuthenticate => Vec<Challenge>);
+
+impl Header for WwwAuthenticate {
+    fn header_name() -> &'static str {
+        static NAME: &str = "WWW-Authenticate";
+        NAME
+    }
+
+    standard_header!(fn WWW_AUTHENTICATE);
+
+    fn parse_header<'a, T>(raw: &'a T) -> ::Result<WwwAuthenticate>
+    where T: RawLike<'a>
+    {
+        parse_challenges(raw).map(WwwAuthenticate)
+    }
+
+    fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
+        f.fmt_line(self)
+    }
+}
+
+impl fmt::Display for WwwAuthenticate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_comma_delimited(f, &self[..])
+    }
+}
+
+/// A single authentication challenge, as sent in `WWW-Authenticate` and
+/// `Proxy-Authenticate`.
+///
+/// A challenge consists of an auth-scheme, optionally followed by either a
+/// `token68` value or a list of auth-params. Parameter values are stored
+/// unquoted, and are quoted as needed when formatted.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Challenge {
+    /// The auth-scheme, such as `Basic` or `Bearer`.
+    pub scheme: String,
+    /// An opaque `token68` value. A challenge with a `token68` has no
+    /// `params`: if both are set, only the `token68` is formatted.
+    pub token68: Option<String>,
+    /// The auth-params as (name, value) pairs, in received order.
+    pub params: Vec<(String, String)>,
+}
+
+impl Challenge {
+    /// Create a new challenge for the given auth-scheme, without parameters.
+    pub fn new<S: Into<String>>(scheme: S) -> Challenge {
+        Challenge {
+            scheme: scheme.into(),
+            token68: None,
+            params: Vec::new(),
+        }
+    }
+
+    /// Set the `token68` value of this challenge, removing any auth-params.
+    pub fn set_token68<S: Into<String>>(mut self, token68: S) -> Challenge {
+        self.token68 = Some(token68.into());
+        self.params.clear();
+        self
+    }
+
+    /// Append an auth-param to this challenge, removing any `token68`.
+    pub fn push_param<N, V>(mut self, name: N, value: V) -> Challenge
+        where N: Into<String>, V: Into<String>
+    {
+        self.token68 = None;
+        self.params.push((name.into(), value.into()));
+        self
+    }
+
+    /// Return the value of the first auth-param with the given name,
+    /// compared case-insensitively.
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params.iter()
+            .find(|p| p.0.eq_ignore_ascii_case(name))
+            .map(|p| &p.1[..])
+    }
+
+    /// Return the value of the `realm` auth-param, if present.
+    pub fn realm(&self) -> Option<&str> {
+        self.param("realm")
+    }
+
+    /// Return true if the auth-scheme matches the given name,
+    /// case-insensitively.
+    pub fn is_scheme(&self, scheme: &str) -> bool {
+        self.scheme.eq_ignore_ascii_case(scheme)
+    }
+}
+
+impl fmt::Display for Challenge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.scheme)?;
+        match self.token68 {
+            Some(ref token68) => write!(f, " {}", token68),
+            None => fmt_auth_params(f, &self.params),
+        }
+    }
+}
+
+impl FromStr for Challenge {
+    type Err = ::Error;
+
+    fn from_str(s: &str) -> ::Result<Challenge> {
+        let mut challenges = Vec::new();
+        parse_auth_list(s, &mut challenges)?;
+        if challenges.len() == 1 {
+            Ok(challenges.pop().unwrap())
+        } else {
+            Err(::Error::Header)
+        }
+    }
+}
+
+/// Parse all values (lines) as a list of one or more challenges.
+pub fn parse_challenges<'a, T>(raw: &'a T) -> ::Result<Vec<Challenge>>
+    where T: RawLike<'a>
+{
+    let mut challenges = Vec::new();
+    for line in raw.iter() {
+        parse_auth_list(from_utf8(line)?, &mut challenges)?;
+    }
+    if challenges.is_empty() {
+        Err(::Error::Header)
+    } else {
+        Ok(challenges)
+    }
+}
+
+/// Parse a comma-separated list of auth-params, as following an auth-scheme,
+/// unquoting values.
+pub fn parse_auth_params(s: &str) -> ::Result<Vec<(String, String)>> {
+    let mut params = Vec::new();
+    for element in split_unquoted(s, b',')? {
+        let element = element.trim();
+        if !element.is_empty() {
+            params.push(parse_auth_param(element)?);
+        }
+    }
+    Ok(params)
+}
+
+/// Format auth-params, as following an auth-scheme, including the leading
+/// space.
+///
+/// The `realm` value is always quoted, as required by RFC 7235. Other values
+/// are quoted only if they are not a valid token.
+pub fn fmt_auth_params(f: &mut fmt::Formatter, params: &[(String, String)])
+    -> fmt::Result
+{
+    for (i, (name, value)) in params.iter().enumerate() {
+        f.write_str(if i == 0 { " " } else { ", " })?;
+        f.write_str(name)?;
+        f.write_str("=")?;
+        if name.eq_ignore_ascii_case("realm") || !is_token(value) {
+            fmt_quoted(f, value)?;
+        } else {
+            f.write_str(value)?;
+        }
+    }
+    Ok(())
+}
+
+// Parse a single line of comma-separated challenges (or credentials),
+// appending to `out`. Commas delimit both challenges and the auth-params
+// within a challenge, so an element starting with `token 1*SP` begins a new
+// challenge, while a `token = value` element continues the prior one.
+fn parse_auth_list(s: &str, out: &mut Vec<Challenge>) -> ::Result<()> {
+    for element in split_unquoted(s, b',')? {
+        let element = element.trim();
+        if element.is_empty() {
+            continue;
+        }
+        let end = element.find(|c| c == ' ' || c == '\t' || c == '=')
+            .unwrap_or(element.len());
+        let rest = element[end..].trim_start_matches(|c| c == ' ' || c == '\t');
+        if rest.starts_with('=') {
+            // Continuation auth-param of the current challenge
+            let param = parse_auth_param(element)?;
+            match out.last_mut() {
+                Some(c) if c.token68.is_none() => c.params.push(param),
+                _ => return Err(::Error::Header),
+            }
+            continue;
+        }
+
+        let scheme = &element[..end];
+        if !is_token(scheme) {
+            return Err(::Error::Header);
+        }
+        let mut challenge = Challenge::new(scheme);
+        if rest.is_empty() {
+            // No token68 or auth-params
+        } else if is_token68(rest) {
+            challenge.token68 = Some(rest.to_owned());
+        } else {
+            challenge.params.push(parse_auth_param(rest)?);
+        }
+        out.push(challenge);
+    }
+    Ok(())
+}
+
+// Parse a `token BWS "=" BWS ( token / quoted-string )` auth-param.
+fn parse_auth_param(s: &str) -> ::Result<(String, String)> {
+    let eq = s.find('=').ok_or(::Error::Header)?;
+    let name = s[..eq].trim();
+    let value = s[eq + 1..].trim();
+    if !is_token(name) {
+        return Err(::Error::Header);
+    }
+    let value = if value.starts_with('"') {
+        unquote(value)?
+    } else if is_token(value) {
+        value.to_owned()
+    } else {
+        return Err(::Error::Header);
+    };
+    Ok((name.to_owned(), value))
+}
+
+fn is_token68(s: &str) -> bool {
+    let value = s.trim_end_matches('=');
+    !value.is_empty() && value.bytes().all(|b| matches!(b,
+        b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' |
+        b'-' | b'.' | b'_' | b'~' | b'+' | b'/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use header::{Header, Raw};
+    use super::*;
+
+    #[test]
+    fn test_parse_single() {
+        let r: Raw = "Basic realm=\"simple\"".into();
+        let auth: WwwAuthenticate = Header::parse_header(&r).unwrap();
+        assert_eq!(auth, WwwAuthenticate(vec![
+            Challenge::new("Basic").push_param("realm", "simple")
+        ]));
+        assert_eq!(auth[0].realm(), Some("simple"));
+    }
+
+    #[test]
+    fn test_parse_multiple_challenges() {
+        // From RFC 7235, section 4.1
+        let r: Raw = "Newauth realm=\"apps\", type=1, \
+                      title=\"Login to \\\"apps\\\"\", Basic realm=\"simple\"".into();
+        let auth: WwwAuthenticate = Header::parse_header(&r).unwrap();
+        assert_eq!(auth, WwwAuthenticate(vec![
+            Challenge::new("Newauth")
+                .push_param("realm", "apps")
+                .push_param("type", "1")
+                .push_param("title", "Login to \"apps\""),
+            Challenge::new("Basic").push_param("realm", "simple"),
+        ]));
+        assert_eq!(
+            auth.to_string(),
+            "Newauth realm=\"apps\", type=1, \
+             title=\"Login to \\\"apps\\\"\", Basic realm=\"simple\"");
+    }
+
+    #[test]
+    fn test_parse_multiple_lines() {
+        let r: Raw = vec![
+            b"Bearer realm=\"a, b\", scope=\"read write\"".to_vec(),
+            b"Negotiate".to_vec(),
+            b"Negotiate YIIBzgYGKwYBBQUCoIIBwjCCAb6gMDAuBgorBgEEAYI3AgIKBgkqhkiC9xIBAgIG==".to_vec(),
+        ].into();
+        let auth: WwwAuthenticate = Header::parse_header(&r).unwrap();
+        assert_eq!(auth.len(), 3);
+        assert!(auth[0].is_scheme("bearer"));
+        assert_eq!(auth[0].realm(), Some("a, b"));
+        assert_eq!(auth[0].param("SCOPE"), Some("read write"));
+        assert_eq!(auth[1], Challenge::new("Negotiate"));
+        assert_eq!(
+            auth[2].token68.as_ref().map(|s| &s[..]),
+            Some("YIIBzgYGKwYBBQUCoIIBwjCCAb6gMDAuBgorBgEEAYI3AgIKBgkqhkiC9xIBAgIG=="));
+        assert!(auth[2].params.is_empty());
+    }
+
+    #[test]
+    fn test_parse_bws() {
+        let r: Raw = "Basic realm = \"simple\" , charset= UTF-8".into();
+        let auth: WwwAuthenticate = Header::parse_header(&r).unwrap();
+        assert_eq!(auth, WwwAuthenticate(vec![
+            Challenge::new("Basic")
+                .push_param("realm", "simple")
+                .push_param("charset", "UTF-8")
+        ]));
+        assert_eq!(auth.to_string(), "Basic realm=\"simple\", charset=UTF-8");
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for bad in &[
+            "",
+            "realm=\"simple\"",
+            "Basic realm=\"unterminated",
+            "Basic realm=bad value",
+            "Negotiate abc==, realm=\"x\"",
+            "B@sic realm=\"x\"",
+        ] {
+            let r: Raw = (*bad).into();
+            let auth: ::Result<WwwAuthenticate> = Header::parse_header(&r);
+            assert!(auth.is_err(), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn test_challenge_from_str() {
+        let c: Challenge = "Bearer error=\"invalid_token\"".parse().unwrap();
+        assert_eq!(c.param("error"), Some("invalid_token"));
+        assert!("Basic, Bearer".parse::<Challenge>().is_err());
+    }
+
+    #[test]
+    fn test_fmt_token68() {
+        let c = Challenge::new("Negotiate").set_token68("a87421000492aa874209af8bc028");
+        assert_eq!(c.to_string(), "Negotiate a87421000492aa874209af8bc028");
+
+        let c = Challenge::new("Negotiate").push_param("realm", "x").set_token68("abc==");
+        assert_eq!(c.to_string(), "Negotiate abc==");
+        let c = Challenge::new("Negotiate").set_token68("abc==").push_param("realm", "x");
+        assert_eq!(c.to_string(), "Negotiate realm=\"x\"");
+        let c = Challenge {
+            scheme: "Negotiate".to_owned(),
+            token68: Some("abc==".to_owned()),
+            params: vec![("realm".to_owned(), "x".to_owned())],
+        };
+        assert_eq!(c.to_string(), "Negotiate abc==");
+    }
+}
+
+bench_header!(bench, WwwAuthenticate,
+              { vec![b"Newauth realm=\"apps\", type=1, Basic realm=\"simple\"".to_vec()] });
+
+standard_header!(WwwAuthenticate, WWW_AUTHENTICATE);
@@ -0,0 +1,142 @@
+use std::fmt;
+use std::net::IpAddr;
+use std::str::from_utf8;
+
+use header::{ForwardedNode, Header, NodeName, RawLike};
+
+/// `X-Forwarded-For` header, a de-facto standard
+///
+/// The `X-Forwarded-For` header identifies the originating IP address of a
+/// client connecting through proxies; each proxy appends the address it
+/// received the request from. It is superseded by the `for` parameter of the
+/// [`Forwarded`](struct.Forwarded.html) header, and can be converted from it.
+///
+/// Elements are parsed as IP addresses, including unbracketed IPv6 addresses,
+/// or else as `Forwarded` node identifiers. An IPv6 address is only formatted
+/// in brackets when it has a port.
+///
+/// # ABNF
+///
+/// ```text
+/// X-Forwarded-For = 1#node
+/// ```
+///
+/// # Example values
+///
+/// * `192.0.2.43`
+/// * `203.0.113.195, 2001:db8:85a3::8a2e:370:7334`
+/// * `[2001:db8::1]:4711, unknown`
+///
+/// # Examples
+///
+/// ```
+/// # extern crate http;
+/// use hyperx::header::{XForwardedFor, TypedHeaders};
+///
+/// let mut headers = http::HeaderMap::new();
+/// headers.insert("x-forwarded-for", "203.0.113.195, 2001:db8::1".parse().unwrap());
+///
+/// let xff: XForwardedFor = headers.decode().unwrap();
+/// assert_eq!(xff[1].ip(), Some("2001:db8::1".parse().unwrap()));
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct XForwardedFor(pub Vec<ForwardedNode>);
+
+__hyper__deref!(XForwardedFor => Vec<ForwardedNode>);
+
+impl Header for XForwardedFor {
+    fn header_name() -> &'static str {
+        static NAME: &str = "X-Forwarded-For";
+        NAME
+    }
+
+    standard_header!(fn "x-forwarded-for");
+
+    fn parse_header<'a, T>(raw: &'a T) -> ::Result<XForwardedFor>
+    where T: RawLike<'a>
+    {
+        let mut nodes = Vec::new();
+        for line in raw.iter() {
+            for node in from_utf8(line)?.split(',') {
+                let node = node.trim();
+                if node.is_empty() {
+                    continue;
+                }
+                nodes.push(match node.parse::<IpAddr>() {
+                    Ok(ip) => ip.into(),
+                    Err(_) => node.parse()?,
+                });
+            }
+        }
+        if nodes.is_empty() {
+            Err(::Error::Header)
+        } else {
+            Ok(XForwardedFor(nodes))
+        }
+    }
+
+    fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
+        f.fmt_line(self)
+    }
+}
+
+impl fmt::Display for XForwardedFor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, node) in self.iter().enumerate() {
+            if i != 0 {
+                f.write_str(", ")?;
+            }
+            match node.name {
+                NodeName::Ipv6(ref ip) if node.port.is_none() => fmt::Display::fmt(ip, f)?,
+                _ => fmt::Display::fmt(node, f)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+
+    use header::{ForwardedNode, Header, NodeName, NodePort, Raw};
+    use super::XForwardedFor;
+
+    fn parse(s: &str) -> ::Result<XForwardedFor> {
+        let r: Raw = s.into();
+        Header::parse_header(&r)
+    }
+
+    #[test]
+    fn test_parse() {
+        let xff = parse("203.0.113.195, 2001:db8:85a3::8a2e:370:7334").unwrap();
+        assert_eq!(xff.len(), 2);
+        assert_eq!(xff[0].ip(), Some("203.0.113.195".parse::<IpAddr>().unwrap()));
+        assert_eq!(xff[1].ip(), Some("2001:db8:85a3::8a2e:370:7334".parse::<IpAddr>().unwrap()));
+        assert_eq!(xff.to_string(), "203.0.113.195, 2001:db8:85a3::8a2e:370:7334");
+
+        let xff = parse("[2001:db8::1]:4711,unknown, _hidden").unwrap();
+        assert_eq!(xff[0].port, Some(NodePort::Port(4711)));
+        assert_eq!(xff[1], ForwardedNode::new(NodeName::Unknown));
+        assert_eq!(xff[2], ForwardedNode::new(NodeName::Obfuscated("_hidden".to_owned())));
+        assert_eq!(xff.to_string(), "[2001:db8::1]:4711, unknown, _hidden");
+    }
+
+    #[test]
+    fn test_parse_multiple_lines() {
+        let r: Raw = vec![b"192.0.2.1".to_vec(), b"192.0.2.2".to_vec()].into();
+        let xff: XForwardedFor = Header::parse_header(&r).unwrap();
+        assert_eq!(xff.to_string(), "192.0.2.1, 192.0.2.2");
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(parse("").is_err());
+        assert!(parse("192.0.2.1, proxy.example").is_err());
+        assert!(parse("[::1").is_err());
+    }
+}
+
+bench_header!(bench, XForwardedFor, { vec![b"203.0.113.195, 2001:db8::1".to_vec()] });
+
+standard_header!(XForwardedFor, "x-forwarded-for");
@@ -0,0 +1,38 @@
+header! {
+    /// `X-Forwarded-Host` header, a de-facto standard
+    ///
+    /// The `X-Forwarded-Host` header identifies the original `Host` requested
+    /// by the client, before a reverse proxy rewrote it. It is superseded by
+    /// the `host` parameter of the [`Forwarded`](struct.Forwarded.html)
+    /// header, and can be converted from it.
+    ///
+    /// # ABNF
+    ///
+    /// ```text
+    /// X-Forwarded-Host = 1#host
+    /// ```
+    ///
+    /// # Example values
+    ///
+    /// * `example.com`
+    /// * `example.com:8080, proxy.example`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate http;
+    /// use hyperx::header::{XForwardedHost, TypedHeaders};
+    ///
+    /// let mut headers = http::HeaderMap::new();
+    /// headers.encode(&XForwardedHost(vec!["example.com".to_owned()]));
+    /// assert_eq!(headers["x-forwarded-host"], "example.com");
+    /// ```
+    (XForwardedHost, "X-Forwarded-Host", "x-forwarded-host") => (String)+
+
+    test_x_forwarded_host {
+        test_header!(test1, vec![b"example.com"]);
+        test_header!(test2, vec![b"example.com:8080, proxy.example"]);
+    }
+}
+
+bench_header!(bench, XForwardedHost, { vec![b"example.com:8080".to_vec()] });
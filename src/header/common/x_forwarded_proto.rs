@@ -0,0 +1,39 @@
+header! {
+    /// `X-Forwarded-Proto` header, a de-facto standard
+    ///
+    /// The `X-Forwarded-Proto` header identifies the protocol (`http` or
+    /// `https`) the client used to connect to a proxy or load balancer. It is
+    /// superseded by the `proto` parameter of the
+    /// [`Forwarded`](struct.Forwarded.html) header, and can be converted from
+    /// it.
+    ///
+    /// # ABNF
+    ///
+    /// ```text
+    /// X-Forwarded-Proto = 1#uri-scheme
+    /// ```
+    ///
+    /// # Example values
+    ///
+    /// * `https`
+    /// * `https, http`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate http;
+    /// use hyperx::header::{XForwardedProto, TypedHeaders};
+    ///
+    /// let mut headers = http::HeaderMap::new();
+    /// headers.encode(&XForwardedProto(vec!["https".to_owned()]));
+    /// assert_eq!(headers["x-forwarded-proto"], "https");
+    /// ```
+    (XForwardedProto, "X-Forwarded-Proto", "x-forwarded-proto") => (String)+
+
+    test_x_forwarded_proto {
+        test_header!(test1, vec![b"https"]);
+        test_header!(test2, vec![b"https, http"]);
+    }
+}
+
+bench_header!(bench, XForwardedProto, { vec![b"https".to_vec()] });
@@ -0,0 +1,402 @@
+//! Evaluation of conditional requests, as specified in
+//! [RFC 9110](https://www.rfc-editor.org/rfc/rfc9110#section-13.2).
+//!
+//! `Preconditions` holds the `If-Match`, `If-None-Match`,
+//! `If-Modified-Since`, `If-Unmodified-Since` and `If-Range` headers of a
+//! request. `Preconditions::evaluate` compares them with the current state of
+//! the target `Resource`, in the order and with the entity-tag comparison
+//! functions required by the specification, and returns the `Outcome`.
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate http;
+//! # extern crate hyperx;
+//! use hyperx::header::EntityTag;
+//! use hyperx::header::conditional::{Outcome, Preconditions, Resource};
+//!
+//! # fn main() {
+//! let mut headers = http::HeaderMap::new();
+//! headers.insert("if-none-match", "W/\"xyzzy\", \"r2d2xxxx\"".parse().unwrap());
+//!
+//! let resource = Resource::existing().with_etag(EntityTag::strong("xyzzy".to_owned()));
+//! let conditions = Preconditions::from_headers(&headers);
+//! assert_eq!(conditions.evaluate(&http::Method::GET, &resource), Outcome::NotModified);
+//! assert_eq!(conditions.evaluate(&http::Method::PUT, &resource), Outcome::PreconditionFailed);
+//! # }
+//! ```
+
+use http::{Method, StatusCode};
+use http::header::HeaderMap;
+
+use header::{EntityTag, HttpDate, IfMatch, IfModifiedSince, IfNoneMatch, IfRange,
+             IfUnmodifiedSince, TypedHeaders};
+
+/// The current state of the target resource of a request.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Resource {
+    /// Whether the resource has a current representation.
+    pub exists: bool,
+    /// The entity-tag of the selected representation, if any.
+    pub etag: Option<EntityTag>,
+    /// The last modification date of the selected representation, if any.
+    pub last_modified: Option<HttpDate>,
+}
+
+impl Resource {
+    /// A resource with a current representation, without validators.
+    pub fn existing() -> Resource {
+        Resource {
+            exists: true,
+            ..Resource::default()
+        }
+    }
+
+    /// A resource without a current representation.
+    pub fn missing() -> Resource {
+        Resource::default()
+    }
+
+    /// Set the entity-tag.
+    pub fn with_etag(mut self, etag: EntityTag) -> Resource {
+        self.etag = Some(etag);
+        self
+    }
+
+    /// Set the last modification date.
+    pub fn with_last_modified(mut self, date: HttpDate) -> Resource {
+        self.last_modified = Some(date);
+        self
+    }
+}
+
+/// The outcome of evaluating the preconditions of a request.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Outcome {
+    /// The preconditions hold (or are absent): perform the method.
+    Proceed,
+    /// Respond with 304 Not Modified, for GET and HEAD.
+    NotModified,
+    /// Respond with 412 Precondition Failed.
+    PreconditionFailed,
+}
+
+impl Outcome {
+    /// Return the response status for the outcome, or `None` for `Proceed`.
+    pub fn status(&self) -> Option<StatusCode> {
+        match *self {
+            Outcome::Proceed => None,
+            Outcome::NotModified => Some(StatusCode::NOT_MODIFIED),
+            Outcome::PreconditionFailed => Some(StatusCode::PRECONDITION_FAILED),
+        }
+    }
+}
+
+/// The conditional headers of a request.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Preconditions {
+    if_match: Option<IfMatch>,
+    if_none_match: Option<IfNoneMatch>,
+    if_modified_since: Option<IfModifiedSince>,
+    if_unmodified_since: Option<IfUnmodifiedSince>,
+    if_range: Option<Option<IfRange>>,
+}
+
+impl Preconditions {
+    /// Create without any conditional headers.
+    pub fn new() -> Preconditions {
+        Preconditions::default()
+    }
+
+    /// Read the conditional headers of a request.
+    ///
+    /// A malformed `If-Match` or `If-None-Match` is treated as a list which
+    /// matches no entity-tag, so a malformed `If-Match` fails. A malformed
+    /// or multi-valued date is ignored, as required. A malformed `If-Range`
+    /// never matches, so the `Range` is ignored.
+    pub fn from_headers(headers: &HeaderMap) -> Preconditions {
+        Preconditions {
+            if_match: headers.try_decode()
+                .map(|r| r.unwrap_or_else(|_| IfMatch::Items(Vec::new()))),
+            if_none_match: headers.try_decode()
+                .map(|r| r.unwrap_or_else(|_| IfNoneMatch::Items(Vec::new()))),
+            if_modified_since: headers.try_decode().and_then(Result::ok),
+            if_unmodified_since: headers.try_decode().and_then(Result::ok),
+            if_range: headers.try_decode().map(Result::ok),
+        }
+    }
+
+    /// Set the `If-Match` header.
+    pub fn with_if_match(mut self, if_match: IfMatch) -> Preconditions {
+        self.if_match = Some(if_match);
+        self
+    }
+
+    /// Set the `If-None-Match` header.
+    pub fn with_if_none_match(mut self, if_none_match: IfNoneMatch) -> Preconditions {
+        self.if_none_match = Some(if_none_match);
+        self
+    }
+
+    /// Set the `If-Modified-Since` header.
+    pub fn with_if_modified_since(mut self, date: IfModifiedSince) -> Preconditions {
+        self.if_modified_since = Some(date);
+        self
+    }
+
+    /// Set the `If-Unmodified-Since` header.
+    pub fn with_if_unmodified_since(mut self, date: IfUnmodifiedSince) -> Preconditions {
+        self.if_unmodified_since = Some(date);
+        self
+    }
+
+    /// Set the `If-Range` header.
+    pub fn with_if_range(mut self, if_range: IfRange) -> Preconditions {
+        self.if_range = Some(Some(if_range));
+        self
+    }
+
+    /// Evaluate the preconditions for a request method on the resource.
+    ///
+    /// The steps of RFC 9110, section 13.2.2 are applied in order:
+    ///
+    /// 1. `If-Match`, by strong comparison; `*` matches if the resource
+    ///    exists. If false, 412.
+    /// 2. Without `If-Match`, `If-Unmodified-Since`, if the resource has a
+    ///    modification date. If modified since, 412.
+    /// 3. `If-None-Match`, by weak comparison; `*` matches if the resource
+    ///    exists. If matched, 304 for GET and HEAD, otherwise 412.
+    /// 4. Without `If-None-Match`, for GET and HEAD, `If-Modified-Since`, if
+    ///    the resource has a modification date. If not modified since, 304.
+    ///
+    /// `If-Range` is evaluated separately by `range_applies`. A server may
+    /// still respond with success instead of 412 for a state-changing method
+    /// whose change is already reflected in the resource, which is not
+    /// detected here.
+    pub fn evaluate(&self, method: &Method, resource: &Resource) -> Outcome {
+        if let Some(ref if_match) = self.if_match {
+            let matched = match *if_match {
+                IfMatch::Any => resource.exists,
+                IfMatch::Items(ref tags) => matches(tags, resource, EntityTag::strong_eq),
+            };
+            if !matched {
+                return Outcome::PreconditionFailed;
+            }
+        } else if let (Some(date), Some(last_modified)) =
+            (self.if_unmodified_since.as_ref(), resource.last_modified)
+        {
+            if last_modified > date.0 {
+                return Outcome::PreconditionFailed;
+            }
+        }
+
+        let get_or_head = *method == Method::GET || *method == Method::HEAD;
+        if let Some(ref if_none_match) = self.if_none_match {
+            let matched = match *if_none_match {
+                IfNoneMatch::Any => resource.exists,
+                IfNoneMatch::Items(ref tags) => matches(tags, resource, EntityTag::weak_eq),
+            };
+            if matched {
+                return if get_or_head {
+                    Outcome::NotModified
+                } else {
+                    Outcome::PreconditionFailed
+                };
+            }
+        } else if let (true, Some(date), Some(last_modified)) =
+            (get_or_head, self.if_modified_since.as_ref(), resource.last_modified)
+        {
+            if last_modified <= date.0 {
+                return Outcome::NotModified;
+            }
+        }
+
+        Outcome::Proceed
+    }
+
+    /// Evaluate `If-Range` (RFC 9110, section 13.1.5), returning true if a
+    /// `Range` of a GET request should be applied, or false if the full
+    /// representation should be sent instead.
+    ///
+    /// Without `If-Range` this is true. An entity-tag must match the current
+    /// one by strong comparison, and a date must exactly equal the last
+    /// modification date. A malformed `If-Range` never matches.
+    pub fn range_applies(&self, resource: &Resource) -> bool {
+        match self.if_range {
+            None => true,
+            Some(None) => false,
+            Some(Some(IfRange::EntityTag(ref tag))) => {
+                resource.etag.as_ref().map_or(false, |etag| etag.strong_eq(tag))
+            }
+            Some(Some(IfRange::Date(ref date))) => resource.last_modified == Some(*date),
+        }
+    }
+}
+
+fn matches(tags: &[EntityTag], resource: &Resource, eq: fn(&EntityTag, &EntityTag) -> bool)
+    -> bool
+{
+    match resource.etag {
+        Some(ref etag) if resource.exists => tags.iter().any(|tag| eq(tag, etag)),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use http::{Method, StatusCode};
+    use http::header::HeaderMap;
+
+    use header::{EntityTag, HttpDate, IfMatch, IfModifiedSince, IfNoneMatch, IfRange,
+                 IfUnmodifiedSince};
+    use super::{Outcome, Preconditions, Resource};
+
+    fn date(secs: u64) -> HttpDate {
+        (SystemTime::UNIX_EPOCH + Duration::from_secs(secs)).into()
+    }
+
+    fn resource() -> Resource {
+        Resource::existing()
+            .with_etag(EntityTag::strong("v2".to_owned()))
+            .with_last_modified(date(1_000_000))
+    }
+
+    fn tags(tags: &[&str]) -> Vec<EntityTag> {
+        tags.iter().map(|t| t.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_no_preconditions() {
+        let p = Preconditions::new();
+        assert_eq!(p.evaluate(&Method::GET, &resource()), Outcome::Proceed);
+        assert_eq!(p.evaluate(&Method::PUT, &Resource::missing()), Outcome::Proceed);
+        assert_eq!(Outcome::Proceed.status(), None);
+        assert_eq!(Outcome::NotModified.status(), Some(StatusCode::NOT_MODIFIED));
+    }
+
+    #[test]
+    fn test_if_match() {
+        let r = resource();
+        let p = Preconditions::new().with_if_match(IfMatch::Items(tags(&["\"v1\"", "\"v2\""])));
+        assert_eq!(p.evaluate(&Method::PUT, &r), Outcome::Proceed);
+
+        // Weak tags never match by strong comparison
+        let p = Preconditions::new().with_if_match(IfMatch::Items(tags(&["W/\"v2\""])));
+        assert_eq!(p.evaluate(&Method::PUT, &r), Outcome::PreconditionFailed);
+        let weak = Resource { etag: Some(EntityTag::weak("v2".to_owned())), ..r.clone() };
+        let p = Preconditions::new().with_if_match(IfMatch::Items(tags(&["\"v2\""])));
+        assert_eq!(p.evaluate(&Method::PUT, &weak), Outcome::PreconditionFailed);
+
+        let p = Preconditions::new().with_if_match(IfMatch::Any);
+        assert_eq!(p.evaluate(&Method::DELETE, &r), Outcome::Proceed);
+        assert_eq!(p.evaluate(&Method::PUT, &Resource::missing()), Outcome::PreconditionFailed);
+        assert_eq!(p.evaluate(&Method::GET, &Resource::existing()), Outcome::Proceed);
+    }
+
+    #[test]
+    fn test_if_unmodified_since() {
+        let r = resource();
+        let p = Preconditions::new().with_if_unmodified_since(IfUnmodifiedSince(date(1_000_000)));
+        assert_eq!(p.evaluate(&Method::PUT, &r), Outcome::Proceed);
+        let p = Preconditions::new().with_if_unmodified_since(IfUnmodifiedSince(date(999_999)));
+        assert_eq!(p.evaluate(&Method::PUT, &r), Outcome::PreconditionFailed);
+        // Ignored without a modification date
+        assert_eq!(p.evaluate(&Method::PUT, &Resource::existing()), Outcome::Proceed);
+        // Ignored when If-Match is present
+        let p = p.with_if_match(IfMatch::Any);
+        assert_eq!(p.evaluate(&Method::PUT, &r), Outcome::Proceed);
+    }
+
+    #[test]
+    fn test_if_none_match() {
+        let r = resource();
+        let p = Preconditions::new().with_if_none_match(IfNoneMatch::Items(tags(&["W/\"v2\""])));
+        assert_eq!(p.evaluate(&Method::GET, &r), Outcome::NotModified);
+        assert_eq!(p.evaluate(&Method::HEAD, &r), Outcome::NotModified);
+        assert_eq!(p.evaluate(&Method::POST, &r), Outcome::PreconditionFailed);
+
+        let p = Preconditions::new().with_if_none_match(IfNoneMatch::Items(tags(&["\"v1\""])));
+        assert_eq!(p.evaluate(&Method::GET, &r), Outcome::Proceed);
+
+        // Create only if missing
+        let p = Preconditions::new().with_if_none_match(IfNoneMatch::Any);
+        assert_eq!(p.evaluate(&Method::PUT, &Resource::missing()), Outcome::Proceed);
+        assert_eq!(p.evaluate(&Method::PUT, &r), Outcome::PreconditionFailed);
+    }
+
+    #[test]
+    fn test_if_modified_since() {
+        let r = resource();
+        let p = Preconditions::new().with_if_modified_since(IfModifiedSince(date(1_000_000)));
+        assert_eq!(p.evaluate(&Method::GET, &r), Outcome::NotModified);
+        assert_eq!(p.evaluate(&Method::HEAD, &r), Outcome::NotModified);
+        // Only for GET and HEAD
+        assert_eq!(p.evaluate(&Method::POST, &r), Outcome::Proceed);
+
+        let p = Preconditions::new().with_if_modified_since(IfModifiedSince(date(999_999)));
+        assert_eq!(p.evaluate(&Method::GET, &r), Outcome::Proceed);
+
+        // Ignored when If-None-Match is present
+        let p = Preconditions::new()
+            .with_if_modified_since(IfModifiedSince(date(1_000_000)))
+            .with_if_none_match(IfNoneMatch::Items(tags(&["\"v1\""])));
+        assert_eq!(p.evaluate(&Method::GET, &r), Outcome::Proceed);
+    }
+
+    #[test]
+    fn test_precedence() {
+        let r = resource();
+        // A failed If-Match takes precedence over a matched If-None-Match
+        let p = Preconditions::new()
+            .with_if_match(IfMatch::Items(tags(&["\"v1\""])))
+            .with_if_none_match(IfNoneMatch::Any);
+        assert_eq!(p.evaluate(&Method::GET, &r), Outcome::PreconditionFailed);
+
+        // A failed If-Unmodified-Since takes precedence over If-Modified-Since
+        let p = Preconditions::new()
+            .with_if_unmodified_since(IfUnmodifiedSince(date(1)))
+            .with_if_modified_since(IfModifiedSince(date(2_000_000)));
+        assert_eq!(p.evaluate(&Method::GET, &r), Outcome::PreconditionFailed);
+    }
+
+    #[test]
+    fn test_if_range() {
+        let r = resource();
+        assert!(Preconditions::new().range_applies(&r));
+        let p = Preconditions::new().with_if_range(IfRange::EntityTag("\"v2\"".parse().unwrap()));
+        assert!(p.range_applies(&r));
+        let p = Preconditions::new().with_if_range(IfRange::EntityTag("W/\"v2\"".parse().unwrap()));
+        assert!(!p.range_applies(&r));
+        let p = Preconditions::new().with_if_range(IfRange::Date(date(1_000_000)));
+        assert!(p.range_applies(&r));
+        let p = Preconditions::new().with_if_range(IfRange::Date(date(1_000_001)));
+        assert!(!p.range_applies(&r));
+        assert!(!p.range_applies(&Resource::existing()));
+    }
+
+    #[test]
+    fn test_from_headers() {
+        let r = resource();
+        let mut headers = HeaderMap::new();
+        headers.insert("if-match", "not-quoted".parse().unwrap());
+        let p = Preconditions::from_headers(&headers);
+        assert_eq!(p.evaluate(&Method::PUT, &r), Outcome::PreconditionFailed);
+
+        let mut headers = HeaderMap::new();
+        headers.insert("if-none-match", "\"v2\"".parse().unwrap());
+        headers.insert("if-modified-since", "invalid".parse().unwrap());
+        headers.insert("if-range", "invalid".parse().unwrap());
+        let p = Preconditions::from_headers(&headers);
+        assert_eq!(p.evaluate(&Method::GET, &r), Outcome::NotModified);
+        assert!(!p.range_applies(&r));
+
+        let mut headers = HeaderMap::new();
+        headers.insert("if-modified-since", date(1_000_000).to_string().parse().unwrap());
+        headers.insert("if-range", "\"v2\"".parse().unwrap());
+        let p = Preconditions::from_headers(&headers);
+        assert_eq!(p.evaluate(&Method::GET, &r), Outcome::NotModified);
+        assert!(p.range_applies(&r));
+    }
+}
@@ -0,0 +1,603 @@
+//! Resolution of the effective client address, scheme and host of a request
+//! received through trusted proxies.
+//!
+//! Forwarding headers (`Forwarded`, or the legacy `X-Forwarded-For`,
+//! `X-Forwarded-Host` and `X-Forwarded-Proto`) are trivially spoofed by
+//! clients, and each proxy appends its own element to any values received.
+//! Only the elements appended by proxies which are trusted can be believed.
+//! `TrustedProxies::resolve` therefore walks the forwarding chain from the
+//! right (nearest) end, starting with the peer address of the connection,
+//! and stops at the first address which is not a trusted proxy. That address
+//! is the client, and the element which disclosed it also gives the scheme
+//! and host of the original request.
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate http;
+//! use hyperx::header::forwarding::{ForwardingHeaders, IpCidr, TrustedProxies};
+//!
+//! let trusted = TrustedProxies::cidrs(ForwardingHeaders::Forwarded, vec![
+//!     "10.0.0.0/8".parse::<IpCidr>().unwrap(),
+//! ]);
+//!
+//! let mut headers = http::HeaderMap::new();
+//! headers.insert("forwarded",
+//!     "for=198.51.100.99, for=192.0.2.43;proto=https;host=example.com, for=10.0.0.7"
+//!         .parse().unwrap());
+//!
+//! let client = trusted.resolve(&headers, "10.0.0.1:443".parse().unwrap());
+//! assert_eq!(client.ip(), Some("192.0.2.43".parse().unwrap()));
+//! assert_eq!(client.scheme.as_ref().map(|s| &s[..]), Some("https"));
+//! assert_eq!(client.host.unwrap().hostname(), "example.com");
+//! ```
+
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::str::{from_utf8, FromStr};
+
+use http::header::{HeaderMap, HeaderName, FORWARDED};
+
+use header::{ForwardedElement, ForwardedNode, Host, NodeName, TypedHeaders};
+use header::parsing::split_unquoted;
+
+/// A range of IP addresses in CIDR notation, such as `10.0.0.0/8` or
+/// `2001:db8::/32`.
+///
+/// A plain address parses as a range of that single address. IPv4-mapped
+/// IPv6 addresses are contained in the corresponding IPv4 ranges.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct IpCidr {
+    addr: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpCidr {
+    /// Create a range from an address and prefix length, or return
+    /// `Error::Header` if the prefix length is too long for the address
+    /// family. Any host bits of the address are cleared.
+    pub fn new(addr: IpAddr, prefix_len: u8) -> ::Result<IpCidr> {
+        let max = match addr {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+        if prefix_len > max {
+            return Err(::Error::Header);
+        }
+        let addr = match addr {
+            IpAddr::V4(ip) => IpAddr::V4(Ipv4Addr::from(
+                u32::from(ip) & mask32(prefix_len))),
+            IpAddr::V6(ip) => IpAddr::V6(Ipv6Addr::from(
+                u128::from(ip) & mask128(prefix_len))),
+        };
+        Ok(IpCidr { addr, prefix_len })
+    }
+
+    /// The network address.
+    pub fn addr(&self) -> IpAddr {
+        self.addr
+    }
+
+    /// The prefix length in bits.
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    /// Return true if the address is within this range.
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.addr, canonical(ip)) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                u32::from(ip) & mask32(self.prefix_len) == u32::from(net)
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                u128::from(ip) & mask128(self.prefix_len) == u128::from(net)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for IpCidr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix_len)
+    }
+}
+
+impl FromStr for IpCidr {
+    type Err = ::Error;
+
+    fn from_str(s: &str) -> ::Result<IpCidr> {
+        let (addr, prefix_len) = match s.find('/') {
+            Some(i) => (&s[..i], Some(&s[i + 1..])),
+            None => (s, None),
+        };
+        let addr: IpAddr = addr.parse().map_err(|_| ::Error::Header)?;
+        let prefix_len = match prefix_len {
+            Some(p) if !p.is_empty() && p.bytes().all(|b| b.is_ascii_digit()) => {
+                p.parse().map_err(|_| ::Error::Header)?
+            }
+            Some(_) => return Err(::Error::Header),
+            None if addr.is_ipv4() => 32,
+            None => 128,
+        };
+        IpCidr::new(addr, prefix_len)
+    }
+}
+
+fn mask32(prefix_len: u8) -> u32 {
+    u32::MAX.checked_shl(32 - u32::from(prefix_len)).unwrap_or(0)
+}
+
+fn mask128(prefix_len: u8) -> u128 {
+    u128::MAX.checked_shl(128 - u32::from(prefix_len)).unwrap_or(0)
+}
+
+// Convert an IPv4-mapped IPv6 address, as reported for IPv4 peers of dual
+// stack sockets, to IPv4.
+fn canonical(ip: IpAddr) -> IpAddr {
+    if let IpAddr::V6(v6) = ip {
+        if let [0, 0, 0, 0, 0, 0xffff, hi, lo] = v6.segments() {
+            return IpAddr::V4(Ipv4Addr::from((u32::from(hi) << 16) | u32::from(lo)));
+        }
+    }
+    ip
+}
+
+// Loopback, private and link-local networks.
+fn is_internal(ip: IpAddr) -> bool {
+    match canonical(ip) {
+        IpAddr::V4(ip) => ip.is_loopback() || ip.is_private() || ip.is_link_local(),
+        IpAddr::V6(ip) => {
+            let first = ip.segments()[0];
+            ip.is_loopback()
+                || (first & 0xfe00) == 0xfc00 // unique local, fc00::/7
+                || (first & 0xffc0) == 0xfe80 // link-local, fe80::/10
+        }
+    }
+}
+
+/// Which forwarding headers `TrustedProxies` reads.
+///
+/// Only the headers which the trusted proxies set (or strip) may be read.
+/// Otherwise a client can supply the other header, and it will be passed
+/// through unmodified. There is therefore no default: the choice must match
+/// the deployment.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ForwardingHeaders {
+    /// Only read `Forwarded`.
+    Forwarded,
+    /// Only read `X-Forwarded-For`, `X-Forwarded-Host` and
+    /// `X-Forwarded-Proto`.
+    XForwarded,
+}
+
+#[derive(Clone, Debug)]
+enum Trust {
+    Cidrs(Vec<IpCidr>),
+    Hops(usize),
+    Internal,
+}
+
+/// Configuration of the proxies trusted to report the client of a request.
+#[derive(Clone, Debug)]
+pub struct TrustedProxies {
+    trust: Trust,
+    headers: ForwardingHeaders,
+}
+
+impl TrustedProxies {
+    /// Trust proxies with addresses in any of the given ranges, which set
+    /// the given forwarding headers.
+    ///
+    /// The client is the rightmost address in the forwarding chain which is
+    /// not in one of the ranges.
+    pub fn cidrs<I>(headers: ForwardingHeaders, cidrs: I) -> TrustedProxies
+    where I: IntoIterator<Item = IpCidr>
+    {
+        TrustedProxies::new(headers, Trust::Cidrs(cidrs.into_iter().collect()))
+    }
+
+    /// Trust exactly the given number of proxies in front of the server,
+    /// including the peer, which set the given forwarding headers.
+    ///
+    /// The client is the address reported by the outermost of these
+    /// proxies, or the leftmost address if the chain is shorter. With zero
+    /// hops, the client is always the peer.
+    pub fn hops(headers: ForwardingHeaders, hops: usize) -> TrustedProxies {
+        TrustedProxies::new(headers, Trust::Hops(hops))
+    }
+
+    /// Trust proxies with loopback, private (RFC 1918), unique local (RFC
+    /// 4193) or link-local addresses, which set the given forwarding
+    /// headers.
+    ///
+    /// The client is the rightmost address in the forwarding chain which is
+    /// not on such a network. This suits deployments where all proxies are
+    /// internal and all clients are on the public Internet.
+    pub fn rightmost_untrusted(headers: ForwardingHeaders) -> TrustedProxies {
+        TrustedProxies::new(headers, Trust::Internal)
+    }
+
+    fn new(headers: ForwardingHeaders, trust: Trust) -> TrustedProxies {
+        TrustedProxies { trust, headers }
+    }
+
+    /// The forwarding headers read.
+    pub fn headers(&self) -> ForwardingHeaders {
+        self.headers
+    }
+
+    /// Resolve the client of a request with the given headers, received on a
+    /// connection from the `peer` address.
+    ///
+    /// If the peer is not a trusted proxy, or no forwarding headers are
+    /// present, the result is the peer itself, with no scheme and the host of
+    /// the `Host` header. Otherwise the chain is walked from the right while
+    /// the addresses are trusted. The walk also stops at an element which is
+    /// malformed, has no `for` node, or has a node which isn't an IP address;
+    /// that node, or `unknown`, is then the client.
+    ///
+    /// The scheme and host are taken from the `Forwarded` element which
+    /// disclosed the client. With the legacy headers, a value of
+    /// `X-Forwarded-Proto` or `X-Forwarded-Host` is only used if that header
+    /// has exactly one value per `X-Forwarded-For` hop, and then the value
+    /// of the hop which disclosed the client. Otherwise the values can't be
+    /// attributed to a trusted proxy, and might have been sent by the
+    /// client, so are ignored. The host falls back to the `Host` header.
+    pub fn resolve(&self, headers: &HeaderMap, peer: SocketAddr) -> ForwardedClient {
+        let request_host = headers.try_decode::<Host>().and_then(|h| h.ok());
+        let mut client = ForwardedClient {
+            addr: peer.into(),
+            scheme: None,
+            host: request_host,
+        };
+        if !self.trusts(peer.ip(), 0) {
+            return client;
+        }
+
+        let use_forwarded = self.headers == ForwardingHeaders::Forwarded;
+        let hops = if use_forwarded {
+            forwarded_hops(headers)
+        } else {
+            legacy_hops(headers)
+        };
+
+        let mut selected = None;
+        let mut index = hops.len();
+        for (trusted, hop) in (1..).zip(hops.iter().rev()) {
+            selected = Some(hop);
+            index -= 1;
+            match *hop {
+                Some(ref e) => match e.for_.as_ref().and_then(ForwardedNode::ip) {
+                    Some(ip) if self.trusts(ip, trusted) => {}
+                    _ => break,
+                },
+                None => break,
+            }
+        }
+        let element = match selected {
+            Some(Some(e)) => e,
+            Some(None) => {
+                return ForwardedClient {
+                    addr: ForwardedNode::new(NodeName::Unknown),
+                    ..client
+                };
+            }
+            None => return client,
+        };
+
+        client.addr = element.for_.clone()
+            .unwrap_or_else(|| ForwardedNode::new(NodeName::Unknown));
+        let (scheme, host) = if use_forwarded {
+            (element.proto.clone(), element.host.clone())
+        } else {
+            (aligned_value(headers, "x-forwarded-proto", hops.len(), index),
+             aligned_value(headers, "x-forwarded-host", hops.len(), index))
+        };
+        client.scheme = scheme.map(|s| s.to_ascii_lowercase());
+        if let Some(host) = host.and_then(|h| h.parse().ok()) {
+            client.host = Some(host);
+        }
+        client
+    }
+
+    // Return true if the proxy at the given address, preceded by `hop`
+    // trusted proxies, is trusted.
+    fn trusts(&self, ip: IpAddr, hop: usize) -> bool {
+        match self.trust {
+            Trust::Cidrs(ref cidrs) => cidrs.iter().any(|c| c.contains(ip)),
+            Trust::Hops(hops) => hop < hops,
+            Trust::Internal => is_internal(ip),
+        }
+    }
+}
+
+/// The effective client of a request, as resolved by `TrustedProxies`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ForwardedClient {
+    /// The client node: an IP address with optional port, or, as reported
+    /// by a trusted proxy, an obfuscated identifier or `unknown`.
+    pub addr: ForwardedNode,
+    /// The scheme of the original request, in lower case, if reported by a
+    /// trusted proxy.
+    pub scheme: Option<String>,
+    /// The host of the original request.
+    pub host: Option<Host>,
+}
+
+impl ForwardedClient {
+    /// Return the client IP address, if known.
+    pub fn ip(&self) -> Option<IpAddr> {
+        self.addr.ip().map(canonical)
+    }
+}
+
+// The elements of all `Forwarded` lines, with `None` for a malformed element.
+// A line with unbalanced quotes is a single malformed element.
+fn forwarded_hops(headers: &HeaderMap) -> Vec<Option<ForwardedElement>> {
+    let mut hops = Vec::new();
+    for line in headers.get_all(FORWARDED) {
+        let elements = from_utf8(line.as_bytes()).ok()
+            .and_then(|line| split_unquoted(line, b',').ok());
+        match elements {
+            Some(elements) => {
+                for element in elements {
+                    let element = element.trim();
+                    if !element.is_empty() {
+                        hops.push(element.parse().ok());
+                    }
+                }
+            }
+            None => hops.push(None),
+        }
+    }
+    hops
+}
+
+// The nodes of all `X-Forwarded-For` lines, as elements with only a `for`
+// node, or `None` if malformed.
+fn legacy_hops(headers: &HeaderMap) -> Vec<Option<ForwardedElement>> {
+    let mut hops = Vec::new();
+    for line in headers.get_all(HeaderName::from_static("x-forwarded-for")) {
+        let line = match from_utf8(line.as_bytes()) {
+            Ok(line) => line,
+            Err(_) => {
+                hops.push(None);
+                continue;
+            }
+        };
+        for node in line.split(',') {
+            let node = node.trim();
+            if node.is_empty() {
+                continue;
+            }
+            let node = match node.parse::<IpAddr>() {
+                Ok(ip) => Some(ip.into()),
+                Err(_) => node.parse::<ForwardedNode>().ok(),
+            };
+            hops.push(node.map(|n| ForwardedElement::new().set_for(n)));
+        }
+    }
+    hops
+}
+
+// The non-empty value at `index` of a comma-separated header, if it has
+// exactly `count` values, aligned with the `X-Forwarded-For` hops.
+fn aligned_value(headers: &HeaderMap, name: &'static str, count: usize, index: usize)
+    -> Option<String>
+{
+    let mut values = Vec::new();
+    for line in headers.get_all(HeaderName::from_static(name)) {
+        let line = from_utf8(line.as_bytes()).ok()?;
+        values.extend(line.split(',').map(str::trim).filter(|v| !v.is_empty()));
+    }
+    if values.len() != count {
+        return None;
+    }
+    values.get(index).map(|v| (*v).to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, SocketAddr};
+
+    use http::header::HeaderMap;
+
+    use header::{ForwardedNode, NodeName};
+    use super::{ForwardingHeaders, IpCidr, TrustedProxies};
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(*name, value.parse().unwrap());
+        }
+        headers
+    }
+
+    fn peer(s: &str) -> SocketAddr {
+        s.parse().unwrap()
+    }
+
+    fn ip(s: &str) -> Option<IpAddr> {
+        Some(s.parse().unwrap())
+    }
+
+    fn internal(headers: ForwardingHeaders) -> TrustedProxies {
+        TrustedProxies::cidrs(headers, vec![
+            "10.0.0.0/8".parse().unwrap(),
+            "2001:db8:ffff::/48".parse().unwrap(),
+        ])
+    }
+
+    #[test]
+    fn test_cidr() {
+        let cidr: IpCidr = "10.1.2.3/8".parse().unwrap();
+        assert_eq!(cidr.to_string(), "10.0.0.0/8");
+        assert!(cidr.contains("10.255.0.1".parse().unwrap()));
+        assert!(cidr.contains("::ffff:10.0.0.1".parse().unwrap()));
+        assert!(!cidr.contains("11.0.0.1".parse().unwrap()));
+        assert!(!cidr.contains("::1".parse().unwrap()));
+
+        let cidr: IpCidr = "2001:db8::/32".parse().unwrap();
+        assert!(cidr.contains("2001:db8:1::1".parse().unwrap()));
+        assert!(!cidr.contains("2001:db9::1".parse().unwrap()));
+
+        let cidr: IpCidr = "192.0.2.1".parse().unwrap();
+        assert_eq!(cidr.prefix_len(), 32);
+        assert!(cidr.contains("192.0.2.1".parse().unwrap()));
+        assert!(!cidr.contains("192.0.2.2".parse().unwrap()));
+
+        let all: IpCidr = "0.0.0.0/0".parse().unwrap();
+        assert!(all.contains("203.0.113.1".parse().unwrap()));
+
+        for bad in &["10.0.0.0/33", "::/129", "10.0.0.0/", "10.0.0.0/+8", "host/8", ""] {
+            assert!(bad.parse::<IpCidr>().is_err(), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn test_untrusted_peer() {
+        let h = headers(&[
+            ("host", "example.com"),
+            ("forwarded", "for=192.0.2.1;proto=https;host=spoofed.example"),
+            ("x-forwarded-for", "192.0.2.2"),
+        ]);
+        let client = internal(ForwardingHeaders::Forwarded).resolve(&h, peer("203.0.113.9:5000"));
+        assert_eq!(client.addr, peer("203.0.113.9:5000").into());
+        assert_eq!(client.scheme, None);
+        assert_eq!(client.host.unwrap().hostname(), "example.com");
+    }
+
+    #[test]
+    fn test_trusted_peer_without_headers() {
+        let h = headers(&[("host", "example.com:8080")]);
+        let client = internal(ForwardingHeaders::XForwarded).resolve(&h, peer("10.0.0.1:5000"));
+        assert_eq!(client.ip(), ip("10.0.0.1"));
+        assert_eq!(client.host.unwrap().port(), Some(8080));
+    }
+
+    #[test]
+    fn test_cidrs_rightmost_untrusted() {
+        // The client spoofs the first element
+        let h = headers(&[
+            ("forwarded", "for=198.51.100.1;proto=http"),
+            ("forwarded", "for=\"[2001:db8::17]:4711\";proto=HTTPS;host=example.com"),
+            ("forwarded", "for=10.0.0.9, for=\"[2001:db8:ffff::2]\""),
+        ]);
+        let client = internal(ForwardingHeaders::Forwarded).resolve(&h, peer("[::ffff:10.0.0.1]:443"));
+        assert_eq!(client.ip(), ip("2001:db8::17"));
+        assert_eq!(client.addr.to_string(), "[2001:db8::17]:4711");
+        assert_eq!(client.scheme, Some("https".to_owned()));
+        assert_eq!(client.host.unwrap().hostname(), "example.com");
+    }
+
+    #[test]
+    fn test_all_trusted() {
+        let h = headers(&[("x-forwarded-for", "10.0.0.3, 10.0.0.2")]);
+        let client = internal(ForwardingHeaders::XForwarded).resolve(&h, peer("10.0.0.1:443"));
+        assert_eq!(client.ip(), ip("10.0.0.3"));
+    }
+
+    #[test]
+    fn test_hops() {
+        let h = headers(&[
+            ("x-forwarded-for", "198.51.100.1, 192.0.2.1"),
+            ("x-forwarded-for", "203.0.113.5"),
+            ("x-forwarded-proto", "http, http, https"),
+        ]);
+        let p = peer("203.0.113.200:443");
+
+        let client = TrustedProxies::hops(ForwardingHeaders::XForwarded, 0).resolve(&h, p);
+        assert_eq!(client.ip(), ip("203.0.113.200"));
+        assert_eq!(client.scheme, None);
+
+        let client = TrustedProxies::hops(ForwardingHeaders::XForwarded, 1).resolve(&h, p);
+        assert_eq!(client.ip(), ip("203.0.113.5"));
+        assert_eq!(client.scheme, Some("https".to_owned()));
+
+        let client = TrustedProxies::hops(ForwardingHeaders::XForwarded, 2).resolve(&h, p);
+        assert_eq!(client.ip(), ip("192.0.2.1"));
+        assert_eq!(client.scheme, Some("http".to_owned()));
+
+        let client = TrustedProxies::hops(ForwardingHeaders::XForwarded, 10).resolve(&h, p);
+        assert_eq!(client.ip(), ip("198.51.100.1"));
+    }
+
+    #[test]
+    fn test_rightmost_untrusted() {
+        let h = headers(&[
+            ("x-forwarded-for", "192.0.2.66, 203.0.113.7, fd00::1, 172.16.0.3"),
+            ("x-forwarded-host", "spoofed.example, example.com, a.internal, b.internal"),
+        ]);
+        let client = TrustedProxies::rightmost_untrusted(ForwardingHeaders::XForwarded)
+            .resolve(&h, peer("127.0.0.1:80"));
+        assert_eq!(client.ip(), ip("203.0.113.7"));
+        assert_eq!(client.host.unwrap().hostname(), "example.com");
+
+        let client = TrustedProxies::rightmost_untrusted(ForwardingHeaders::XForwarded)
+            .resolve(&h, peer("192.0.2.200:80"));
+        assert_eq!(client.ip(), ip("192.0.2.200"));
+    }
+
+    #[test]
+    fn test_legacy_values_not_aligned() {
+        // The nearest proxy appends to `X-Forwarded-For` but doesn't set
+        // `X-Forwarded-Proto` or `X-Forwarded-Host`, so those are the client's.
+        let h = headers(&[
+            ("host", "example.com"),
+            ("x-forwarded-for", "192.0.2.1"),
+            ("x-forwarded-for", "203.0.113.7"),
+            ("x-forwarded-proto", "https"),
+            ("x-forwarded-host", "spoofed.example"),
+        ]);
+        let client = TrustedProxies::hops(ForwardingHeaders::XForwarded, 1)
+            .resolve(&h, peer("10.0.0.1:80"));
+        assert_eq!(client.ip(), ip("203.0.113.7"));
+        assert_eq!(client.scheme, None);
+        assert_eq!(client.host.unwrap().hostname(), "example.com");
+    }
+
+    #[test]
+    fn test_stops_at_unknown_and_malformed() {
+        let trusted = TrustedProxies::rightmost_untrusted(ForwardingHeaders::Forwarded);
+        let p = peer("10.0.0.1:443");
+
+        let h = headers(&[("forwarded", "for=192.0.2.1, for=_hidden;proto=https, for=10.0.0.2")]);
+        let client = trusted.resolve(&h, p);
+        assert_eq!(client.addr, ForwardedNode::new(NodeName::Obfuscated("_hidden".to_owned())));
+        assert_eq!(client.ip(), None);
+        assert_eq!(client.scheme, Some("https".to_owned()));
+
+        let h = headers(&[("forwarded", "for=192.0.2.1, proto=https")]);
+        assert_eq!(trusted.resolve(&h, p).addr, ForwardedNode::new(NodeName::Unknown));
+
+        let h = headers(&[("forwarded", "for=192.0.2.1, for=[::1], for=10.0.0.2")]);
+        assert_eq!(trusted.resolve(&h, p).addr, ForwardedNode::new(NodeName::Unknown));
+
+        let trusted = TrustedProxies::rightmost_untrusted(ForwardingHeaders::XForwarded);
+        let h = headers(&[("x-forwarded-for", "192.0.2.1, proxy.example")]);
+        assert_eq!(trusted.resolve(&h, p).addr, ForwardedNode::new(NodeName::Unknown));
+    }
+
+    #[test]
+    fn test_header_selection() {
+        let h = headers(&[
+            ("forwarded", "for=192.0.2.1;proto=https;host=spoofed.example"),
+            ("x-forwarded-for", "192.0.2.2"),
+        ]);
+        let p = peer("10.0.0.1:443");
+        let forwarded = internal(ForwardingHeaders::Forwarded);
+        assert_eq!(forwarded.headers(), ForwardingHeaders::Forwarded);
+        assert_eq!(forwarded.resolve(&h, p).ip(), ip("192.0.2.1"));
+
+        // A client supplied `Forwarded` is ignored behind proxies which only
+        // append `X-Forwarded-For`.
+        let client = internal(ForwardingHeaders::XForwarded).resolve(&h, p);
+        assert_eq!(client.ip(), ip("192.0.2.2"));
+        assert_eq!(client.scheme, None);
+        assert_eq!(client.host, None);
+
+        let h = headers(&[("x-forwarded-for", "192.0.2.2")]);
+        assert_eq!(internal(ForwardingHeaders::Forwarded).resolve(&h, p).ip(), ip("10.0.0.1"));
+    }
+}
@@ -0,0 +1,485 @@
+//! Calculation of the freshness and age of stored responses, as specified
+//! in [RFC 9111](https://www.rfc-editor.org/rfc/rfc9111#section-4.2), and
+//! whether a cache may use them to satisfy a request.
+//!
+//! A `StoredResponse` holds the caching headers of a response and the times
+//! at which its request was sent and it was received. Its `evaluate` method
+//! applies the `Cache-Control` directives of a request, including those of
+//! [RFC 5861](https://tools.ietf.org/html/rfc5861) for serving stale
+//! responses, giving a `Decision`.
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate http;
+//! # extern crate hyperx;
+//! use std::time::{Duration, SystemTime};
+//! use hyperx::header::{Age, CacheControl, CacheDirective, Date, TypedHeaders};
+//! use hyperx::header::freshness::{Decision, StoredResponse};
+//!
+//! # fn main() {
+//! let sent = SystemTime::now();
+//! let received = sent + Duration::from_secs(1);
+//!
+//! let mut headers = http::HeaderMap::new();
+//! headers.encode(&CacheControl(vec![CacheDirective::MaxAge(600)]));
+//! headers.encode(&Date(sent.into()));
+//! headers.encode(&Age(100));
+//!
+//! let response = StoredResponse::from_headers(&headers, sent, received);
+//! assert_eq!(response.freshness_lifetime(), Duration::from_secs(600));
+//! assert_eq!(response.current_age(received), Duration::from_secs(101));
+//!
+//! assert_eq!(response.evaluate(&[], received + Duration::from_secs(60)), Decision::Fresh);
+//! assert_eq!(response.evaluate(&[], received + Duration::from_secs(600)), Decision::Revalidate);
+//!
+//! let request = [CacheDirective::MaxAge(120)];
+//! assert_eq!(response.evaluate(&request, received + Duration::from_secs(60)),
+//!            Decision::Revalidate);
+//! # }
+//! ```
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use http::header::HeaderMap;
+
+use header::{Age, CacheControl, CacheDirective, Date, Expires, HttpDate, LastModified,
+             TypedHeaders};
+
+/// How a cache may use a stored response to satisfy a request.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Decision {
+    /// The response is fresh, or its staleness is acceptable to the
+    /// client, so it may be used without validation.
+    Fresh,
+    /// The response is stale but within its `stale-while-revalidate`
+    /// window, so it may be used while it is validated in the background.
+    StaleWhileRevalidate,
+    /// The response is stale but within a `stale-if-error` window, so it
+    /// must be validated, but may be used if validation fails with an error
+    /// (a 500, 502, 503 or 504 response, or no response).
+    StaleIfError,
+    /// The response must be validated before it is used.
+    Revalidate,
+    /// The response can't be used without validation, but the request has
+    /// `only-if-cached`, so the cache should respond with 504 Gateway
+    /// Timeout.
+    GatewayTimeout,
+}
+
+/// The caching headers of a stored response, with its request and response
+/// times.
+#[derive(Clone, PartialEq, Debug)]
+pub struct StoredResponse {
+    cache_control: Vec<CacheDirective>,
+    expires: Option<SystemTime>,
+    date: Option<SystemTime>,
+    age: Option<u32>,
+    last_modified: Option<SystemTime>,
+    request_time: SystemTime,
+    response_time: SystemTime,
+    shared: bool,
+    heuristic: bool,
+}
+
+impl StoredResponse {
+    /// The fraction of the time since the `Last-Modified` date used as the
+    /// heuristic freshness lifetime, 10%.
+    pub const HEURISTIC_FRACTION: u32 = 10;
+
+    /// Create for a response without caching headers, for a request sent at
+    /// `request_time` and a response received at `response_time`.
+    pub fn new(request_time: SystemTime, response_time: SystemTime) -> StoredResponse {
+        StoredResponse {
+            cache_control: Vec::new(),
+            expires: None,
+            date: None,
+            age: None,
+            last_modified: None,
+            request_time,
+            response_time,
+            shared: false,
+            heuristic: true,
+        }
+    }
+
+    /// Create from the `Cache-Control`, `Expires`, `Date`, `Age` and
+    /// `Last-Modified` headers of a response.
+    ///
+    /// An invalid `Expires` is treated as a time in the past, and other
+    /// invalid headers are ignored.
+    pub fn from_headers(headers: &HeaderMap, request_time: SystemTime,
+                        response_time: SystemTime) -> StoredResponse
+    {
+        let mut response = StoredResponse::new(request_time, response_time);
+        if let Some(Ok(CacheControl(directives))) = headers.try_decode() {
+            response.cache_control = directives;
+        }
+        response.expires = headers.try_decode()
+            .map(|r| r.map_or(UNIX_EPOCH, |Expires(date)| date.into()));
+        response.date = headers.try_decode().and_then(Result::ok)
+            .map(|Date(date)| date.into());
+        response.age = headers.try_decode().and_then(Result::ok).map(|Age(age)| age);
+        response.last_modified = headers.try_decode().and_then(Result::ok)
+            .map(|LastModified(date)| date.into());
+        response
+    }
+
+    /// Set the `Cache-Control` directives.
+    pub fn with_cache_control(mut self, directives: Vec<CacheDirective>) -> StoredResponse {
+        self.cache_control = directives;
+        self
+    }
+
+    /// Set the `Expires` date.
+    pub fn with_expires(mut self, expires: HttpDate) -> StoredResponse {
+        self.expires = Some(expires.into());
+        self
+    }
+
+    /// Set the `Date`.
+    pub fn with_date(mut self, date: HttpDate) -> StoredResponse {
+        self.date = Some(date.into());
+        self
+    }
+
+    /// Set the `Age`, in seconds.
+    pub fn with_age(mut self, age: u32) -> StoredResponse {
+        self.age = Some(age);
+        self
+    }
+
+    /// Set the `Last-Modified` date.
+    pub fn with_last_modified(mut self, last_modified: HttpDate) -> StoredResponse {
+        self.last_modified = Some(last_modified.into());
+        self
+    }
+
+    /// Set whether the cache is shared, so `s-maxage` and
+    /// `proxy-revalidate` apply. The default is a private cache.
+    pub fn with_shared(mut self, shared: bool) -> StoredResponse {
+        self.shared = shared;
+        self
+    }
+
+    /// Set whether a heuristic freshness lifetime may be used, which is
+    /// only allowed for responses with a heuristically cacheable status
+    /// code, such as 200 OK, or with `public`. The default is true.
+    pub fn with_heuristic(mut self, heuristic: bool) -> StoredResponse {
+        self.heuristic = heuristic;
+        self
+    }
+
+    /// The freshness lifetime given by `s-maxage`, `max-age` or `Expires`,
+    /// if any.
+    pub fn explicit_freshness_lifetime(&self) -> Option<Duration> {
+        let directive = |shared_only: bool| self.cache_control.iter().filter_map(|d| match *d {
+            CacheDirective::SMaxAge(secs) if shared_only => Some(secs),
+            CacheDirective::MaxAge(secs) if !shared_only => Some(secs),
+            _ => None,
+        }).next();
+        let max_age = if self.shared { directive(true) } else { None }
+            .or_else(|| directive(false));
+        if let Some(secs) = max_age {
+            return Some(Duration::from_secs(u64::from(secs)));
+        }
+        self.expires.map(|expires| {
+            let date = self.date.unwrap_or(self.response_time);
+            expires.duration_since(date).unwrap_or_default()
+        })
+    }
+
+    /// The heuristic freshness lifetime, 10% of the time between the
+    /// `Last-Modified` date and the `Date`, if allowed.
+    pub fn heuristic_freshness_lifetime(&self) -> Option<Duration> {
+        let public = self.cache_control.contains(&CacheDirective::Public);
+        if !self.heuristic && !public {
+            return None;
+        }
+        self.last_modified.map(|last_modified| {
+            let date = self.date.unwrap_or(self.response_time);
+            date.duration_since(last_modified).unwrap_or_default()
+                / StoredResponse::HEURISTIC_FRACTION
+        })
+    }
+
+    /// The freshness lifetime: explicit, heuristic, or else zero.
+    pub fn freshness_lifetime(&self) -> Duration {
+        self.explicit_freshness_lifetime()
+            .or_else(|| self.heuristic_freshness_lifetime())
+            .unwrap_or_default()
+    }
+
+    /// The current age of the response at `now`, from its `Age`, `Date`
+    /// and the time it has been stored.
+    pub fn current_age(&self, now: SystemTime) -> Duration {
+        let apparent_age = self.date
+            .and_then(|date| self.response_time.duration_since(date).ok())
+            .unwrap_or_default();
+        let response_delay = self.response_time.duration_since(self.request_time)
+            .unwrap_or_default();
+        let age = Duration::from_secs(u64::from(self.age.unwrap_or(0)));
+        let corrected_initial_age = apparent_age.max(age + response_delay);
+        let resident_time = now.duration_since(self.response_time).unwrap_or_default();
+        corrected_initial_age + resident_time
+    }
+
+    /// Whether the response is fresh at `now`, regardless of request
+    /// directives.
+    pub fn is_fresh(&self, now: SystemTime) -> bool {
+        self.freshness_lifetime() > self.current_age(now)
+    }
+
+    /// Decide how the response may be used at `now` to satisfy a request
+    /// with the given `Cache-Control` directives.
+    ///
+    /// Responses with `no-cache` always need validation, as do responses
+    /// older than the request's `max-age`, fresh responses without the
+    /// request's `min-fresh` left, and stale responses with
+    /// `must-revalidate`, or with `proxy-revalidate` or `s-maxage` in a
+    /// shared cache. Otherwise, a stale response may be used if its
+    /// staleness is within the request's `max-stale` or the response's
+    /// `stale-while-revalidate` or `stale-if-error`.
+    pub fn evaluate(&self, request: &[CacheDirective], now: SystemTime) -> Decision {
+        let decision = self.decide(request, now);
+        if request.contains(&CacheDirective::OnlyIfCached) {
+            match decision {
+                Decision::Fresh | Decision::StaleWhileRevalidate => decision,
+                _ => Decision::GatewayTimeout,
+            }
+        } else {
+            decision
+        }
+    }
+
+    fn decide(&self, request: &[CacheDirective], now: SystemTime) -> Decision {
+        if self.cache_control.contains(&CacheDirective::NoCache)
+            || request.contains(&CacheDirective::NoCache)
+        {
+            return Decision::Revalidate;
+        }
+
+        let lifetime = self.freshness_lifetime();
+        let age = self.current_age(now);
+        if seconds(request, "max-age").map_or(false, |max_age| age >= max_age) {
+            return Decision::Revalidate;
+        }
+        if age < lifetime {
+            let min_fresh = seconds(request, "min-fresh").unwrap_or_default();
+            return if lifetime - age >= min_fresh {
+                Decision::Fresh
+            } else {
+                Decision::Revalidate
+            };
+        }
+
+        let must_revalidate = self.cache_control.iter().any(|d| match *d {
+            CacheDirective::MustRevalidate => true,
+            CacheDirective::ProxyRevalidate | CacheDirective::SMaxAge(_) => self.shared,
+            _ => false,
+        });
+        if must_revalidate {
+            return Decision::Revalidate;
+        }
+
+        let staleness = age - lifetime;
+        let max_stale = request.iter().filter_map(|d| match *d {
+            CacheDirective::MaxStale(secs) => Some(Duration::from_secs(u64::from(secs))),
+            CacheDirective::MaxStaleAny => Some(Duration::from_secs(u64::MAX)),
+            _ => None,
+        }).next();
+        if max_stale.map_or(false, |max_stale| staleness <= max_stale) {
+            return Decision::Fresh;
+        }
+        if seconds(&self.cache_control, "stale-while-revalidate")
+            .map_or(false, |window| staleness <= window)
+        {
+            return Decision::StaleWhileRevalidate;
+        }
+        let stale_if_error = seconds(&self.cache_control, "stale-if-error")
+            .into_iter()
+            .chain(seconds(request, "stale-if-error"))
+            .max();
+        if stale_if_error.map_or(false, |window| staleness <= window) {
+            return Decision::StaleIfError;
+        }
+        Decision::Revalidate
+    }
+}
+
+// The value of a delta-seconds directive.
+fn seconds(directives: &[CacheDirective], name: &str) -> Option<Duration> {
+    directives.iter().filter_map(|d| {
+        let secs = match (d, name) {
+            (&CacheDirective::MaxAge(secs), "max-age") => secs,
+            (&CacheDirective::MinFresh(secs), "min-fresh") => secs,
+            (&CacheDirective::StaleWhileRevalidate(secs), "stale-while-revalidate") => secs,
+            (&CacheDirective::StaleIfError(secs), "stale-if-error") => secs,
+            _ => return None,
+        };
+        Some(Duration::from_secs(u64::from(secs)))
+    }).next()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use http::header::{HeaderMap, HeaderValue, EXPIRES};
+
+    use header::{CacheDirective, TypedHeaders, Date, LastModified};
+    use super::{Decision, StoredResponse};
+
+    fn at(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(1_600_000_000 + secs)
+    }
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    fn max_age(max_age: u32) -> StoredResponse {
+        StoredResponse::new(at(0), at(0))
+            .with_date(at(0).into())
+            .with_cache_control(vec![CacheDirective::MaxAge(max_age)])
+    }
+
+    #[test]
+    fn test_freshness_lifetime() {
+        let response = StoredResponse::new(at(0), at(0))
+            .with_date(at(0).into())
+            .with_expires(at(300).into());
+        assert_eq!(response.freshness_lifetime(), secs(300));
+        let response = response.with_cache_control(vec![
+            CacheDirective::SMaxAge(60), CacheDirective::MaxAge(120)]);
+        assert_eq!(response.freshness_lifetime(), secs(120));
+        assert_eq!(response.with_shared(true).freshness_lifetime(), secs(60));
+
+        // Expires before Date
+        let response = StoredResponse::new(at(0), at(100))
+            .with_date(at(100).into())
+            .with_expires(at(0).into());
+        assert_eq!(response.freshness_lifetime(), secs(0));
+    }
+
+    #[test]
+    fn test_invalid_expires() {
+        let mut headers = HeaderMap::new();
+        headers.insert(EXPIRES, HeaderValue::from_static("0"));
+        headers.encode(&Date(at(0).into()));
+        let response = StoredResponse::from_headers(&headers, at(0), at(0));
+        assert_eq!(response.explicit_freshness_lifetime(), Some(secs(0)));
+        assert!(!response.is_fresh(at(0)));
+    }
+
+    #[test]
+    fn test_heuristic() {
+        let mut headers = HeaderMap::new();
+        headers.encode(&Date(at(10_000).into()));
+        headers.encode(&LastModified(at(0).into()));
+        let response = StoredResponse::from_headers(&headers, at(10_000), at(10_000));
+        assert_eq!(response.explicit_freshness_lifetime(), None);
+        assert_eq!(response.freshness_lifetime(), secs(1000));
+        assert!(response.is_fresh(at(10_999)));
+        assert!(!response.is_fresh(at(11_000)));
+
+        let response = response.with_heuristic(false);
+        assert_eq!(response.freshness_lifetime(), secs(0));
+        let response = response.with_cache_control(vec![CacheDirective::Public]);
+        assert_eq!(response.freshness_lifetime(), secs(1000));
+    }
+
+    #[test]
+    fn test_current_age() {
+        // Apparent age from Date
+        let response = StoredResponse::new(at(0), at(2)).with_date(at(0).into());
+        assert_eq!(response.current_age(at(2)), secs(2));
+        assert_eq!(response.current_age(at(12)), secs(12));
+        // Age corrected by the response delay
+        let response = response.with_age(30);
+        assert_eq!(response.current_age(at(2)), secs(32));
+        // Date in the future
+        let response = StoredResponse::new(at(0), at(1)).with_date(at(60).into());
+        assert_eq!(response.current_age(at(1)), secs(1));
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let response = max_age(100);
+        assert_eq!(response.evaluate(&[], at(99)), Decision::Fresh);
+        assert_eq!(response.evaluate(&[], at(100)), Decision::Revalidate);
+        assert_eq!(response.evaluate(&[CacheDirective::NoCache], at(0)), Decision::Revalidate);
+        let no_cache = response.clone().with_cache_control(vec![CacheDirective::NoCache]);
+        assert_eq!(no_cache.evaluate(&[], at(0)), Decision::Revalidate);
+    }
+
+    #[test]
+    fn test_request_directives() {
+        let response = max_age(100);
+        let request = [CacheDirective::MaxAge(10)];
+        assert_eq!(response.evaluate(&request, at(9)), Decision::Fresh);
+        assert_eq!(response.evaluate(&request, at(10)), Decision::Revalidate);
+
+        let request = [CacheDirective::MinFresh(20)];
+        assert_eq!(response.evaluate(&request, at(80)), Decision::Fresh);
+        assert_eq!(response.evaluate(&request, at(81)), Decision::Revalidate);
+
+        let request = [CacheDirective::MaxStale(50)];
+        assert_eq!(response.evaluate(&request, at(150)), Decision::Fresh);
+        assert_eq!(response.evaluate(&request, at(151)), Decision::Revalidate);
+        let request = [CacheDirective::MaxStaleAny];
+        assert_eq!(response.evaluate(&request, at(1_000_000)), Decision::Fresh);
+
+        let request = [CacheDirective::OnlyIfCached];
+        assert_eq!(response.evaluate(&request, at(0)), Decision::Fresh);
+        assert_eq!(response.evaluate(&request, at(100)), Decision::GatewayTimeout);
+    }
+
+    #[test]
+    fn test_stale_extensions() {
+        let response = max_age(100).with_cache_control(vec![
+            CacheDirective::MaxAge(100),
+            CacheDirective::StaleWhileRevalidate(30),
+            CacheDirective::StaleIfError(60),
+        ]);
+        assert_eq!(response.evaluate(&[], at(99)), Decision::Fresh);
+        assert_eq!(response.evaluate(&[], at(130)), Decision::StaleWhileRevalidate);
+        assert_eq!(response.evaluate(&[], at(131)), Decision::StaleIfError);
+        assert_eq!(response.evaluate(&[], at(161)), Decision::Revalidate);
+        assert_eq!(response.evaluate(&[CacheDirective::StaleIfError(100)], at(161)),
+                   Decision::StaleIfError);
+        assert_eq!(response.evaluate(&[CacheDirective::OnlyIfCached], at(130)),
+                   Decision::StaleWhileRevalidate);
+        assert_eq!(response.evaluate(&[CacheDirective::OnlyIfCached], at(131)),
+                   Decision::GatewayTimeout);
+
+        // Staleness is measured from the response's own lifetime
+        let request = [CacheDirective::MaxAge(10)];
+        assert!(response.is_fresh(at(20)));
+        assert_eq!(response.evaluate(&request, at(20)), Decision::Revalidate);
+        let request = [CacheDirective::MinFresh(20)];
+        assert_eq!(response.evaluate(&request, at(90)), Decision::Revalidate);
+        let request = [CacheDirective::MaxAge(200)];
+        assert_eq!(response.evaluate(&request, at(130)), Decision::StaleWhileRevalidate);
+    }
+
+    #[test]
+    fn test_must_revalidate() {
+        let response = max_age(100).with_cache_control(vec![
+            CacheDirective::MaxAge(100),
+            CacheDirective::ProxyRevalidate,
+            CacheDirective::StaleWhileRevalidate(30),
+        ]);
+        let request = [CacheDirective::MaxStale(50)];
+        assert_eq!(response.evaluate(&request, at(120)), Decision::Fresh);
+        let response = response.with_shared(true);
+        assert_eq!(response.evaluate(&request, at(99)), Decision::Fresh);
+        assert_eq!(response.evaluate(&request, at(120)), Decision::Revalidate);
+
+        let response = max_age(100).with_cache_control(vec![
+            CacheDirective::MaxAge(100),
+            CacheDirective::MustRevalidate,
+        ]);
+        assert_eq!(response.evaluate(&request, at(120)), Decision::Revalidate);
+    }
+}
@@ -85,6 +85,11 @@
 //! # }
 //! ```
 //!
+//! The [`TypedHeadersExt`](trait.TypedHeadersExt.html) extension trait
+//! provides `decode_header`/`try_encode` and related methods for any header
+//! type, including custom headers, and `decode_detailed` for a
+//! `HeaderError` describing a failure.
+//!
 //! ## Defining Custom Headers
 //!
 //! Hyper*x* provides all commonly used headers in HTTP. If you
@@ -95,15 +100,15 @@
 //! ```
 //! # extern crate http;
 //! # #[macro_use] extern crate hyperx;
+//! use hyperx::header::TypedHeadersExt;
+//!
 //! header! { (XRequestGuid, "X-Request-Guid") => [String] }
 //!
 //! fn main () {
 //!     let mut headers = http::HeaderMap::new();
 //!
-//!     headers.insert(
-//!         "x-request-guid",
-//!         XRequestGuid("a proper guid".to_owned()).to_string().parse().unwrap()
-//!     );
+//!     headers.try_encode(&XRequestGuid("a proper guid".to_owned())).unwrap();
+//!     assert_eq!(headers["x-request-guid"], "a proper guid");
 //! }
 //! ```
 //!
@@ -177,13 +182,23 @@ use self::internals::{Item, VecMap, Entry};
 #[cfg(feature = "headers")]
 use bytes::Bytes;
 
-pub use self::compat::{TypedHeaders, StandardHeader, ValueMapIter};
+pub use self::compat::{TypedHeaders, TypedHeadersBuilder, TypedHeadersExt, StandardHeader,
+                       ValueMapIter};
 
 mod common;
 mod internals;
 mod raw;
 mod shared;
+pub mod byteranges;
+pub mod chunked;
+pub mod conditional;
+pub mod forwarding;
+pub mod freshness;
+pub mod negotiation;
 pub mod parsing;
+pub mod ranges;
+pub mod structured;
+pub mod trailers;
 mod compat;
 
 /// A trait for any object that will represent a header field and value.
@@ -197,6 +212,36 @@ pub trait Header: 'static + HeaderClone + Send + Sync {
     fn header_name() -> &'static str
     where Self: Sized;
 
+    /// Returns the `HeaderName` of the _http_ crate for this header, as used
+    /// by `TypedHeadersExt`.
+    ///
+    /// The default implementation converts `header_name`, caching the result
+    /// per thread and type, so that later calls only clone it. The standard
+    /// headers return the constant, as also given by `StandardHeader`.
+    ///
+    /// # Panics
+    ///
+    /// The default implementation panics if `header_name` is not a valid
+    /// header name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate http;
+    /// # extern crate hyperx;
+    /// use hyperx::header::{ContentLength, Header, LastEventId};
+    ///
+    /// # fn main() {
+    /// assert_eq!(<ContentLength as Header>::http_header_name(), http::header::CONTENT_LENGTH);
+    /// assert_eq!(LastEventId::http_header_name(), "last-event-id");
+    /// # }
+    /// ```
+    fn http_header_name() -> ::http::header::HeaderName
+    where Self: Sized
+    {
+        compat::cached_http_header_name::<Self>()
+    }
+
     /// Parse a header from a raw stream of bytes.
     ///
     /// It's possible that a request can include a header field more than once,
@@ -207,6 +252,36 @@ pub trait Header: 'static + HeaderClone + Send + Sync {
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<Self>
     where T: RawLike<'a>, Self: Sized;
 
+    /// Parse a header like `parse_header`, but on failure return a
+    /// `HeaderError` with the header name, the offending value, the byte
+    /// offset and the reason, which converts into `Error::Header`.
+    ///
+    /// Unlike `parse_header`, list headers fail on any element which fails to
+    /// parse, rather than skipping it. The default implementation diagnoses
+    /// a failure of `parse_header` with `parsing::diagnose`, which is only
+    /// approximate. Headers of the `header!` macro override it to report
+    /// the actual reason and offset from `parsing::from_one_raw_str_detailed`
+    /// or `parsing::from_comma_delimited_detailed`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyperx::HeaderErrorKind;
+    /// use hyperx::header::{ContentLength, Header, Raw};
+    ///
+    /// let raw: Raw = "12a".into();
+    /// let err = ContentLength::parse_header_detailed(&raw).unwrap_err();
+    /// assert_eq!(err.name(), Some("Content-Length"));
+    /// assert_eq!(err.kind(), HeaderErrorKind::InvalidNumber);
+    /// assert_eq!(err.to_string(),
+    ///            "invalid Content-Length header: invalid number at byte 2 in \"12a\"");
+    /// ```
+    fn parse_header_detailed<'a, T>(raw: &'a T) -> ::std::result::Result<Self, ::HeaderError>
+    where T: RawLike<'a>, Self: Sized
+    {
+        Self::parse_header(raw).map_err(|_| parsing::diagnose::<Self, T>(raw))
+    }
+
     /// Format a header to outgoing stream.
     ///
     /// Most headers should be formatted on one line, and so a common pattern
@@ -335,6 +410,13 @@ impl<'a> fmt::Display for ValueString<'a> {
     }
 }
 
+// Format a header into raw lines, with one line per field line.
+pub(crate) fn fmt_raw<H: Header>(header: &H) -> Result<Raw, fmt::Error> {
+    let mut raw = Raw::from(Vec::<Vec<u8>>::new());
+    header.fmt_header(&mut Formatter(Multi::Raw(&mut raw)))?;
+    Ok(raw)
+}
+
 struct NewlineReplacer<'a, F: fmt::Write + 'a>(&'a mut F);
 
 impl<'a, F: fmt::Write + 'a> fmt::Write for NewlineReplacer<'a, F> {
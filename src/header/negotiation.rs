@@ -0,0 +1,509 @@
+//! Proactive content negotiation, selecting the best of a set of variants
+//! of a resource for the `Accept`, `Accept-Language`, `Accept-Encoding` and
+//! `Accept-Charset` request headers, as specified in
+//! [RFC 9110](https://www.rfc-editor.org/rfc/rfc9110#section-12).
+//!
+//! Each `Variant` describes a representation the server can send, by media
+//! type, language, content coding and charset. A `Negotiator` holds the
+//! request preferences and computes the quality of each variant as the
+//! product of its own (source) quality and the quality of the most specific
+//! match in each header. A variant with quality zero is not acceptable. Any
+//! dimension which a variant doesn't specify, or for which the header is
+//! absent, is acceptable.
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate http;
+//! # extern crate hyperx;
+//! # extern crate mime;
+//! use hyperx::header::Encoding;
+//! use hyperx::header::negotiation::{Negotiator, Variant};
+//!
+//! # fn main() {
+//! let mut headers = http::HeaderMap::new();
+//! headers.insert("accept", "text/html, application/json;q=0.9, */*;q=0.1".parse().unwrap());
+//! headers.insert("accept-encoding", "br, gzip, identity;q=0.5".parse().unwrap());
+//!
+//! let variants = [
+//!     Variant::new().with_media_type(mime::TEXT_PLAIN),
+//!     Variant::new().with_media_type(mime::APPLICATION_JSON),
+//!     Variant::new().with_media_type(mime::APPLICATION_JSON).with_encoding(Encoding::Gzip),
+//! ];
+//! let best = Negotiator::from_headers(&headers).negotiate(&variants);
+//! assert_eq!(best, Some(&variants[2]));
+//! # }
+//! ```
+
+use http::header::{HeaderMap, ACCEPT_LANGUAGE};
+use mime::{self, Mime};
+
+use header::{Accept, AcceptCharset, AcceptEncoding, AcceptLanguage, Charset, Encoding,
+             LanguageTag, Quality, QualityItem, TypedHeaders, q};
+use header::parsing::split_list;
+
+/// A representation of a resource which the server can select.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Variant {
+    /// The media type, matched against `Accept`.
+    pub media_type: Option<Mime>,
+    /// The language, matched against `Accept-Language`.
+    pub language: Option<LanguageTag>,
+    /// The content coding, matched against `Accept-Encoding`. `None` is
+    /// equivalent to `identity`.
+    pub encoding: Option<Encoding>,
+    /// The charset, matched against `Accept-Charset`. If `None`, the
+    /// `charset` parameter of the media type is used, if any.
+    pub charset: Option<Charset>,
+    /// The source quality: the server's relative preference for this
+    /// variant. Defaults to 1.
+    pub quality: Quality,
+}
+
+impl Variant {
+    /// Create a variant without any specified dimension.
+    pub fn new() -> Variant {
+        Variant::default()
+    }
+
+    /// Set the media type.
+    pub fn with_media_type(mut self, media_type: Mime) -> Variant {
+        self.media_type = Some(media_type);
+        self
+    }
+
+    /// Set the language.
+    pub fn with_language(mut self, language: LanguageTag) -> Variant {
+        self.language = Some(language);
+        self
+    }
+
+    /// Set the content coding.
+    pub fn with_encoding(mut self, encoding: Encoding) -> Variant {
+        self.encoding = Some(encoding);
+        self
+    }
+
+    /// Set the charset.
+    pub fn with_charset(mut self, charset: Charset) -> Variant {
+        self.charset = Some(charset);
+        self
+    }
+
+    /// Set the source quality.
+    pub fn with_quality(mut self, quality: Quality) -> Variant {
+        self.quality = quality;
+        self
+    }
+}
+
+/// The content negotiation preferences of a request.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Negotiator {
+    accept: Option<Accept>,
+    accept_language: Option<AcceptLanguage>,
+    accept_language_any: Option<Quality>,
+    accept_encoding: Option<AcceptEncoding>,
+    accept_charset: Option<AcceptCharset>,
+}
+
+impl Negotiator {
+    /// Create a negotiator without preferences, accepting any variant.
+    pub fn new() -> Negotiator {
+        Negotiator::default()
+    }
+
+    /// Create a negotiator from the headers of a request. Any header which
+    /// fails to parse is ignored, as if absent.
+    pub fn from_headers(headers: &HeaderMap) -> Negotiator {
+        Negotiator {
+            accept: headers.try_decode().and_then(Result::ok),
+            accept_language: headers.try_decode().and_then(Result::ok),
+            accept_language_any: language_wildcard(headers),
+            accept_encoding: headers.try_decode().and_then(Result::ok),
+            accept_charset: headers.try_decode().and_then(Result::ok),
+        }
+    }
+
+    /// Set the `Accept` header.
+    pub fn with_accept(mut self, accept: Accept) -> Negotiator {
+        self.accept = Some(accept);
+        self
+    }
+
+    /// Set the `Accept-Language` header.
+    pub fn with_accept_language(mut self, accept_language: AcceptLanguage) -> Negotiator {
+        self.accept_language = Some(accept_language);
+        self
+    }
+
+    /// Set the quality of the `*` language range of `Accept-Language`,
+    /// which `AcceptLanguage` can't represent. It applies to any language
+    /// not matched by another range.
+    pub fn with_accept_language_any(mut self, quality: Quality) -> Negotiator {
+        self.accept_language_any = Some(quality);
+        self
+    }
+
+    /// Set the `Accept-Encoding` header.
+    pub fn with_accept_encoding(mut self, accept_encoding: AcceptEncoding) -> Negotiator {
+        self.accept_encoding = Some(accept_encoding);
+        self
+    }
+
+    /// Set the `Accept-Charset` header.
+    pub fn with_accept_charset(mut self, accept_charset: AcceptCharset) -> Negotiator {
+        self.accept_charset = Some(accept_charset);
+        self
+    }
+
+    /// Return the acceptable variant with the highest quality, or `None` if
+    /// no variant is acceptable. Of variants with equal quality, the first
+    /// is returned.
+    ///
+    /// On `None`, a server may respond with 406 Not Acceptable, or disregard
+    /// the preferences and send a default representation.
+    pub fn negotiate<'v>(&self, variants: &'v [Variant]) -> Option<&'v Variant> {
+        let mut best = None;
+        let mut best_score = 0;
+        for variant in variants {
+            let score = self.score(variant);
+            if score > best_score {
+                best = Some(variant);
+                best_score = score;
+            }
+        }
+        best
+    }
+
+    /// Return the quality of a variant, where zero is not acceptable.
+    pub fn quality(&self, variant: &Variant) -> Quality {
+        q((self.score(variant) / 1_000_000_000_000) as u16)
+    }
+
+    // The product of all qualities, each in thousandths.
+    fn score(&self, variant: &Variant) -> u64 {
+        let charset = variant.charset.clone().or_else(|| {
+            variant.media_type.as_ref()
+                .and_then(|m| m.get_param(mime::CHARSET))
+                .and_then(|c| c.as_str().parse().ok())
+        });
+        [
+            variant.quality,
+            media_type_quality(self.accept.as_ref(), variant.media_type.as_ref()),
+            language_quality(self.accept_language.as_ref(), self.accept_language_any,
+                             variant.language.as_ref()),
+            encoding_quality(self.accept_encoding.as_ref(), variant.encoding.as_ref()),
+            charset_quality(self.accept_charset.as_ref(), charset.as_ref()),
+        ].iter().map(|q| u64::from(q.as_u16())).product()
+    }
+}
+
+// The quality of the most specific matching media range: type/subtype with
+// more parameters, then type/subtype, then type/*, then */*.
+fn media_type_quality(accept: Option<&Accept>, media_type: Option<&Mime>) -> Quality {
+    let (accept, media_type) = match (accept, media_type) {
+        (Some(accept), Some(media_type)) => (accept, media_type),
+        _ => return Quality::default(),
+    };
+    let mut best: Option<(usize, Quality)> = None;
+    for range in accept.iter() {
+        if let Some(specificity) = media_range_specificity(&range.item, media_type) {
+            if best.map_or(true, |(s, _)| specificity > s) {
+                best = Some((specificity, range.quality));
+            }
+        }
+    }
+    best.map_or(q(0), |(_, quality)| quality)
+}
+
+// Return the specificity of the range if it matches the media type.
+fn media_range_specificity(range: &Mime, media_type: &Mime) -> Option<usize> {
+    if range.type_() == mime::STAR {
+        return if range.subtype() == mime::STAR { Some(0) } else { None };
+    }
+    if !range.type_().as_str().eq_ignore_ascii_case(media_type.type_().as_str()) {
+        return None;
+    }
+    if range.subtype() == mime::STAR {
+        return Some(1);
+    }
+    if !range.subtype().as_str().eq_ignore_ascii_case(media_type.subtype().as_str()) {
+        return None;
+    }
+    let mut params = 0;
+    for (name, value) in range.params() {
+        match media_type.get_param(name) {
+            Some(v) if v.as_str().eq_ignore_ascii_case(value.as_str()) => params += 1,
+            _ => return None,
+        }
+    }
+    Some(2 + params)
+}
+
+// The quality of the longest language range which matches the tag by basic
+// filtering (RFC 4647, section 3.3.1): equal to the tag, or a prefix of it
+// followed by "-", ignoring case. The "*" range matches any other tag.
+fn language_quality(accept: Option<&AcceptLanguage>, any: Option<Quality>,
+                    language: Option<&LanguageTag>)
+    -> Quality
+{
+    let language = match language {
+        Some(language) => language.as_str(),
+        None => return Quality::default(),
+    };
+    let ranges = match accept {
+        Some(accept) => &accept[..],
+        None if any.is_some() => &[],
+        None => return Quality::default(),
+    };
+    let mut best: Option<(usize, Quality)> = None;
+    for range in ranges {
+        let range_str = range.item.as_str();
+        let matches = language.len() >= range_str.len()
+            && language[..range_str.len()].eq_ignore_ascii_case(range_str)
+            && (language.len() == range_str.len()
+                || language.as_bytes()[range_str.len()] == b'-');
+        if matches && best.map_or(true, |(len, _)| range_str.len() > len) {
+            best = Some((range_str.len(), range.quality));
+        }
+    }
+    best.map_or(any.unwrap_or(q(0)), |(_, quality)| quality)
+}
+
+// The quality of the "*" range of the Accept-Language header, which isn't a
+// valid `LanguageTag`, so is dropped from `AcceptLanguage`.
+fn language_wildcard(headers: &HeaderMap) -> Option<Quality> {
+    headers.get_all(ACCEPT_LANGUAGE).iter()
+        .filter_map(|line| line.to_str().ok())
+        .filter_map(|line| split_list(line).ok())
+        .flat_map(|elements| elements.into_iter())
+        .filter_map(|element| element.trim().parse::<QualityItem<String>>().ok())
+        .find(|range| range.item == "*")
+        .map(|range| range.quality)
+}
+
+// RFC 9110, section 12.5.3: without the header any coding is acceptable. An
+// empty header only accepts identity. "*" matches any coding not listed.
+// Identity is acceptable unless excluded explicitly or by "*;q=0".
+fn encoding_quality(accept: Option<&AcceptEncoding>, encoding: Option<&Encoding>) -> Quality {
+    let accept = match accept {
+        Some(accept) => accept,
+        None => return Quality::default(),
+    };
+    let name = encoding.map_or_else(|| "identity".to_owned(), coding_name);
+    if let Some(quality) = find_quality(accept, |e| coding_name(e) == name) {
+        return quality;
+    }
+    match find_quality(accept, |e| coding_name(e) == "*") {
+        Some(quality) => quality,
+        None if name == "identity" => Quality::default(),
+        None => q(0),
+    }
+}
+
+fn coding_name(encoding: &Encoding) -> String {
+    let name = encoding.to_string().to_ascii_lowercase();
+    match &name[..] {
+        "x-gzip" => "gzip".to_owned(),
+        "x-compress" => "compress".to_owned(),
+        _ => name,
+    }
+}
+
+// RFC 9110, section 12.5.2: without the header any charset is acceptable.
+// "*" matches any charset not listed.
+fn charset_quality(accept: Option<&AcceptCharset>, charset: Option<&Charset>) -> Quality {
+    let (accept, charset) = match (accept, charset) {
+        (Some(accept), Some(charset)) => (accept, charset.to_string()),
+        _ => return Quality::default(),
+    };
+    find_quality(accept, |c| c.to_string().eq_ignore_ascii_case(&charset))
+        .or_else(|| find_quality(accept, |c| c.to_string() == "*"))
+        .unwrap_or_else(|| q(0))
+}
+
+fn find_quality<T, F>(items: &[QualityItem<T>], f: F) -> Option<Quality>
+where F: Fn(&T) -> bool
+{
+    items.iter().find(|qi| f(&qi.item)).map(|qi| qi.quality)
+}
+
+#[cfg(test)]
+mod tests {
+    use http::header::HeaderMap;
+    use mime;
+
+    use header::{Accept, AcceptCharset, AcceptEncoding, AcceptLanguage, Charset, Encoding,
+                 Header, Raw, q};
+    use super::{Negotiator, Variant};
+
+    fn parse<H: Header>(s: &str) -> H {
+        let r: Raw = s.into();
+        H::parse_header(&r).unwrap()
+    }
+
+    fn media(s: &str) -> Variant {
+        Variant::new().with_media_type(s.parse().unwrap())
+    }
+
+    fn lang(s: &str) -> Variant {
+        Variant::new().with_language(s.parse().unwrap())
+    }
+
+    fn coding(s: &str) -> Variant {
+        Variant::new().with_encoding(s.parse().unwrap())
+    }
+
+    #[test]
+    fn test_no_preferences() {
+        let variants = [media("text/html"), lang("en")];
+        assert_eq!(Negotiator::new().negotiate(&variants), Some(&variants[0]));
+        assert_eq!(Negotiator::new().negotiate(&[]), None);
+    }
+
+    #[test]
+    fn test_media_range_specificity() {
+        // RFC 9110, section 12.5.1
+        let n = Negotiator::new().with_accept(parse::<Accept>(
+            "text/*;q=0.3, text/plain;q=0.7, text/plain;format=flowed, \
+             text/plain;format=fixed;q=0.4, */*;q=0.5"));
+        for &(media_type, quality) in &[
+            ("text/plain;format=flowed", 1000),
+            ("text/plain", 700),
+            ("text/html", 300),
+            ("image/jpeg", 500),
+            ("text/plain;format=fixed", 400),
+            ("text/html;level=3", 300),
+        ] {
+            assert_eq!(n.quality(&media(media_type)), q(quality), "{}", media_type);
+        }
+    }
+
+    #[test]
+    fn test_media_type_exclusion() {
+        let n = Negotiator::new().with_accept(parse::<Accept>("*/*, image/png;q=0"));
+        let variants = [media("image/png"), media("image/webp")];
+        assert_eq!(n.quality(&variants[0]), q(0));
+        assert_eq!(n.negotiate(&variants), Some(&variants[1]));
+        assert_eq!(n.negotiate(&variants[..1]), None);
+
+        let n = Negotiator::new().with_accept(parse::<Accept>("application/json"));
+        assert_eq!(n.negotiate(&[media("text/html")]), None);
+    }
+
+    #[test]
+    fn test_language() {
+        let n = Negotiator::new().with_accept_language(parse::<AcceptLanguage>(
+            "da, en-GB;q=0.8, en;q=0.7, de;q=0"));
+        for &(tag, quality) in &[
+            ("da", 1000),
+            ("en-GB", 800),
+            ("EN-gb", 800),
+            ("en-US", 700),
+            ("en", 700),
+            ("de-AT", 0),
+            ("fr", 0),
+            ("dan", 0),
+        ] {
+            assert_eq!(n.quality(&lang(tag)), q(quality), "{}", tag);
+        }
+        let variants = [lang("fr"), lang("en-US"), lang("en-GB")];
+        assert_eq!(n.negotiate(&variants), Some(&variants[2]));
+    }
+
+    #[test]
+    fn test_language_wildcard() {
+        let mut headers = HeaderMap::new();
+        headers.insert("accept-language", "en, de;q=0, *;q=0.5".parse().unwrap());
+        let n = Negotiator::from_headers(&headers);
+        assert_eq!(n.quality(&lang("en-US")), q(1000));
+        assert_eq!(n.quality(&lang("fr")), q(500));
+        assert_eq!(n.quality(&lang("de-CH")), q(0));
+        let variants = [lang("de"), lang("fr")];
+        assert_eq!(n.negotiate(&variants), Some(&variants[1]));
+
+        let mut headers = HeaderMap::new();
+        headers.insert("accept-language", "*;q=0".parse().unwrap());
+        let n = Negotiator::from_headers(&headers);
+        assert_eq!(n.quality(&lang("fr")), q(0));
+        assert_eq!(n.quality(&Variant::new()), q(1000));
+
+        let n = Negotiator::new().with_accept_language_any(q(200));
+        assert_eq!(n.quality(&lang("fr")), q(200));
+    }
+
+    #[test]
+    fn test_encoding() {
+        let n = Negotiator::new().with_accept_encoding(parse::<AcceptEncoding>(
+            "gzip;q=0.8, br"));
+        assert_eq!(n.quality(&coding("br")), q(1000));
+        assert_eq!(n.quality(&coding("x-gzip")), q(800));
+        assert_eq!(n.quality(&coding("deflate")), q(0));
+        assert_eq!(n.quality(&Variant::new()), q(1000));
+
+        let n = Negotiator::new().with_accept_encoding(parse::<AcceptEncoding>(
+            "gzip, *;q=0.2"));
+        assert_eq!(n.quality(&coding("deflate")), q(200));
+        assert_eq!(n.quality(&coding("identity")), q(200));
+
+        let n = Negotiator::new().with_accept_encoding(parse::<AcceptEncoding>(
+            "gzip, *;q=0"));
+        assert_eq!(n.quality(&Variant::new()), q(0));
+        let n = Negotiator::new().with_accept_encoding(parse::<AcceptEncoding>(
+            "gzip, identity;q=0"));
+        assert_eq!(n.quality(&Variant::new()), q(0));
+        assert_eq!(n.quality(&coding("gzip")), q(1000));
+
+        // An empty header only accepts identity
+        let n = Negotiator::new().with_accept_encoding(AcceptEncoding(vec![]));
+        assert_eq!(n.quality(&Variant::new()), q(1000));
+        assert_eq!(n.quality(&coding("gzip")), q(0));
+    }
+
+    #[test]
+    fn test_charset() {
+        let n = Negotiator::new().with_accept_charset(parse::<AcceptCharset>(
+            "utf-8, iso-8859-1;q=0.5"));
+        assert_eq!(n.quality(&Variant::new().with_charset(Charset::Ext("UTF-8".to_owned()))),
+                   q(1000));
+        assert_eq!(n.quality(&Variant::new().with_charset(Charset::Iso_8859_1)), q(500));
+        assert_eq!(n.quality(&Variant::new().with_charset(Charset::Us_Ascii)), q(0));
+        assert_eq!(n.quality(&Variant::new().with_media_type(mime::TEXT_PLAIN_UTF_8)),
+                   q(1000));
+        assert_eq!(n.quality(&media("text/plain; charset=koi8-r")), q(0));
+
+        let n = Negotiator::new().with_accept_charset(parse::<AcceptCharset>(
+            "utf-8, *;q=0.1"));
+        assert_eq!(n.quality(&Variant::new().with_charset(Charset::Us_Ascii)), q(100));
+    }
+
+    #[test]
+    fn test_combined() {
+        let n = Negotiator::new()
+            .with_accept(parse::<Accept>("text/html, application/xhtml+xml;q=0.9"))
+            .with_accept_language(parse::<AcceptLanguage>("fr;q=0.5, en"))
+            .with_accept_encoding(parse::<AcceptEncoding>("gzip"));
+        let html = media("text/html");
+        let variants = [
+            Variant { language: Some("fr".parse().unwrap()), ..html.clone() },
+            Variant { language: Some("en".parse().unwrap()), ..media("application/xhtml+xml") },
+            Variant { language: Some("en".parse().unwrap()), encoding: Some(Encoding::Brotli),
+                      ..html.clone() },
+            Variant { language: Some("en".parse().unwrap()), encoding: Some(Encoding::Gzip),
+                      quality: q(0.95), ..html.clone() },
+        ];
+        assert_eq!(n.quality(&variants[0]), q(500));
+        assert_eq!(n.quality(&variants[1]), q(900));
+        assert_eq!(n.quality(&variants[2]), q(0));
+        assert_eq!(n.quality(&variants[3]), q(950));
+        assert_eq!(n.negotiate(&variants), Some(&variants[3]));
+    }
+
+    #[test]
+    fn test_first_of_equal_quality() {
+        let n = Negotiator::new().with_accept(parse::<Accept>("text/*"));
+        let variants = [media("text/plain"), media("text/html")];
+        assert_eq!(n.negotiate(&variants), Some(&variants[0]));
+    }
+}
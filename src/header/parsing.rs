@@ -1,12 +1,14 @@
 //! Utility functions for Header implementations.
 
 use language_tags::LanguageTag;
+use std::borrow::Cow;
 use std::str;
 use std::str::FromStr;
 use std::fmt::{self, Display};
 use percent_encoding;
 
-use header::RawLike;
+use header::{Header, Raw, RawLike};
+use {HeaderError, HeaderErrorKind};
 use header::shared::Charset;
 
 /// Reads a single raw string when parsing a header.
@@ -27,7 +29,98 @@ pub fn from_raw_str<T: str::FromStr>(raw: &[u8]) -> ::Result<T> {
     T::from_str(s).or(Err(::Error::Header))
 }
 
+/// Reads a single raw string like `from_one_raw_str`, but on failure returns
+/// a `HeaderError` with the offending value, the byte offset and the reason,
+/// but no header name. See `from_raw_str_detailed`.
+pub fn from_one_raw_str_detailed<'a, R, T>(raw: &'a R) -> Result<T, HeaderError>
+where R: RawLike<'a>, T: str::FromStr
+{
+    one_raw_line(raw).and_then(from_raw_str_detailed)
+}
+
+/// Reads a single raw string like `from_one_raw_str_detailed`, as a number.
+/// See `from_raw_number_detailed`.
+pub fn from_one_raw_number_detailed<'a, R, T>(raw: &'a R) -> Result<T, HeaderError>
+where R: RawLike<'a>, T: str::FromStr
+{
+    one_raw_line(raw).and_then(from_raw_number_detailed)
+}
+
+/// Reads a single raw string like `from_one_raw_str_detailed`, as a date.
+/// See `from_raw_date_detailed`.
+pub fn from_one_raw_date_detailed<'a, R, T>(raw: &'a R) -> Result<T, HeaderError>
+where R: RawLike<'a>, T: str::FromStr
+{
+    one_raw_line(raw).and_then(from_raw_date_detailed)
+}
+
+// Returns the only line of a raw header, or a `Missing` or `MultipleValues`
+// error.
+fn one_raw_line<'a, R: RawLike<'a>>(raw: &'a R) -> Result<&'a [u8], HeaderError> {
+    let mut lines = raw.iter();
+    match (lines.next(), lines.next()) {
+        (None, _) => Err(HeaderError::new(HeaderErrorKind::Missing)),
+        (Some(_), Some(second)) => {
+            Err(HeaderError::new(HeaderErrorKind::MultipleValues).with_value(second, None))
+        }
+        (Some(line), None) => Ok(line),
+    }
+}
+
+/// Reads a raw string into a value like `from_raw_str`, but on failure
+/// returns a `HeaderError` with the value, the byte offset and the reason,
+/// but no header name.
+///
+/// The reason is `InvalidUtf8` or `Empty`, or otherwise `Invalid` at the
+/// start of the value, as the reason for a failure of `T::from_str` is
+/// unknown.
+pub fn from_raw_str_detailed<T: str::FromStr>(raw: &[u8]) -> Result<T, HeaderError> {
+    let (value, start) = trimmed_str(raw)?;
+    value.parse().map_err(|_| {
+        HeaderError::new(HeaderErrorKind::Invalid).with_value(raw, Some(start))
+    })
+}
+
+/// Reads a raw string into a number of decimal digits, `1*DIGIT`, like
+/// `from_raw_str_detailed`, but with reason `InvalidNumber`, at the first
+/// byte which isn't a digit, or at the start if it otherwise fails to parse,
+/// such as on overflow.
+pub fn from_raw_number_detailed<T: str::FromStr>(raw: &[u8]) -> Result<T, HeaderError> {
+    let (value, start) = trimmed_str(raw)?;
+    let offset = value.bytes().position(|b| !b.is_ascii_digit()).unwrap_or(0);
+    value.parse().map_err(|_| {
+        HeaderError::new(HeaderErrorKind::InvalidNumber).with_value(raw, Some(start + offset))
+    })
+}
+
+/// Reads a raw string into a date, such as `HttpDate`, like
+/// `from_raw_str_detailed`, but with reason `InvalidDate`.
+pub fn from_raw_date_detailed<T: str::FromStr>(raw: &[u8]) -> Result<T, HeaderError> {
+    let (value, start) = trimmed_str(raw)?;
+    value.parse().map_err(|_| {
+        HeaderError::new(HeaderErrorKind::InvalidDate).with_value(raw, Some(start))
+    })
+}
+
+// Returns the trimmed, non-empty UTF-8 value of a raw string, with the byte
+// offset of its start.
+fn trimmed_str(raw: &[u8]) -> Result<(&str, usize), HeaderError> {
+    let s = str::from_utf8(raw).map_err(|e| {
+        HeaderError::new(HeaderErrorKind::InvalidUtf8).with_value(raw, Some(e.valid_up_to()))
+    })?;
+    let value = s.trim();
+    if value.is_empty() {
+        return Err(HeaderError::new(HeaderErrorKind::Empty).with_value(raw, None));
+    }
+    Ok((value, s.len() - s.trim_start().len()))
+}
+
 /// Reads a comma-delimited raw header into a Vec.
+///
+/// Commas within quoted-strings don't delimit elements. Empty elements, and
+/// elements that fail to parse, are skipped. A line with an unterminated
+/// quoted-string is split on every comma instead, so that only the
+/// offending element is skipped.
 #[inline]
 pub fn from_comma_delimited<'a, R, T>(raw: &'a R) -> ::Result<Vec<T>>
 where R: RawLike<'a>, T: str::FromStr
@@ -35,16 +128,416 @@ where R: RawLike<'a>, T: str::FromStr
     let mut result = Vec::new();
     for s in raw.iter() {
         let s = str::from_utf8(s.as_ref())?;
-        result.extend(s.split(',')
-                      .filter_map(|x| match x.trim() {
-                          "" => None,
-                          y => Some(y)
-                      })
-                      .filter_map(|x| x.trim().parse().ok()))
+        let elements = split_list(s).unwrap_or_else(|_| {
+            s.split(',')
+                .map(|x| x.trim_matches(|c| c == ' ' || c == '\t'))
+                .filter(|x| !x.is_empty())
+                .collect()
+        });
+        result.extend(elements.into_iter().filter_map(|x| x.parse().ok()))
+    }
+    Ok(result)
+}
+
+/// Reads a comma-delimited raw header into a Vec, like
+/// `from_comma_delimited`, but fails on any element that fails to parse,
+/// instead of skipping it.
+///
+/// The `HeaderError` has the offending line, the byte offset and the
+/// reason, but no header name. The reason is `InvalidUtf8`, `InvalidToken`
+/// for an unterminated quoted-string, or otherwise `Invalid` at the start of
+/// the element. This is the strict parsing of `Header::parse_header_detailed`
+/// for list headers.
+pub fn from_comma_delimited_detailed<'a, R, T>(raw: &'a R) -> Result<Vec<T>, HeaderError>
+where R: RawLike<'a>, T: str::FromStr
+{
+    from_comma_delimited_detailed_with(raw, |element| {
+        element.parse().map_err(|_| (HeaderErrorKind::Invalid, 0))
+    })
+}
+
+/// Reads a comma-delimited raw header into a Vec like
+/// `from_comma_delimited_detailed`, parsing each element with the given
+/// function, which on failure returns the reason and the byte offset within
+/// the element.
+pub fn from_comma_delimited_detailed_with<'a, R, T, F>(raw: &'a R, mut parse: F)
+    -> Result<Vec<T>, HeaderError>
+where R: RawLike<'a>, F: FnMut(&str) -> Result<T, (HeaderErrorKind, usize)>
+{
+    let mut result = Vec::new();
+    for line in raw.iter() {
+        let s = str::from_utf8(line).map_err(|e| {
+            HeaderError::new(HeaderErrorKind::InvalidUtf8).with_value(line, Some(e.valid_up_to()))
+        })?;
+        let elements = split_list(s).map_err(|_| {
+            HeaderError::new(HeaderErrorKind::InvalidToken).with_value(line, token_error_offset(s))
+        })?;
+        for element in elements {
+            let offset = element.as_ptr() as usize - s.as_ptr() as usize;
+            result.push(parse(element).map_err(|(kind, inner)| {
+                HeaderError::new(kind).with_value(line, Some(offset + inner))
+            })?);
+        }
     }
     Ok(result)
 }
 
+/// Splits a list (`#element`) into its trimmed, non-empty elements.
+///
+/// Commas within quoted-strings don't delimit elements. Fails on an
+/// unterminated quoted-string.
+pub fn split_list(s: &str) -> ::Result<Vec<&str>> {
+    Ok(split_unquoted(s, b',')?
+        .into_iter()
+        .map(|x| x.trim_matches(|c| c == ' ' || c == '\t'))
+        .filter(|x| !x.is_empty())
+        .collect())
+}
+
+/// Splits on the delimiter, except where it appears within a quoted-string.
+/// Fails on an unterminated quoted-string.
+pub fn split_unquoted(s: &str, delim: u8) -> ::Result<Vec<&str>> {
+    let mut parts = Vec::new();
+    let mut in_quotes = false;
+    let mut escaped = false;
+    let mut start = 0;
+    for (i, &b) in s.as_bytes().iter().enumerate() {
+        if escaped {
+            escaped = false;
+        } else if in_quotes && b == b'\\' {
+            escaped = true;
+        } else if b == b'"' {
+            in_quotes = !in_quotes;
+        } else if !in_quotes && b == delim {
+            parts.push(&s[start..i]);
+            start = i + 1;
+        }
+    }
+    if in_quotes {
+        return Err(::Error::Header);
+    }
+    parts.push(&s[start..]);
+    Ok(parts)
+}
+
+/// Returns true if the value is a non-empty token.
+pub fn is_token(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(is_tchar)
+}
+
+fn is_tchar(b: u8) -> bool {
+    matches!(b,
+        b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' |
+        b'!' | b'#' | b'$' | b'%' | b'&' | b'\'' | b'*' |
+        b'+' | b'-' | b'.' | b'^' | b'_' | b'`' | b'|' | b'~')
+}
+
+/// Removes the surrounding quotes of a complete quoted-string and resolves
+/// any quoted-pair escapes.
+pub fn unquote(s: &str) -> ::Result<String> {
+    let bytes = s.as_bytes();
+    if bytes.len() < 2 || bytes[0] != b'"' || bytes[bytes.len() - 1] != b'"' {
+        return Err(::Error::Header);
+    }
+    let mut out = String::with_capacity(s.len() - 2);
+    let mut chars = s[1..s.len() - 1].chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(e) => out.push(e),
+                None => return Err(::Error::Header),
+            },
+            '"' => return Err(::Error::Header),
+            c => out.push(c),
+        }
+    }
+    Ok(out)
+}
+
+/// Writes the value as a quoted-string, escaping `"` and `\`.
+pub fn fmt_quoted(f: &mut fmt::Formatter, value: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    for c in value.chars() {
+        if c == '"' || c == '\\' {
+            f.write_str("\\")?;
+        }
+        fmt::Write::write_char(f, c)?;
+    }
+    f.write_str("\"")
+}
+
+/// Writes the value as a token if it is one, or else as a quoted-string.
+pub fn fmt_token_or_quoted(f: &mut fmt::Formatter, value: &str) -> fmt::Result {
+    if is_token(value) {
+        f.write_str(value)
+    } else {
+        fmt_quoted(f, value)
+    }
+}
+
+/// A lexical element of a header field value, as produced by `Tokenizer`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Token<'a> {
+    /// A token, one or more `tchar`s.
+    Token(&'a str),
+    /// The content of a quoted-string, with quoted-pairs resolved.
+    QuotedString(Cow<'a, str>),
+    /// The content of a comment without its outer parentheses. Nested
+    /// comments and quoted-pairs are left as they are.
+    Comment(&'a str),
+    /// Any other visible ASCII character, such as `,`, `;`, `=` or `/`.
+    Delimiter(char),
+}
+
+/// Splits a header field value into `Token`s, skipping the whitespace
+/// between them, per the grammar of
+/// [RFC 9110](https://www.rfc-editor.org/rfc/rfc9110#section-5.6).
+///
+/// An unterminated quoted-string or comment, or a control or non-ASCII
+/// character outside of them, is an error, which ends the iteration.
+///
+/// # ABNF
+///
+/// ```text
+/// token          = 1*tchar
+/// quoted-string  = DQUOTE *( qdtext / quoted-pair ) DQUOTE
+/// comment        = "(" *( ctext / quoted-pair / comment ) ")"
+/// quoted-pair    = "\" ( HTAB / SP / VCHAR / obs-text )
+/// ```
+///
+/// # Examples
+///
+/// ```
+/// use hyperx::header::parsing::{Token, Tokenizer};
+///
+/// let tokens: Vec<Token> = Tokenizer::new(r#"a=b; c="d, \"e\"" (f)"#)
+///     .collect::<Result<_, _>>()
+///     .unwrap();
+/// assert_eq!(tokens, vec![
+///     Token::Token("a"), Token::Delimiter('='), Token::Token("b"),
+///     Token::Delimiter(';'), Token::Token("c"), Token::Delimiter('='),
+///     Token::QuotedString("d, \"e\"".into()), Token::Comment("f"),
+/// ]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Tokenizer<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl<'a> Tokenizer<'a> {
+    /// Create a tokenizer for the value.
+    pub fn new(s: &'a str) -> Tokenizer<'a> {
+        Tokenizer { s, pos: 0 }
+    }
+
+    /// The remaining input, after the tokens read so far.
+    pub fn rest(&self) -> &'a str {
+        &self.s[self.pos..]
+    }
+
+    fn quoted_string(&mut self) -> ::Result<Token<'a>> {
+        let bytes = self.s.as_bytes();
+        let start = self.pos;
+        let mut escaped = false;
+        for (i, &b) in bytes.iter().enumerate().skip(start + 1) {
+            match b {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => {
+                    self.pos = i + 1;
+                    let quoted = &self.s[start..self.pos];
+                    return Ok(Token::QuotedString(if quoted.contains('\\') {
+                        Cow::Owned(unquote(quoted)?)
+                    } else {
+                        Cow::Borrowed(&quoted[1..quoted.len() - 1])
+                    }));
+                }
+                _ => {}
+            }
+        }
+        Err(::Error::Header)
+    }
+
+    fn comment(&mut self) -> ::Result<Token<'a>> {
+        let bytes = self.s.as_bytes();
+        let start = self.pos;
+        let mut escaped = false;
+        let mut depth = 0;
+        for (i, &b) in bytes.iter().enumerate().skip(start) {
+            match b {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'(' => depth += 1,
+                b')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        self.pos = i + 1;
+                        return Ok(Token::Comment(&self.s[start + 1..i]));
+                    }
+                }
+                _ => {}
+            }
+        }
+        Err(::Error::Header)
+    }
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = ::Result<Token<'a>>;
+
+    fn next(&mut self) -> Option<::Result<Token<'a>>> {
+        let bytes = self.s.as_bytes();
+        while self.pos < bytes.len() && (bytes[self.pos] == b' ' || bytes[self.pos] == b'\t') {
+            self.pos += 1;
+        }
+        let b = *bytes.get(self.pos)?;
+        let token = if is_tchar(b) {
+            let start = self.pos;
+            while self.pos < bytes.len() && is_tchar(bytes[self.pos]) {
+                self.pos += 1;
+            }
+            Ok(Token::Token(&self.s[start..self.pos]))
+        } else if b == b'"' {
+            self.quoted_string()
+        } else if b == b'(' {
+            self.comment()
+        } else if b.is_ascii_graphic() {
+            self.pos += 1;
+            Ok(Token::Delimiter(b as char))
+        } else {
+            Err(::Error::Header)
+        };
+        if token.is_err() {
+            self.pos = bytes.len();
+        }
+        Some(token)
+    }
+}
+
+/// Parses parameters, with unquoted values.
+///
+/// Parameters without a value, which some fields (such as `Prefer`) allow,
+/// have a value of `None`. Empty parameters are skipped.
+///
+/// # ABNF
+///
+/// ```text
+/// parameters      = *( OWS ";" OWS [ parameter ] )
+/// parameter       = parameter-name [ "=" parameter-value ]
+/// parameter-name  = token
+/// parameter-value = ( token / quoted-string )
+/// ```
+///
+/// # Examples
+///
+/// ```
+/// use hyperx::header::parsing::parse_parameters;
+///
+/// assert_eq!(
+///     parse_parameters(r#"; a=1 ;b="x; y";c"#).unwrap(),
+///     vec![
+///         ("a".to_owned(), Some("1".to_owned())),
+///         ("b".to_owned(), Some("x; y".to_owned())),
+///         ("c".to_owned(), None),
+///     ]);
+/// assert!(parse_parameters("; a=b=c").is_err());
+/// ```
+pub fn parse_parameters(s: &str) -> ::Result<Vec<(String, Option<String>)>> {
+    let mut params = Vec::new();
+    let mut tokens = Tokenizer::new(s).peekable();
+    while let Some(token) = tokens.next() {
+        if token? != Token::Delimiter(';') {
+            return Err(::Error::Header);
+        }
+        let name = match tokens.peek() {
+            Some(&Ok(Token::Token(name))) => name,
+            Some(&Ok(Token::Delimiter(';'))) | None => continue,
+            _ => return Err(::Error::Header),
+        };
+        tokens.next();
+        let value = if let Some(&Ok(Token::Delimiter('='))) = tokens.peek() {
+            tokens.next();
+            match tokens.next() {
+                Some(Ok(Token::Token(value))) => Some(value.to_owned()),
+                Some(Ok(Token::QuotedString(value))) => Some(value.into_owned()),
+                _ => return Err(::Error::Header),
+            }
+        } else {
+            None
+        };
+        params.push((name.to_owned(), value));
+    }
+    Ok(params)
+}
+
+/// Diagnoses why a raw header failed to parse as `H`, after the fact.
+///
+/// This is the default `Header::parse_header_detailed`, for headers without
+/// their own detailed parsing. Unlike those, the reason and offset are only
+/// approximate.
+///
+/// Missing, empty and non-UTF-8 values are detected directly, and multiple
+/// values if each line parses alone. Otherwise the value is tokenized to
+/// find invalid syntax, giving `InvalidToken`. Failing that, the reason is
+/// `Invalid`, at the start of the first list element which doesn't parse
+/// alone, if some do, or else of the value.
+pub fn diagnose<'a, H, R>(raw: &'a R) -> HeaderError
+where H: Header, R: RawLike<'a>
+{
+    let error = |kind| HeaderError::new(kind).with_name(H::header_name());
+    let parses = |value: &[u8]| H::parse_header(&Raw::from(value)).is_ok();
+
+    let lines: Vec<&[u8]> = raw.iter().collect();
+    if lines.is_empty() {
+        return error(HeaderErrorKind::Missing);
+    }
+    // Lines which each parse alone but not together are multiple values of
+    // a single-valued header. A list header may span lines.
+    if lines.len() > 1 && lines.iter().all(|line| parses(line)) {
+        return error(HeaderErrorKind::MultipleValues).with_value(lines[1], None);
+    }
+    let line = lines.iter().find(|line| !parses(line)).unwrap_or(&lines[0]);
+    let s = match str::from_utf8(line) {
+        Ok(s) => s,
+        Err(e) => {
+            return error(HeaderErrorKind::InvalidUtf8).with_value(line, Some(e.valid_up_to()));
+        }
+    };
+    if s.trim().is_empty() {
+        return error(HeaderErrorKind::Empty).with_value(line, None);
+    }
+
+    if let Some(offset) = token_error_offset(s) {
+        return error(HeaderErrorKind::InvalidToken).with_value(line, Some(offset));
+    }
+
+    let elements = split_list(s).unwrap_or_default();
+    let is_list = elements.len() > 1 && elements.iter().any(|element| parses(element.as_bytes()));
+    let element = if is_list {
+        elements.into_iter().find(|element| !parses(element.as_bytes()))
+    } else {
+        None
+    };
+    let element = element.unwrap_or_else(|| s.trim());
+    let offset = element.as_ptr() as usize - s.as_ptr() as usize;
+    error(HeaderErrorKind::Invalid).with_value(line, Some(offset))
+}
+
+// The byte offset of the first token which fails to tokenize, if any.
+fn token_error_offset(s: &str) -> Option<usize> {
+    let mut tokens = Tokenizer::new(s);
+    loop {
+        let rest = tokens.rest();
+        let start = s.len() - rest.trim_start_matches(|c| c == ' ' || c == '\t').len();
+        match tokens.next() {
+            Some(Ok(_)) => {}
+            Some(Err(_)) => return Some(start),
+            None => return None,
+        }
+    }
+}
+
 /// Format an array into a comma-delimited string.
 pub fn fmt_comma_delimited<T: Display>(f: &mut fmt::Formatter, parts: &[T]) -> fmt::Result {
     let mut iter = parts.iter();
@@ -180,10 +673,162 @@ mod percent_encoding_http {
 
 #[cfg(test)]
 mod tests {
+    use header::{Accept, CacheControl, CacheDirective, ContentLength, Date, EntityTag,
+                 Forwarded, Header, HttpDate, Raw};
     use header::shared::Charset;
-    use super::{ExtendedValue, parse_extended_value};
+    use {HeaderError, HeaderErrorKind};
+    use super::{ExtendedValue, Token, Tokenizer, from_comma_delimited,
+                from_comma_delimited_detailed, from_one_raw_str_detailed,
+                from_raw_date_detailed, from_raw_number_detailed, from_raw_str_detailed,
+                parse_extended_value, parse_parameters, split_list};
     use language_tags::LanguageTag;
 
+    #[test]
+    fn test_split_list() {
+        assert_eq!(split_list(" a , ,b,\t\"c, d\",\"e\\\"f,\" ").unwrap(),
+                   vec!["a", "b", "\"c, d\"", "\"e\\\"f,\""]);
+        assert!(split_list("a, \"b").is_err());
+        assert!(split_list("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_from_comma_delimited() {
+        let r: Raw = vec![b"1, x, 2".to_vec(), b"3".to_vec()].into();
+        let lenient: Vec<u32> = from_comma_delimited(&r).unwrap();
+        assert_eq!(lenient, vec![1, 2, 3]);
+        let err = from_comma_delimited_detailed::<_, u32>(&r).unwrap_err();
+        assert_eq!(err.value(), Some("1, x, 2"));
+        assert_eq!(err.offset(), Some(3));
+
+        let r: Raw = "\"a, b\", c".into();
+        let strings: Vec<String> = from_comma_delimited_detailed(&r).unwrap();
+        assert_eq!(strings, vec!["\"a, b\"".to_owned(), "c".to_owned()]);
+
+        let r: Raw = "a, \"b".into();
+        let err = from_comma_delimited_detailed::<_, String>(&r).unwrap_err();
+        assert_eq!(err.kind(), HeaderErrorKind::InvalidToken);
+        assert_eq!(err.offset(), Some(3));
+    }
+
+    #[test]
+    fn test_from_raw_str_detailed() {
+        fn detail<T, F>(parse: F, raw: &[u8]) -> (HeaderErrorKind, Option<usize>)
+        where F: Fn(&[u8]) -> Result<T, HeaderError>
+        {
+            let err = parse(raw).err().unwrap();
+            (err.kind(), err.offset())
+        }
+        let number = from_raw_number_detailed::<u64>;
+
+        assert_eq!(from_raw_str_detailed::<u64>(b" 12 ").unwrap(), 12);
+        assert_eq!(detail(from_raw_str_detailed::<u64>, b" 12a"),
+                   (HeaderErrorKind::Invalid, Some(1)));
+        assert_eq!(detail(from_raw_str_detailed::<EntityTag>, b"a"),
+                   (HeaderErrorKind::Invalid, Some(0)));
+        assert_eq!(from_raw_number_detailed::<u64>(b" 12 ").unwrap(), 12);
+        assert_eq!(detail(number, b"12a"), (HeaderErrorKind::InvalidNumber, Some(2)));
+        assert_eq!(detail(number, b"  12a"), (HeaderErrorKind::InvalidNumber, Some(4)));
+        assert_eq!(detail(number, b"-1"), (HeaderErrorKind::InvalidNumber, Some(0)));
+        assert_eq!(detail(from_raw_number_detailed::<u8>, b"256"),
+                   (HeaderErrorKind::InvalidNumber, Some(0)));
+        assert_eq!(detail(number, b" "), (HeaderErrorKind::Empty, None));
+        assert_eq!(detail(number, b"1\xff"), (HeaderErrorKind::InvalidUtf8, Some(1)));
+        assert_eq!(detail(from_raw_date_detailed::<HttpDate>, b"Sun, 06 Nov 1994 08:49:37 XYZ"),
+                   (HeaderErrorKind::InvalidDate, Some(0)));
+
+        let r: Raw = vec![b"1".to_vec(), b"2".to_vec()].into();
+        let err = from_one_raw_str_detailed::<_, u64>(&r).unwrap_err();
+        assert_eq!((err.kind(), err.value()), (HeaderErrorKind::MultipleValues, Some("2")));
+        let r: Raw = "max-age=10, max-age=1a".into();
+        let err = from_comma_delimited_detailed::<_, CacheDirective>(&r).unwrap_err();
+        assert_eq!((err.kind(), err.offset()), (HeaderErrorKind::Invalid, Some(12)));
+        let err = CacheControl::parse_header_detailed(&r).unwrap_err();
+        assert_eq!((err.kind(), err.offset()), (HeaderErrorKind::InvalidNumber, Some(20)));
+    }
+
+    #[test]
+    fn test_tokenizer() {
+        let tokens: Vec<Token> = Tokenizer::new("W/\"x\" (a (nested) \\) comment) ,")
+            .collect::<::Result<_>>()
+            .unwrap();
+        assert_eq!(tokens, vec![
+            Token::Token("W"), Token::Delimiter('/'), Token::QuotedString("x".into()),
+            Token::Comment("a (nested) \\) comment"), Token::Delimiter(','),
+        ]);
+
+        let mut tokens = Tokenizer::new("a \"b");
+        assert_eq!(tokens.next().unwrap().unwrap(), Token::Token("a"));
+        assert_eq!(tokens.rest(), " \"b");
+        assert!(tokens.next().unwrap().is_err());
+        assert!(tokens.next().is_none());
+
+        assert!(Tokenizer::new("(a").next().unwrap().is_err());
+        assert!(Tokenizer::new("\u{1}").next().unwrap().is_err());
+    }
+
+    #[test]
+    fn test_diagnose() {
+        fn diagnose<H: Header>(raw: Raw) -> (HeaderErrorKind, Option<usize>, Option<String>) {
+            let err = H::parse_header_detailed(&raw).err().unwrap();
+            assert_eq!(err.name(), Some(H::header_name()));
+            (err.kind(), err.offset(), err.value().map(str::to_owned))
+        }
+
+        assert_eq!(diagnose::<ContentLength>(Raw::from(Vec::<Vec<u8>>::new())),
+                   (HeaderErrorKind::Missing, None, None));
+        assert_eq!(diagnose::<ContentLength>(vec![b"1".to_vec(), b"2".to_vec()].into()),
+                   (HeaderErrorKind::MultipleValues, None, Some("2".to_owned())));
+        assert_eq!(diagnose::<ContentLength>(" ".into()),
+                   (HeaderErrorKind::Empty, None, Some(" ".to_owned())));
+        assert_eq!(diagnose::<ContentLength>(b"12\xff".to_vec().into()),
+                   (HeaderErrorKind::InvalidUtf8, Some(2), Some("12\u{fffd}".to_owned())));
+        assert_eq!(diagnose::<ContentLength>("-1".into()),
+                   (HeaderErrorKind::InvalidNumber, Some(0), Some("-1".to_owned())));
+        assert_eq!(diagnose::<Date>("Sun, 06 Nov 1994 08:49:37 XYZ".into()).0,
+                   HeaderErrorKind::InvalidDate);
+        assert_eq!(diagnose::<CacheControl>("no-cache, max-age=\"1".into()),
+                   (HeaderErrorKind::InvalidToken, Some(18),
+                    Some("no-cache, max-age=\"1".to_owned())));
+        // A list header may span lines
+        let r: Raw = vec![b"no-cache".to_vec(), b"max-age=\"1".to_vec()].into();
+        let err = super::diagnose::<CacheControl, _>(&r);
+        assert_eq!((err.kind(), err.offset()), (HeaderErrorKind::InvalidToken, Some(8)));
+
+        // List elements which `parse_header` skips are rejected
+        let r: Raw = "no-cache, max-age=abc".into();
+        assert_eq!(CacheControl::parse_header(&r).unwrap(),
+                   CacheControl(vec![CacheDirective::NoCache]));
+        assert_eq!(diagnose::<CacheControl>(r), (HeaderErrorKind::InvalidNumber, Some(18),
+                                                 Some("no-cache, max-age=abc".to_owned())));
+        let r: Raw = "text/html, chunk#;e".into();
+        assert_eq!(Accept::parse_header(&r).unwrap().len(), 1);
+        assert_eq!(diagnose::<Accept>(r).1, Some(11));
+        assert_eq!(diagnose::<Forwarded>("for=192.0.2.60, for=1:2, proto=http".into()),
+                   (HeaderErrorKind::Invalid, Some(16),
+                    Some("for=192.0.2.60, for=1:2, proto=http".to_owned())));
+    }
+
+    #[test]
+    fn test_error_excerpt() {
+        let value = format!("{}!{}", "a".repeat(100), "b".repeat(100));
+        let err = HeaderError::new(HeaderErrorKind::InvalidToken)
+            .with_name("X-Test")
+            .with_value(value.as_bytes(), Some(100));
+        assert_eq!(err.value(), Some(&*format!("...{}!{}...", "a".repeat(16), "b".repeat(47))));
+        assert_eq!(::Error::from(err).to_string(), "invalid Header provided");
+    }
+
+    #[test]
+    fn test_parse_parameters() {
+        assert_eq!(parse_parameters("").unwrap(), vec![]);
+        assert_eq!(parse_parameters(" ;; a = \"\\\"b\\\"\" ;").unwrap(),
+                   vec![("a".to_owned(), Some("\"b\"".to_owned()))]);
+        assert!(parse_parameters("a=b").is_err());
+        assert!(parse_parameters("; a=").is_err());
+        assert!(parse_parameters("; a=b/c").is_err());
+        assert!(parse_parameters("; =b").is_err());
+    }
+
     #[test]
     fn test_parse_extended_value_with_encoding_and_language_tag() {
         let expected_language_tag = "en".parse::<LanguageTag>().unwrap();
@@ -0,0 +1,280 @@
+//! Resolution of range requests, as specified in
+//! [RFC 9110](https://www.rfc-editor.org/rfc/rfc9110#section-14), into the
+//! response to send.
+//!
+//! A `RangeResolver` evaluates `If-Range`, normalizes each
+//! `ByteRangeSpec` against the representation length, and sorts and
+//! coalesces the result. Since many small, overlapping or unordered ranges
+//! are a denial of service vector (RFC 9110, section 17.15), it limits the
+//! number of ranges and overlaps, beyond which the `Range` header is ignored
+//! and the full representation is sent.
+//!
+//! # Examples
+//!
+//! ```
+//! use hyperx::header::{ContentRange, ContentRangeSpec, Range};
+//! use hyperx::header::conditional::Resource;
+//! use hyperx::header::ranges::{RangeResolver, Resolution};
+//!
+//! let range = Range::bytes_multi(vec![(0, 99), (50, 199), (1000, 1099)]);
+//! let resolver = RangeResolver::new();
+//! assert_eq!(
+//!     resolver.resolve(&range, None, &Resource::existing(), 10_000),
+//!     Resolution::Multiple(vec![(0, 199), (1000, 1099)]));
+//!
+//! assert_eq!(
+//!     resolver.resolve(&Range::bytes(20_000, 20_100), None, &Resource::existing(), 10_000),
+//!     Resolution::NotSatisfiable(ContentRange(ContentRangeSpec::Bytes {
+//!         range: None,
+//!         instance_length: Some(10_000),
+//!     })));
+//! ```
+
+use http::StatusCode;
+
+use header::{ContentRange, ContentRangeSpec, IfRange, Range};
+use header::conditional::{Preconditions, Resource};
+
+/// The response to a range request, as resolved by `RangeResolver`.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Resolution {
+    /// Send the full representation with 200 OK, ignoring the `Range`.
+    Full,
+    /// Send a single part with 206 Partial Content and this `Content-Range`.
+    Single(ContentRange),
+    /// Send a `multipart/byteranges` body with 206 Partial Content with
+    /// these sorted, non-overlapping, end-inclusive ranges.
+    Multiple(Vec<(u64, u64)>),
+    /// Respond with 416 Range Not Satisfiable and this `Content-Range`.
+    NotSatisfiable(ContentRange),
+}
+
+impl Resolution {
+    /// Return the response status.
+    pub fn status(&self) -> StatusCode {
+        match *self {
+            Resolution::Full => StatusCode::OK,
+            Resolution::Single(_) | Resolution::Multiple(_) => StatusCode::PARTIAL_CONTENT,
+            Resolution::NotSatisfiable(_) => StatusCode::RANGE_NOT_SATISFIABLE,
+        }
+    }
+}
+
+/// Resolves range requests, with limits on abusive range sets.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RangeResolver {
+    max_ranges: usize,
+    max_overlaps: usize,
+    coalesce_gap: u64,
+}
+
+impl Default for RangeResolver {
+    fn default() -> RangeResolver {
+        RangeResolver {
+            max_ranges: RangeResolver::DEFAULT_MAX_RANGES,
+            max_overlaps: RangeResolver::DEFAULT_MAX_OVERLAPS,
+            coalesce_gap: RangeResolver::DEFAULT_COALESCE_GAP,
+        }
+    }
+}
+
+impl RangeResolver {
+    /// The default maximum number of ranges in a request, 32.
+    pub const DEFAULT_MAX_RANGES: usize = 32;
+
+    /// The default maximum number of overlapping ranges in a request, 4.
+    pub const DEFAULT_MAX_OVERLAPS: usize = 4;
+
+    /// The default maximum gap between ranges which are coalesced, 80
+    /// bytes: roughly the overhead of a part of a `multipart/byteranges`
+    /// body.
+    pub const DEFAULT_COALESCE_GAP: u64 = 80;
+
+    /// Create with the default limits.
+    pub fn new() -> RangeResolver {
+        RangeResolver::default()
+    }
+
+    /// Set the maximum number of ranges (`ByteRangeSpec`s) in a request.
+    /// With more, the `Range` is ignored.
+    pub fn with_max_ranges(mut self, max_ranges: usize) -> RangeResolver {
+        self.max_ranges = max_ranges;
+        self
+    }
+
+    /// Set the maximum number of satisfiable ranges which overlap another.
+    /// With more, the `Range` is ignored.
+    pub fn with_max_overlaps(mut self, max_overlaps: usize) -> RangeResolver {
+        self.max_overlaps = max_overlaps;
+        self
+    }
+
+    /// Set the maximum number of bytes between two ranges for them to be
+    /// coalesced into one. Adjacent and overlapping ranges are always
+    /// coalesced.
+    pub fn with_coalesce_gap(mut self, coalesce_gap: u64) -> RangeResolver {
+        self.coalesce_gap = coalesce_gap;
+        self
+    }
+
+    /// Resolve a `Range` of a GET request for a representation of the given
+    /// length, with the optional `If-Range` of the request and the
+    /// validators of the resource.
+    ///
+    /// The full representation is sent if `If-Range` doesn't match, the
+    /// range unit isn't bytes, or a limit is exceeded. If no range is
+    /// satisfiable, the result is 416 Range Not Satisfiable. Otherwise, the
+    /// satisfiable ranges are sorted and coalesced, giving one or more
+    /// ranges.
+    pub fn resolve(&self, range: &Range, if_range: Option<&IfRange>, resource: &Resource,
+                   length: u64) -> Resolution
+    {
+        if let Some(if_range) = if_range {
+            if !Preconditions::new().with_if_range(if_range.clone()).range_applies(resource) {
+                return Resolution::Full;
+            }
+        }
+        let specs = match *range {
+            Range::Bytes(ref specs) => specs,
+            Range::Unregistered(..) => return Resolution::Full,
+        };
+        if specs.is_empty() || specs.len() > self.max_ranges {
+            return Resolution::Full;
+        }
+
+        let mut ranges: Vec<(u64, u64)> = specs.iter()
+            .filter_map(|spec| spec.to_satisfiable_range(length))
+            .collect();
+        if ranges.is_empty() {
+            return Resolution::NotSatisfiable(ContentRange(ContentRangeSpec::Bytes {
+                range: None,
+                instance_length: Some(length),
+            }));
+        }
+        ranges.sort_unstable();
+
+        let mut overlaps = 0;
+        let mut coalesced: Vec<(u64, u64)> = Vec::with_capacity(ranges.len());
+        for (start, end) in ranges {
+            if let Some(last) = coalesced.last_mut() {
+                if start <= last.1 {
+                    overlaps += 1;
+                    if overlaps > self.max_overlaps {
+                        return Resolution::Full;
+                    }
+                }
+                if start <= last.1.saturating_add(1).saturating_add(self.coalesce_gap) {
+                    last.1 = last.1.max(end);
+                    continue;
+                }
+            }
+            coalesced.push((start, end));
+        }
+
+        if coalesced.len() == 1 {
+            Resolution::Single(ContentRange(ContentRangeSpec::Bytes {
+                range: Some(coalesced[0]),
+                instance_length: Some(length),
+            }))
+        } else {
+            Resolution::Multiple(coalesced)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use header::{ByteRangeSpec, ContentRange, ContentRangeSpec, EntityTag, IfRange, Range};
+    use header::conditional::Resource;
+    use super::{RangeResolver, Resolution};
+
+    fn single(start: u64, end: u64, length: u64) -> Resolution {
+        Resolution::Single(ContentRange(ContentRangeSpec::Bytes {
+            range: Some((start, end)),
+            instance_length: Some(length),
+        }))
+    }
+
+    fn resolve(resolver: &RangeResolver, range: &str, length: u64) -> Resolution {
+        resolver.resolve(&range.parse().unwrap(), None, &Resource::existing(), length)
+    }
+
+    #[test]
+    fn test_single() {
+        let r = RangeResolver::new();
+        assert_eq!(resolve(&r, "bytes=0-499", 10_000), single(0, 499, 10_000));
+        assert_eq!(resolve(&r, "bytes=9500-", 10_000), single(9500, 9999, 10_000));
+        assert_eq!(resolve(&r, "bytes=-500", 10_000), single(9500, 9999, 10_000));
+        assert_eq!(resolve(&r, "bytes=9500-20000", 10_000), single(9500, 9999, 10_000));
+        assert_eq!(resolve(&r, "bytes=-20000", 10_000), single(0, 9999, 10_000));
+        assert_eq!(single(0, 1, 2).status(), StatusCode::PARTIAL_CONTENT);
+    }
+
+    #[test]
+    fn test_not_satisfiable() {
+        let r = RangeResolver::new();
+        let expected = Resolution::NotSatisfiable(ContentRange(ContentRangeSpec::Bytes {
+            range: None,
+            instance_length: Some(100),
+        }));
+        assert_eq!(resolve(&r, "bytes=100-200", 100), expected);
+        assert_eq!(resolve(&r, "bytes=100-200, 300-", 100), expected);
+        assert_eq!(expected.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+        // Unsatisfiable ranges are dropped from a satisfiable set
+        assert_eq!(resolve(&r, "bytes=100-200, 10-19", 100), single(10, 19, 100));
+    }
+
+    #[test]
+    fn test_coalesce() {
+        let r = RangeResolver::new().with_coalesce_gap(0);
+        assert_eq!(resolve(&r, "bytes=500-599, 0-99, 200-299", 1000),
+                   Resolution::Multiple(vec![(0, 99), (200, 299), (500, 599)]));
+        // Adjacent and overlapping
+        assert_eq!(resolve(&r, "bytes=0-99, 100-199, 150-249, 400-", 1000),
+                   Resolution::Multiple(vec![(0, 249), (400, 999)]));
+        assert_eq!(resolve(&r, "bytes=0-99, 100-199", 1000), single(0, 199, 1000));
+        // Small gaps
+        let r = RangeResolver::new();
+        assert_eq!(resolve(&r, "bytes=0-99, 180-199, 281-300", 1000),
+                   Resolution::Multiple(vec![(0, 199), (281, 300)]));
+        assert_eq!(Resolution::Multiple(vec![]).status(), StatusCode::PARTIAL_CONTENT);
+    }
+
+    #[test]
+    fn test_limits() {
+        let r = RangeResolver::new().with_max_ranges(3).with_max_overlaps(1);
+        assert_eq!(resolve(&r, "bytes=0-9, 20-29, 40-49, 60-69", 1000), Resolution::Full);
+        assert_eq!(resolve(&r, "bytes=0-9, 5-14, 10-19", 1000), Resolution::Full);
+        assert_eq!(resolve(&r, "bytes=0-9, 5-14, 200-", 1000),
+                   Resolution::Multiple(vec![(0, 14), (200, 999)]));
+        assert_eq!(Resolution::Full.status(), StatusCode::OK);
+
+        // The many overlapping ranges of a classic attack
+        let specs = (0..10).map(|_| ByteRangeSpec::AllFrom(0)).collect();
+        let range = Range::Bytes(specs);
+        assert_eq!(RangeResolver::new().resolve(&range, None, &Resource::existing(), 1000),
+                   Resolution::Full);
+    }
+
+    #[test]
+    fn test_unregistered() {
+        let range = Range::Unregistered("items".to_owned(), "0-9".to_owned());
+        assert_eq!(RangeResolver::new().resolve(&range, None, &Resource::existing(), 1000),
+                   Resolution::Full);
+    }
+
+    #[test]
+    fn test_if_range() {
+        let resource = Resource::existing().with_etag(EntityTag::strong("v1".to_owned()));
+        let range = Range::bytes(0, 9);
+        let r = RangeResolver::new();
+        let matching = IfRange::EntityTag(EntityTag::strong("v1".to_owned()));
+        assert_eq!(r.resolve(&range, Some(&matching), &resource, 100), single(0, 9, 100));
+        let stale = IfRange::EntityTag(EntityTag::strong("v0".to_owned()));
+        assert_eq!(r.resolve(&range, Some(&stale), &resource, 100), Resolution::Full);
+        let weak = IfRange::EntityTag(EntityTag::weak("v1".to_owned()));
+        assert_eq!(r.resolve(&range, Some(&weak), &resource, 100), Resolution::Full);
+    }
+}
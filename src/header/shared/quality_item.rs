@@ -22,6 +22,14 @@ use self::internal::IntoQuality;
 #[derive(Copy, Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
 pub struct Quality(u16);
 
+impl Quality {
+    /// Return the quality as an integer from 0 to 1000, in thousandths, as
+    /// accepted by `q`.
+    pub fn as_u16(self) -> u16 {
+        self.0
+    }
+}
+
 impl Default for Quality {
     fn default() -> Quality {
         Quality(1000)
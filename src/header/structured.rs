@@ -0,0 +1,1169 @@
+//! Structured Field Values for HTTP, as defined in
+//! [RFC 9651](https://www.rfc-editor.org/rfc/rfc9651) (obsoleting RFC 8941).
+//!
+//! Structured fields are one of three top-level types: an `Item`, a `List`
+//! or a `Dictionary`. Use `parse_item`, `parse_list` or `parse_dictionary`
+//! to parse all values (lines) of a header field, or the `FromStr`
+//! implementations for a single string. The `Display` implementations
+//! serialize in canonical form, and fail with `fmt::Error` if a value is out
+//! of range or contains characters not allowed for its type.
+//!
+//! # Examples
+//!
+//! ```
+//! use hyperx::header::structured::{BareItem, Dictionary, Item, Member};
+//!
+//! let dict: Dictionary = "u=2, i".parse().unwrap();
+//! assert_eq!(dict.get("u"), Some(&Member::Item(Item::new(2))));
+//! assert_eq!(dict.get("i"), Some(&Member::Item(Item::new(true))));
+//!
+//! let mut dict = Dictionary::new();
+//! dict.insert("u", Item::new(5));
+//! dict.insert("i", Item::new(BareItem::Boolean(true)));
+//! assert_eq!(dict.to_string(), "u=5, i");
+//! ```
+
+use std::borrow::Cow;
+use std::fmt::{self, Display, Write};
+use std::ops::{Deref, DerefMut};
+use std::str::{self, FromStr};
+
+use base64;
+
+use header::RawLike;
+
+/// An Item: a bare item with parameters.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Item {
+    /// The bare item value.
+    pub bare_item: BareItem,
+    /// The parameters of the item.
+    pub params: Parameters,
+}
+
+impl Item {
+    /// Create a new item without parameters.
+    pub fn new<B: Into<BareItem>>(bare_item: B) -> Item {
+        Item {
+            bare_item: bare_item.into(),
+            params: Parameters::new(),
+        }
+    }
+
+    /// Add or replace a parameter of this item.
+    pub fn with_param<K, V>(mut self, key: K, value: V) -> Item
+        where K: Into<String>, V: Into<BareItem>
+    {
+        self.params.insert(key, value);
+        self
+    }
+}
+
+impl Display for Item {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Display::fmt(&self.bare_item, f)?;
+        Display::fmt(&self.params, f)
+    }
+}
+
+impl FromStr for Item {
+    type Err = ::Error;
+
+    fn from_str(s: &str) -> ::Result<Item> {
+        Parser::new(s.as_bytes()).parse_top(Parser::parse_item)
+    }
+}
+
+/// A bare item value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BareItem {
+    /// An Integer, in the range of ±999,999,999,999,999.
+    Integer(i64),
+    /// A Decimal, with at most 12 integer and 3 fractional digits.
+    Decimal(Decimal),
+    /// A String of printable ASCII characters.
+    String(String),
+    /// A Token, such as `text/html` or `*foo`.
+    Token(String),
+    /// A Byte Sequence, serialized as base64.
+    ByteSequence(Vec<u8>),
+    /// A Boolean.
+    Boolean(bool),
+    /// A Date, as seconds since the Unix epoch.
+    Date(i64),
+    /// A Display String of Unicode characters.
+    DisplayString(String),
+}
+
+impl BareItem {
+    /// Return the Integer value, if this is one.
+    pub fn as_integer(&self) -> Option<i64> {
+        match *self {
+            BareItem::Integer(i) => Some(i),
+            _ => None,
+        }
+    }
+
+    /// Return the Decimal value, if this is one.
+    pub fn as_decimal(&self) -> Option<Decimal> {
+        match *self {
+            BareItem::Decimal(d) => Some(d),
+            _ => None,
+        }
+    }
+
+    /// Return the String value, if this is one.
+    pub fn as_str(&self) -> Option<&str> {
+        match *self {
+            BareItem::String(ref s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Return the Token value, if this is one.
+    pub fn as_token(&self) -> Option<&str> {
+        match *self {
+            BareItem::Token(ref s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Return the Byte Sequence value, if this is one.
+    pub fn as_byte_sequence(&self) -> Option<&[u8]> {
+        match *self {
+            BareItem::ByteSequence(ref b) => Some(b),
+            _ => None,
+        }
+    }
+
+    /// Return the Boolean value, if this is one.
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            BareItem::Boolean(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    /// Return the Date value, if this is one.
+    pub fn as_date(&self) -> Option<i64> {
+        match *self {
+            BareItem::Date(d) => Some(d),
+            _ => None,
+        }
+    }
+
+    /// Return the Display String value, if this is one.
+    pub fn as_display_string(&self) -> Option<&str> {
+        match *self {
+            BareItem::DisplayString(ref s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+impl From<i64> for BareItem {
+    fn from(i: i64) -> BareItem {
+        BareItem::Integer(i)
+    }
+}
+
+impl From<Decimal> for BareItem {
+    fn from(d: Decimal) -> BareItem {
+        BareItem::Decimal(d)
+    }
+}
+
+impl From<bool> for BareItem {
+    fn from(b: bool) -> BareItem {
+        BareItem::Boolean(b)
+    }
+}
+
+impl From<Vec<u8>> for BareItem {
+    fn from(b: Vec<u8>) -> BareItem {
+        BareItem::ByteSequence(b)
+    }
+}
+
+impl<'a> From<&'a str> for BareItem {
+    fn from(s: &'a str) -> BareItem {
+        BareItem::String(s.to_owned())
+    }
+}
+
+impl From<String> for BareItem {
+    fn from(s: String) -> BareItem {
+        BareItem::String(s)
+    }
+}
+
+impl Display for BareItem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BareItem::Integer(i) => fmt_integer(f, i),
+            BareItem::Decimal(d) => Display::fmt(&d, f),
+            BareItem::String(ref s) => {
+                f.write_char('"')?;
+                for c in s.chars() {
+                    match c {
+                        '"' | '\\' => {
+                            f.write_char('\\')?;
+                            f.write_char(c)?;
+                        }
+                        ' '..='~' => f.write_char(c)?,
+                        _ => return Err(fmt::Error),
+                    }
+                }
+                f.write_char('"')
+            }
+            BareItem::Token(ref s) => {
+                let mut bytes = s.bytes();
+                match bytes.next() {
+                    Some(b) if b.is_ascii_alphabetic() || b == b'*' => (),
+                    _ => return Err(fmt::Error),
+                }
+                if !bytes.all(|b| is_tchar(b) || b == b':' || b == b'/') {
+                    return Err(fmt::Error);
+                }
+                f.write_str(s)
+            }
+            BareItem::ByteSequence(ref b) => write!(f, ":{}:", base64::encode(b)),
+            BareItem::Boolean(b) => f.write_str(if b { "?1" } else { "?0" }),
+            BareItem::Date(d) => {
+                f.write_char('@')?;
+                fmt_integer(f, d)
+            }
+            BareItem::DisplayString(ref s) => {
+                f.write_str("%\"")?;
+                for b in s.bytes() {
+                    match b {
+                        b'%' | b'"' => write!(f, "%{:02x}", b)?,
+                        b' '..=b'~' => f.write_char(b as char)?,
+                        _ => write!(f, "%{:02x}", b)?,
+                    }
+                }
+                f.write_char('"')
+            }
+        }
+    }
+}
+
+/// A Decimal number, stored exactly as an integer count of thousandths.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Decimal(i64);
+
+impl Decimal {
+    /// Create a Decimal from an integer count of thousandths, so that
+    /// `Decimal::from_thousandths(1500)` is `1.5`.
+    pub fn from_thousandths(thousandths: i64) -> Decimal {
+        Decimal(thousandths)
+    }
+
+    /// Return the value as an integer count of thousandths.
+    pub fn thousandths(self) -> i64 {
+        self.0
+    }
+
+    /// Create a Decimal from a float, rounding to three fractional digits
+    /// (half to even). Returns `None` if the value is not finite or is out of
+    /// range.
+    pub fn from_f64(value: f64) -> Option<Decimal> {
+        if !value.is_finite() {
+            return None;
+        }
+        let scaled = value * 1000.0;
+        let rounded = if (scaled - scaled.trunc()).abs() == 0.5 {
+            (scaled / 2.0).round() * 2.0
+        } else {
+            scaled.round()
+        };
+        if rounded.abs() > MAX_DECIMAL as f64 {
+            return None;
+        }
+        Some(Decimal(rounded as i64))
+    }
+
+    /// Return the value as a float.
+    pub fn as_f64(self) -> f64 {
+        self.0 as f64 / 1000.0
+    }
+}
+
+impl Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let abs = match self.0.checked_abs() {
+            Some(abs) if abs <= MAX_DECIMAL => abs,
+            _ => return Err(fmt::Error),
+        };
+        if self.0 < 0 {
+            f.write_char('-')?;
+        }
+        let frac = abs % 1000;
+        write!(f, "{}.", abs / 1000)?;
+        if frac == 0 {
+            f.write_char('0')
+        } else {
+            let digits = format!("{:03}", frac);
+            f.write_str(digits.trim_end_matches('0'))
+        }
+    }
+}
+
+/// The parameters of an `Item` or `InnerList`, as ordered, unique keys with
+/// bare item values.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct Parameters(Vec<(String, BareItem)>);
+
+impl Parameters {
+    /// Create empty parameters.
+    pub fn new() -> Parameters {
+        Parameters(Vec::new())
+    }
+
+    /// Return the value of the parameter with the given key.
+    pub fn get(&self, key: &str) -> Option<&BareItem> {
+        self.0.iter().find(|p| p.0 == key).map(|p| &p.1)
+    }
+
+    /// Add a parameter, or replace the value of an existing parameter in
+    /// place.
+    pub fn insert<K, V>(&mut self, key: K, value: V)
+        where K: Into<String>, V: Into<BareItem>
+    {
+        insert(&mut self.0, key.into(), value.into());
+    }
+
+    /// Iterate over the (key, value) pairs, in order.
+    pub fn iter(&self) -> ::std::slice::Iter<'_, (String, BareItem)> {
+        self.0.iter()
+    }
+
+    /// Return the number of parameters.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Return true if there are no parameters.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Display for Parameters {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (key, value) in self.iter() {
+            f.write_char(';')?;
+            fmt_key(f, key)?;
+            if *value != BareItem::Boolean(true) {
+                f.write_char('=')?;
+                Display::fmt(value, f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An Inner List: a list of items with parameters, as a member of a `List`
+/// or `Dictionary`.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct InnerList {
+    /// The items of the inner list.
+    pub items: Vec<Item>,
+    /// The parameters of the inner list.
+    pub params: Parameters,
+}
+
+impl InnerList {
+    /// Create a new inner list without parameters.
+    pub fn new(items: Vec<Item>) -> InnerList {
+        InnerList {
+            items,
+            params: Parameters::new(),
+        }
+    }
+
+    /// Add or replace a parameter of this inner list.
+    pub fn with_param<K, V>(mut self, key: K, value: V) -> InnerList
+        where K: Into<String>, V: Into<BareItem>
+    {
+        self.params.insert(key, value);
+        self
+    }
+}
+
+impl Display for InnerList {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_char('(')?;
+        for (i, item) in self.items.iter().enumerate() {
+            if i > 0 {
+                f.write_char(' ')?;
+            }
+            Display::fmt(item, f)?;
+        }
+        f.write_char(')')?;
+        Display::fmt(&self.params, f)
+    }
+}
+
+/// A member of a `List` or `Dictionary`: either an `Item` or an
+/// `InnerList`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Member {
+    /// An item
+    Item(Item),
+    /// An inner list
+    InnerList(InnerList),
+}
+
+impl Member {
+    /// Return the item, if this is one.
+    pub fn as_item(&self) -> Option<&Item> {
+        match *self {
+            Member::Item(ref item) => Some(item),
+            Member::InnerList(_) => None,
+        }
+    }
+
+    /// Return the inner list, if this is one.
+    pub fn as_inner_list(&self) -> Option<&InnerList> {
+        match *self {
+            Member::Item(_) => None,
+            Member::InnerList(ref list) => Some(list),
+        }
+    }
+}
+
+impl From<Item> for Member {
+    fn from(item: Item) -> Member {
+        Member::Item(item)
+    }
+}
+
+impl From<InnerList> for Member {
+    fn from(list: InnerList) -> Member {
+        Member::InnerList(list)
+    }
+}
+
+impl Display for Member {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Member::Item(ref item) => Display::fmt(item, f),
+            Member::InnerList(ref list) => Display::fmt(list, f),
+        }
+    }
+}
+
+/// A List of members.
+///
+/// An empty list serializes as the empty string; such a field should not be
+/// sent.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct List(pub Vec<Member>);
+
+impl Deref for List {
+    type Target = Vec<Member>;
+
+    fn deref(&self) -> &Vec<Member> {
+        &self.0
+    }
+}
+
+impl DerefMut for List {
+    fn deref_mut(&mut self) -> &mut Vec<Member> {
+        &mut self.0
+    }
+}
+
+impl Display for List {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, member) in self.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            Display::fmt(member, f)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for List {
+    type Err = ::Error;
+
+    fn from_str(s: &str) -> ::Result<List> {
+        Parser::new(s.as_bytes()).parse_top(Parser::parse_list)
+    }
+}
+
+/// A Dictionary: ordered, unique keys with member values.
+///
+/// An empty dictionary serializes as the empty string; such a field should
+/// not be sent.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct Dictionary(Vec<(String, Member)>);
+
+impl Dictionary {
+    /// Create an empty dictionary.
+    pub fn new() -> Dictionary {
+        Dictionary(Vec::new())
+    }
+
+    /// Return the member with the given key.
+    pub fn get(&self, key: &str) -> Option<&Member> {
+        self.0.iter().find(|m| m.0 == key).map(|m| &m.1)
+    }
+
+    /// Add a member, or replace the value of an existing member in place.
+    pub fn insert<K, V>(&mut self, key: K, value: V)
+        where K: Into<String>, V: Into<Member>
+    {
+        insert(&mut self.0, key.into(), value.into());
+    }
+
+    /// Remove and return the member with the given key.
+    pub fn remove(&mut self, key: &str) -> Option<Member> {
+        let pos = self.0.iter().position(|m| m.0 == key)?;
+        Some(self.0.remove(pos).1)
+    }
+
+    /// Iterate over the (key, member) pairs, in order.
+    pub fn iter(&self) -> ::std::slice::Iter<'_, (String, Member)> {
+        self.0.iter()
+    }
+
+    /// Return the number of members.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Return true if there are no members.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Display for Dictionary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, (key, member)) in self.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            fmt_key(f, key)?;
+            match *member {
+                Member::Item(ref item) if item.bare_item == BareItem::Boolean(true) => {
+                    Display::fmt(&item.params, f)?;
+                }
+                _ => {
+                    f.write_char('=')?;
+                    Display::fmt(member, f)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Dictionary {
+    type Err = ::Error;
+
+    fn from_str(s: &str) -> ::Result<Dictionary> {
+        Parser::new(s.as_bytes()).parse_top(Parser::parse_dictionary)
+    }
+}
+
+/// Parse all values (lines) of a header field as a structured Item.
+///
+/// Multiple lines are combined with commas, as for any field, which an Item
+/// does not allow.
+pub fn parse_item<'a, T>(raw: &'a T) -> ::Result<Item>
+    where T: RawLike<'a>
+{
+    if raw.len() == 0 {
+        return Err(::Error::Header);
+    }
+    Parser::new(&combine_lines(raw)).parse_top(Parser::parse_item)
+}
+
+/// Parse all values (lines) of a header field as a structured List,
+/// combining lines in order.
+pub fn parse_list<'a, T>(raw: &'a T) -> ::Result<List>
+    where T: RawLike<'a>
+{
+    Parser::new(&combine_lines(raw)).parse_top(Parser::parse_list)
+}
+
+/// Parse all values (lines) of a header field as a structured Dictionary,
+/// combining lines in order.
+pub fn parse_dictionary<'a, T>(raw: &'a T) -> ::Result<Dictionary>
+    where T: RawLike<'a>
+{
+    Parser::new(&combine_lines(raw)).parse_top(Parser::parse_dictionary)
+}
+
+const MAX_INTEGER: i64 = 999_999_999_999_999;
+const MAX_DECIMAL: i64 = 999_999_999_999_999;
+
+fn combine_lines<'a, T>(raw: &'a T) -> Cow<'a, [u8]>
+    where T: RawLike<'a>
+{
+    if let Some(line) = raw.one() {
+        return Cow::Borrowed(line);
+    }
+    let mut combined = Vec::new();
+    for (i, line) in raw.iter().enumerate() {
+        if i > 0 {
+            combined.extend_from_slice(b", ");
+        }
+        combined.extend_from_slice(line);
+    }
+    Cow::Owned(combined)
+}
+
+fn insert<V>(entries: &mut Vec<(String, V)>, key: String, value: V) {
+    match entries.iter_mut().find(|e| e.0 == key) {
+        Some(entry) => entry.1 = value,
+        None => entries.push((key, value)),
+    }
+}
+
+fn fmt_integer(f: &mut fmt::Formatter, i: i64) -> fmt::Result {
+    if i.checked_abs().map_or(true, |a| a > MAX_INTEGER) {
+        return Err(fmt::Error);
+    }
+    write!(f, "{}", i)
+}
+
+fn fmt_key(f: &mut fmt::Formatter, key: &str) -> fmt::Result {
+    let mut bytes = key.bytes();
+    match bytes.next() {
+        Some(b) if is_key_start(b) => (),
+        _ => return Err(fmt::Error),
+    }
+    if !bytes.all(is_key_char) {
+        return Err(fmt::Error);
+    }
+    f.write_str(key)
+}
+
+fn is_key_start(b: u8) -> bool {
+    b.is_ascii_lowercase() || b == b'*'
+}
+
+fn is_key_char(b: u8) -> bool {
+    matches!(b, b'a'..=b'z' | b'0'..=b'9' | b'_' | b'-' | b'.' | b'*')
+}
+
+fn is_tchar(b: u8) -> bool {
+    matches!(b,
+        b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' |
+        b'!' | b'#' | b'$' | b'%' | b'&' | b'\'' | b'*' |
+        b'+' | b'-' | b'.' | b'^' | b'_' | b'`' | b'|' | b'~')
+}
+
+// A parser over the input bytes, following the algorithms of RFC 9651,
+// section 4.2.
+struct Parser<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a [u8]) -> Parser<'a> {
+        Parser { input, pos: 0 }
+    }
+
+    fn parse_top<T, F>(mut self, parse: F) -> ::Result<T>
+        where F: FnOnce(&mut Parser<'a>) -> ::Result<T>
+    {
+        self.skip_sp();
+        let value = parse(&mut self)?;
+        self.skip_sp();
+        if self.is_empty() {
+            Ok(value)
+        } else {
+            Err(::Error::Header)
+        }
+    }
+
+    fn parse_list(&mut self) -> ::Result<List> {
+        let mut members = Vec::new();
+        while !self.is_empty() {
+            members.push(self.parse_member()?);
+            self.skip_ows();
+            if self.is_empty() {
+                break;
+            }
+            self.expect(b',')?;
+            self.skip_ows();
+            if self.is_empty() {
+                return Err(::Error::Header);
+            }
+        }
+        Ok(List(members))
+    }
+
+    fn parse_dictionary(&mut self) -> ::Result<Dictionary> {
+        let mut dict = Dictionary::new();
+        while !self.is_empty() {
+            let key = self.parse_key()?;
+            let member = if self.eat(b'=') {
+                self.parse_member()?
+            } else {
+                Member::Item(Item {
+                    bare_item: BareItem::Boolean(true),
+                    params: self.parse_parameters()?,
+                })
+            };
+            insert(&mut dict.0, key, member);
+            self.skip_ows();
+            if self.is_empty() {
+                break;
+            }
+            self.expect(b',')?;
+            self.skip_ows();
+            if self.is_empty() {
+                return Err(::Error::Header);
+            }
+        }
+        Ok(dict)
+    }
+
+    fn parse_member(&mut self) -> ::Result<Member> {
+        if self.peek() == Some(b'(') {
+            self.parse_inner_list().map(Member::InnerList)
+        } else {
+            self.parse_item().map(Member::Item)
+        }
+    }
+
+    fn parse_inner_list(&mut self) -> ::Result<InnerList> {
+        self.expect(b'(')?;
+        let mut items = Vec::new();
+        loop {
+            self.skip_sp();
+            if self.eat(b')') {
+                return Ok(InnerList {
+                    items,
+                    params: self.parse_parameters()?,
+                });
+            }
+            items.push(self.parse_item()?);
+            match self.peek() {
+                Some(b' ') | Some(b')') => (),
+                _ => return Err(::Error::Header),
+            }
+        }
+    }
+
+    fn parse_item(&mut self) -> ::Result<Item> {
+        Ok(Item {
+            bare_item: self.parse_bare_item()?,
+            params: self.parse_parameters()?,
+        })
+    }
+
+    fn parse_bare_item(&mut self) -> ::Result<BareItem> {
+        match self.peek() {
+            Some(b'-') => self.parse_number(),
+            Some(b) if b.is_ascii_digit() => self.parse_number(),
+            Some(b'"') => self.parse_string().map(BareItem::String),
+            Some(b) if b.is_ascii_alphabetic() || b == b'*' => {
+                Ok(BareItem::Token(self.parse_token()))
+            }
+            Some(b':') => self.parse_byte_sequence().map(BareItem::ByteSequence),
+            Some(b'?') => self.parse_boolean().map(BareItem::Boolean),
+            Some(b'@') => {
+                self.pos += 1;
+                match self.parse_number()? {
+                    BareItem::Integer(i) => Ok(BareItem::Date(i)),
+                    _ => Err(::Error::Header),
+                }
+            }
+            Some(b'%') => self.parse_display_string().map(BareItem::DisplayString),
+            _ => Err(::Error::Header),
+        }
+    }
+
+    fn parse_parameters(&mut self) -> ::Result<Parameters> {
+        let mut params = Parameters::new();
+        while self.eat(b';') {
+            self.skip_sp();
+            let key = self.parse_key()?;
+            let value = if self.eat(b'=') {
+                self.parse_bare_item()?
+            } else {
+                BareItem::Boolean(true)
+            };
+            insert(&mut params.0, key, value);
+        }
+        Ok(params)
+    }
+
+    fn parse_key(&mut self) -> ::Result<String> {
+        let start = self.pos;
+        match self.peek() {
+            Some(b) if is_key_start(b) => self.pos += 1,
+            _ => return Err(::Error::Header),
+        }
+        while self.peek().map_or(false, is_key_char) {
+            self.pos += 1;
+        }
+        Ok(self.slice_str(start))
+    }
+
+    fn parse_number(&mut self) -> ::Result<BareItem> {
+        let negative = self.eat(b'-');
+        let start = self.pos;
+        if !self.peek().map_or(false, |b| b.is_ascii_digit()) {
+            return Err(::Error::Header);
+        }
+        let mut dot = None;
+        while let Some(b) = self.peek() {
+            if b.is_ascii_digit() {
+                self.pos += 1;
+            } else if b == b'.' && dot.is_none() {
+                if self.pos - start > 12 {
+                    return Err(::Error::Header);
+                }
+                dot = Some(self.pos);
+                self.pos += 1;
+            } else {
+                break;
+            }
+            let len = self.pos - start;
+            if (dot.is_none() && len > 15) || len > 16 {
+                return Err(::Error::Header);
+            }
+        }
+        let sign = if negative { -1 } else { 1 };
+        match dot {
+            None => {
+                let i: i64 = self.slice_str(start).parse().map_err(|_| ::Error::Header)?;
+                Ok(BareItem::Integer(sign * i))
+            }
+            Some(dot) => {
+                let frac = &self.input[dot + 1..self.pos];
+                if frac.is_empty() || frac.len() > 3 {
+                    return Err(::Error::Header);
+                }
+                let int: i64 = str::from_utf8(&self.input[start..dot]).unwrap()
+                    .parse().map_err(|_| ::Error::Header)?;
+                let frac = frac.iter().chain(b"00".iter()).take(3)
+                    .fold(0, |acc, &b| acc * 10 + i64::from(b - b'0'));
+                Ok(BareItem::Decimal(Decimal(sign * (int * 1000 + frac))))
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> ::Result<String> {
+        self.expect(b'"')?;
+        let mut out = String::new();
+        loop {
+            match self.next() {
+                Some(b'\\') => match self.next() {
+                    Some(b) if b == b'"' || b == b'\\' => out.push(b as char),
+                    _ => return Err(::Error::Header),
+                },
+                Some(b'"') => return Ok(out),
+                Some(b) if (b' '..=b'~').contains(&b) => out.push(b as char),
+                _ => return Err(::Error::Header),
+            }
+        }
+    }
+
+    fn parse_token(&mut self) -> String {
+        let start = self.pos;
+        self.pos += 1;
+        while self.peek().map_or(false, |b| is_tchar(b) || b == b':' || b == b'/') {
+            self.pos += 1;
+        }
+        self.slice_str(start)
+    }
+
+    fn parse_byte_sequence(&mut self) -> ::Result<Vec<u8>> {
+        self.expect(b':')?;
+        let start = self.pos;
+        while self.peek().map_or(false, |b| b.is_ascii_alphanumeric() || b"+/=".contains(&b)) {
+            self.pos += 1;
+        }
+        let encoded = &self.input[start..self.pos];
+        self.expect(b':')?;
+        base64::decode(encoded).map_err(|_| ::Error::Header)
+    }
+
+    fn parse_boolean(&mut self) -> ::Result<bool> {
+        self.expect(b'?')?;
+        match self.next() {
+            Some(b'1') => Ok(true),
+            Some(b'0') => Ok(false),
+            _ => Err(::Error::Header),
+        }
+    }
+
+    fn parse_display_string(&mut self) -> ::Result<String> {
+        self.expect(b'%')?;
+        self.expect(b'"')?;
+        let mut bytes = Vec::new();
+        loop {
+            match self.next() {
+                Some(b'%') => {
+                    let hi = self.next().and_then(lower_hex).ok_or(::Error::Header)?;
+                    let lo = self.next().and_then(lower_hex).ok_or(::Error::Header)?;
+                    bytes.push(hi << 4 | lo);
+                }
+                Some(b'"') => return String::from_utf8(bytes).map_err(|_| ::Error::Header),
+                Some(b) if (b' '..=b'~').contains(&b) => bytes.push(b),
+                _ => return Err(::Error::Header),
+            }
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.pos).cloned()
+    }
+
+    fn next(&mut self) -> Option<u8> {
+        let b = self.peek();
+        if b.is_some() {
+            self.pos += 1;
+        }
+        b
+    }
+
+    fn eat(&mut self, b: u8) -> bool {
+        if self.peek() == Some(b) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, b: u8) -> ::Result<()> {
+        if self.eat(b) {
+            Ok(())
+        } else {
+            Err(::Error::Header)
+        }
+    }
+
+    fn skip_sp(&mut self) {
+        while self.eat(b' ') {}
+    }
+
+    fn skip_ows(&mut self) {
+        while self.eat(b' ') || self.eat(b'\t') {}
+    }
+
+    fn is_empty(&self) -> bool {
+        self.pos >= self.input.len()
+    }
+
+    // Only called for ranges of already validated ASCII bytes.
+    fn slice_str(&self, start: usize) -> String {
+        str::from_utf8(&self.input[start..self.pos]).unwrap().to_owned()
+    }
+}
+
+fn lower_hex(b: u8) -> Option<u8> {
+    match b {
+        b'0'..=b'9' => Some(b - b'0'),
+        b'a'..=b'f' => Some(b - b'a' + 10),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use header::Raw;
+    use super::*;
+
+    fn round_trip<T>(s: &str, canonical: &str)
+        where T: FromStr<Err = ::Error> + Display + fmt::Debug
+    {
+        let value: T = s.parse().unwrap_or_else(|_| panic!("parse {:?}", s));
+        assert_eq!(value.to_string(), canonical, "from {:?}", s);
+    }
+
+    #[test]
+    fn test_item_bare_types() {
+        assert_eq!("42".parse::<Item>().unwrap(), Item::new(42));
+        assert_eq!("-42".parse::<Item>().unwrap(), Item::new(-42));
+        assert_eq!("4.5".parse::<Item>().unwrap(),
+                   Item::new(Decimal::from_thousandths(4500)));
+        assert_eq!("-0.001".parse::<Item>().unwrap(),
+                   Item::new(Decimal::from_thousandths(-1)));
+        assert_eq!("\"hello \\\"world\\\"\"".parse::<Item>().unwrap(),
+                   Item::new("hello \"world\""));
+        assert_eq!("*foo/bar:baz".parse::<Item>().unwrap(),
+                   Item::new(BareItem::Token("*foo/bar:baz".to_owned())));
+        assert_eq!(":cHJldGVuZCB0aGlzIGlzIGJpbmFyeSBjb250ZW50Lg==:".parse::<Item>().unwrap(),
+                   Item::new(b"pretend this is binary content.".to_vec()));
+        assert_eq!("?1".parse::<Item>().unwrap(), Item::new(true));
+        assert_eq!("?0".parse::<Item>().unwrap(), Item::new(false));
+        assert_eq!("@1659578233".parse::<Item>().unwrap(),
+                   Item::new(BareItem::Date(1659578233)));
+        assert_eq!("%\"This is intended for display to %c3%bcsers.\"".parse::<Item>().unwrap(),
+                   Item::new(BareItem::DisplayString(
+                       "This is intended for display to \u{fc}sers.".to_owned())));
+    }
+
+    #[test]
+    fn test_item_canonical() {
+        round_trip::<Item>("  42  ", "42");
+        round_trip::<Item>("1.50", "1.5");
+        round_trip::<Item>("-1.0", "-1.0");
+        round_trip::<Item>("0.100", "0.1");
+        round_trip::<Item>("abc;a=1;b; c=?0", "abc;a=1;b;c=?0");
+        round_trip::<Item>("1;a=1;a=2;b", "1;a=2;b");
+        round_trip::<Item>("%\"50%25 off\"", "%\"50%25 off\"");
+        round_trip::<Item>("@-62135596800", "@-62135596800");
+    }
+
+    #[test]
+    fn test_item_invalid() {
+        for bad in &[
+            "",
+            "1 2",
+            "1234567890123456",
+            "1234567890123.0",
+            "1.",
+            "1.1234",
+            "-",
+            "\"unterminated",
+            "\"bad \\escape\"",
+            "\"\u{e9}\"",
+            ":bad base64!:",
+            "?2",
+            "@1.5",
+            "%\"%C3%BC\"",
+            "%\"%ff\"",
+            "%\"50%\"",
+            "a;A=1",
+            "1,",
+        ] {
+            assert!(bad.parse::<Item>().is_err(), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn test_list() {
+        let list: List = "sugar, tea, rum".parse().unwrap();
+        assert_eq!(list.len(), 3);
+        assert_eq!(list[1], Member::Item(Item::new(BareItem::Token("tea".to_owned()))));
+
+        // From RFC 9651, section 3.1.1
+        let list: List = "(\"foo\" \"bar\"), (\"baz\"), (\"bat\" \"one\"), ()".parse().unwrap();
+        assert_eq!(list.len(), 4);
+        assert_eq!(list[3], Member::InnerList(InnerList::new(vec![])));
+        assert_eq!(list[0].as_inner_list().unwrap().items,
+                   vec![Item::new("foo"), Item::new("bar")]);
+
+        round_trip::<List>("(  \"foo\"   \"bar\" );lvl=5, (\"bar\" \"baz\");lvl=1",
+                           "(\"foo\" \"bar\");lvl=5, (\"bar\" \"baz\");lvl=1");
+        round_trip::<List>("abc;a=1;b=2; cde_456, (ghi;jk=4 l);q=\"9\";r=w",
+                           "abc;a=1;b=2;cde_456, (ghi;jk=4 l);q=\"9\";r=w");
+        round_trip::<List>("a,\tb ,c", "a, b, c");
+        round_trip::<List>("", "");
+    }
+
+    #[test]
+    fn test_list_invalid() {
+        for bad in &["a,", ",a", "a,,b", "(a b", "(a,b)", "(a)b", "a b"] {
+            assert!(bad.parse::<List>().is_err(), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn test_dictionary() {
+        // From RFC 9651, section 3.2
+        let dict: Dictionary = "en=\"Applepie\", da=:w4ZibGV0w6ZydGU=:".parse().unwrap();
+        assert_eq!(dict.get("en"), Some(&Member::Item(Item::new("Applepie"))));
+        assert_eq!(dict.get("da").and_then(|m| m.as_item())
+                       .and_then(|i| i.bare_item.as_byte_sequence()),
+                   Some(&b"\xc3\x86blet\xc3\xa6rte"[..]));
+
+        round_trip::<Dictionary>("a=?0, b, c; foo=bar", "a=?0, b, c;foo=bar");
+        round_trip::<Dictionary>("rating=1.5, feelings=(joy sadness)",
+                                 "rating=1.5, feelings=(joy sadness)");
+        round_trip::<Dictionary>("a=(1 2), b=3, c=4;aa=bb, d=(5 6);valid",
+                                 "a=(1 2), b=3, c=4;aa=bb, d=(5 6);valid");
+        round_trip::<Dictionary>("a=1, b=2, a=3", "a=3, b=2");
+        round_trip::<Dictionary>("a=?1;x", "a;x");
+    }
+
+    #[test]
+    fn test_dictionary_edit() {
+        let mut dict = Dictionary::new();
+        dict.insert("u", Item::new(3));
+        dict.insert("i", Item::new(true));
+        dict.insert("u", Item::new(1));
+        dict.insert("list", InnerList::new(vec![Item::new(1), Item::new(2)])
+                    .with_param("k", BareItem::Token("v".to_owned())));
+        assert_eq!(dict.to_string(), "u=1, i, list=(1 2);k=v");
+        assert_eq!(dict.remove("i"), Some(Member::Item(Item::new(true))));
+        assert_eq!(dict.len(), 2);
+    }
+
+    #[test]
+    fn test_dictionary_invalid() {
+        for bad in &["a=1,", "A=1", "a=", "a=1 b=2", "1=a"] {
+            assert!(bad.parse::<Dictionary>().is_err(), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn test_raw_lines() {
+        let r: Raw = vec![b"a=1, b=2".to_vec(), b"c=3".to_vec()].into();
+        let dict = parse_dictionary(&r).unwrap();
+        assert_eq!(dict.to_string(), "a=1, b=2, c=3");
+
+        let r: Raw = vec![b"1".to_vec(), b"(2 3)".to_vec()].into();
+        assert_eq!(parse_list(&r).unwrap().to_string(), "1, (2 3)");
+
+        assert!(parse_item(&r).is_err());
+        let r: Raw = "\"a\";b".into();
+        assert_eq!(parse_item(&r).unwrap(), Item::new("a").with_param("b", true));
+
+        let r: Raw = Vec::<Vec<u8>>::new().into();
+        assert!(parse_list(&r).unwrap().is_empty());
+        assert!(parse_item(&r).is_err());
+    }
+
+    #[test]
+    fn test_serialize_invalid() {
+        use std::fmt::Write;
+        for item in vec![
+            Item::new(1_000_000_000_000_000),
+            Item::new(Decimal::from_thousandths(1_000_000_000_000_000)),
+            Item::new(i64::MIN),
+            Item::new(Decimal::from_thousandths(i64::MIN)),
+            Item::new("\u{e9}"),
+            Item::new("\n"),
+            Item::new(BareItem::Token("1a".to_owned())),
+            Item::new(BareItem::Token("a b".to_owned())),
+            Item::new(1).with_param("A", 1),
+        ] {
+            let mut s = String::new();
+            assert!(write!(s, "{}", item).is_err(), "{:?}", item);
+        }
+    }
+
+    #[test]
+    fn test_serialize_display_string() {
+        let item = Item::new(BareItem::DisplayString("f\u{fc}\"r".to_owned()));
+        assert_eq!(item.to_string(), "%\"f%c3%bc%22r\"");
+        assert_eq!(item.to_string().parse::<Item>().unwrap(), item);
+    }
+
+    #[test]
+    fn test_decimal() {
+        assert_eq!(Decimal::from_f64(0.0625), Some(Decimal::from_thousandths(62)));
+        assert_eq!(Decimal::from_f64(0.1875), Some(Decimal::from_thousandths(188)));
+        assert_eq!(Decimal::from_f64(-1.23456), Some(Decimal::from_thousandths(-1235)));
+        assert_eq!(Decimal::from_f64(f64::NAN), None);
+        assert_eq!(Decimal::from_f64(1e13), None);
+        assert_eq!(Decimal::from_thousandths(-500).to_string(), "-0.5");
+        assert_eq!(Decimal::from_thousandths(2500).as_f64(), 2.5);
+    }
+}
@@ -0,0 +1,235 @@
+//! Validation of the trailer fields of a chunked message, as specified in
+//! [RFC 9110](https://www.rfc-editor.org/rfc/rfc9110#section-6.5).
+//!
+//! Trailer fields are sent after the content, so many fields cannot safely
+//! be sent as trailers: those needed for framing, routing, authentication,
+//! request modifiers, response control or processing the content. A
+//! recipient which merged such a trailer into the header section could be
+//! misled. `validate` checks the trailer fields of a message against these
+//! restrictions, against the fields declared by its `Trailer` header, and
+//! against whether the `TE` of the request accepts trailers.
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate http;
+//! # extern crate hyperx;
+//! use hyperx::header::{Encoding, Te, Trailer, qitem};
+//! use hyperx::header::trailers::{validate, TrailerError};
+//!
+//! # fn main() {
+//! let te = Te(vec![qitem(Encoding::Trailers)]);
+//! let declared = Trailer(vec!["grpc-status".parse().unwrap()]);
+//!
+//! let mut trailers = http::HeaderMap::new();
+//! trailers.insert("grpc-status", "0".parse().unwrap());
+//! assert_eq!(validate(Some(&declared), Some(&te), &trailers), Ok(()));
+//!
+//! trailers.insert("content-length", "0".parse().unwrap());
+//! assert_eq!(
+//!     validate(Some(&declared), Some(&te), &trailers),
+//!     Err(TrailerError::Forbidden(http::header::CONTENT_LENGTH)));
+//! # }
+//! ```
+
+use std::error::Error as StdError;
+use std::fmt;
+
+use http::header::{self, HeaderMap, HeaderName};
+
+use header::{q, Encoding, Te, Trailer};
+
+// Fields which must not be sent as trailers, from RFC 9110, section 6.5.1
+// and RFC 7230, section 4.1.2.
+const FORBIDDEN: &[HeaderName] = &[
+    // Message framing
+    header::CONTENT_LENGTH,
+    header::TRANSFER_ENCODING,
+    header::TRAILER,
+    // Routing
+    header::HOST,
+    // Request modifiers
+    header::CACHE_CONTROL,
+    header::EXPECT,
+    header::IF_MATCH,
+    header::IF_MODIFIED_SINCE,
+    header::IF_NONE_MATCH,
+    header::IF_RANGE,
+    header::IF_UNMODIFIED_SINCE,
+    header::MAX_FORWARDS,
+    header::PRAGMA,
+    header::RANGE,
+    header::TE,
+    // Authentication
+    header::AUTHORIZATION,
+    header::COOKIE,
+    header::PROXY_AUTHENTICATE,
+    header::PROXY_AUTHORIZATION,
+    header::SET_COOKIE,
+    header::WWW_AUTHENTICATE,
+    // Response control
+    header::AGE,
+    header::DATE,
+    header::EXPIRES,
+    header::LOCATION,
+    header::RETRY_AFTER,
+    header::VARY,
+    header::WARNING,
+    // Content processing
+    header::CONTENT_ENCODING,
+    header::CONTENT_RANGE,
+    header::CONTENT_TYPE,
+];
+
+/// A reason for rejecting the trailer fields of a message.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum TrailerError {
+    /// The request `TE` does not accept trailers, but trailer fields are
+    /// present.
+    NotAccepted,
+    /// A field which must not be sent as a trailer, declared or present.
+    Forbidden(HeaderName),
+    /// A trailer field which is not declared by the `Trailer` header.
+    Undeclared(HeaderName),
+}
+
+impl fmt::Display for TrailerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TrailerError::NotAccepted => f.write_str("trailers not accepted by TE"),
+            TrailerError::Forbidden(ref name) => write!(f, "forbidden trailer field: {}", name),
+            TrailerError::Undeclared(ref name) => write!(f, "undeclared trailer field: {}", name),
+        }
+    }
+}
+
+impl StdError for TrailerError {}
+
+/// Return true if the field must not be sent as a trailer.
+pub fn is_forbidden(name: &HeaderName) -> bool {
+    FORBIDDEN.contains(name)
+}
+
+/// Return true if the `TE` header of a request accepts trailers in the
+/// response, with `trailers` at a non-zero quality.
+pub fn accepts_trailers(te: Option<&Te>) -> bool {
+    te.map_or(false, |te| {
+        te.iter().any(|item| item.item == Encoding::Trailers && item.quality > q(0))
+    })
+}
+
+/// Check the trailer fields of a message against the declared `Trailer`
+/// header, without regard to `TE`.
+///
+/// Without a `Trailer` header, no trailer field is declared. A forbidden
+/// field is rejected whether declared or present.
+pub fn check_fields(declared: Option<&Trailer>, trailers: &HeaderMap)
+    -> Result<(), TrailerError>
+{
+    let declared = declared.map_or(&[][..], |trailer| &trailer[..]);
+    for name in declared {
+        if let Some(forbidden) = FORBIDDEN.iter().find(|f| name.eq_ignore_ascii_case(f.as_str())) {
+            return Err(TrailerError::Forbidden(forbidden.clone()));
+        }
+    }
+    for name in trailers.keys() {
+        if is_forbidden(name) {
+            return Err(TrailerError::Forbidden(name.clone()));
+        }
+        if !declared.iter().any(|d| d.eq_ignore_ascii_case(name.as_str())) {
+            return Err(TrailerError::Undeclared(name.clone()));
+        }
+    }
+    Ok(())
+}
+
+/// Validate the trailer fields of a message, given its declared `Trailer`
+/// header and the `TE` header of the request.
+///
+/// The fields are checked as by `check_fields`, then any trailer fields
+/// present must be accepted by `TE`. For the trailers of a request, which
+/// are not subject to `TE`, use `check_fields` instead.
+pub fn validate(declared: Option<&Trailer>, te: Option<&Te>, trailers: &HeaderMap)
+    -> Result<(), TrailerError>
+{
+    check_fields(declared, trailers)?;
+    if !trailers.is_empty() && !accepts_trailers(te) {
+        return Err(TrailerError::NotAccepted);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use http::header::{HeaderMap, AUTHORIZATION, CONTENT_LENGTH, HOST};
+
+    use header::{q, qitem, Encoding, QualityItem, Te, Trailer};
+    use super::*;
+
+    fn trailer(names: &[&str]) -> Trailer {
+        Trailer(names.iter().map(|n| n.parse().unwrap()).collect())
+    }
+
+    fn te_trailers() -> Te {
+        Te(vec![qitem(Encoding::Trailers)])
+    }
+
+    #[test]
+    fn test_accepts_trailers() {
+        assert!(!accepts_trailers(None));
+        assert!(!accepts_trailers(Some(&Te(vec![]))));
+        assert!(!accepts_trailers(Some(&Te(vec![qitem(Encoding::Gzip)]))));
+        assert!(!accepts_trailers(Some(&Te(vec![QualityItem::new(Encoding::Trailers, q(0))]))));
+        assert!(accepts_trailers(Some(&Te(vec![
+            qitem(Encoding::Gzip),
+            QualityItem::new(Encoding::Trailers, q(500)),
+        ]))));
+    }
+
+    #[test]
+    fn test_is_forbidden() {
+        assert!(is_forbidden(&CONTENT_LENGTH));
+        assert!(is_forbidden(&HOST));
+        assert!(is_forbidden(&AUTHORIZATION));
+        assert!(!is_forbidden(&"grpc-status".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_validate() {
+        let declared = trailer(&["Grpc-Status", "grpc-message"]);
+        let te = te_trailers();
+
+        let mut trailers = HeaderMap::new();
+        assert_eq!(validate(None, None, &trailers), Ok(()));
+        assert_eq!(validate(Some(&declared), None, &trailers), Ok(()));
+
+        trailers.insert("grpc-status", "0".parse().unwrap());
+        assert_eq!(validate(Some(&declared), Some(&te), &trailers), Ok(()));
+        assert_eq!(validate(Some(&declared), None, &trailers), Err(TrailerError::NotAccepted));
+        assert_eq!(
+            validate(None, Some(&te), &trailers),
+            Err(TrailerError::Undeclared("grpc-status".parse().unwrap())));
+
+        trailers.insert("x-extra", "1".parse().unwrap());
+        assert_eq!(
+            validate(Some(&declared), Some(&te), &trailers),
+            Err(TrailerError::Undeclared("x-extra".parse().unwrap())));
+
+        let mut trailers = HeaderMap::new();
+        trailers.insert(AUTHORIZATION, "Bearer x".parse().unwrap());
+        assert_eq!(
+            validate(Some(&trailer(&["authorization"])), Some(&te), &trailers),
+            Err(TrailerError::Forbidden(AUTHORIZATION)));
+    }
+
+    #[test]
+    fn test_check_fields_declared_forbidden() {
+        let trailers = HeaderMap::new();
+        assert_eq!(
+            check_fields(Some(&trailer(&["grpc-status", "Content-Length"])), &trailers),
+            Err(TrailerError::Forbidden(CONTENT_LENGTH)));
+        assert_eq!(
+            TrailerError::Forbidden(CONTENT_LENGTH).to_string(),
+            "forbidden trailer field: content-length");
+    }
+}
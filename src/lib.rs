@@ -17,15 +17,19 @@ extern crate base64;
 extern crate bytes;
 extern crate http;
 extern crate language_tags;
+#[cfg(feature = "digest")]
+extern crate md5;
 pub extern crate mime;
 extern crate percent_encoding;
+#[cfg(feature = "digest")]
+extern crate sha2;
 extern crate httpdate;
 extern crate unicase;
 
 #[cfg(all(test, feature = "nightly"))]
 extern crate test;
 
-pub use error::{Result, Error};
+pub use error::{Result, Error, HeaderError, HeaderErrorKind};
 
 #[cfg(feature = "headers")]
 pub use header::Headers;